@@ -6,13 +6,81 @@ pub struct VpnDto {
     pub display_name: String,
     pub config_path: String,
     pub status: VpnStatusDto,
+    #[serde(default)]
+    pub metadata: ProfileMetadataDto,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileMetadataDto {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub favorite: bool,
+    /// RFC 3339 timestamp of the last successful connection.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_used: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connect_timeout_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reconnect: Option<RetryPolicyDto>,
+    /// Other tunnels are stopped when this profile connects, and this one
+    /// when another connects.
+    #[serde(default)]
+    pub exclusive: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetryPolicyDto {
+    pub enabled: bool,
+    /// Absent: retry until disconnected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_attempts: Option<u32>,
+    pub initial_delay_secs: u64,
+    pub max_delay_secs: u64,
+    pub jitter_percent: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VpnStatusDto {
     pub state: ConnectionStateDto,
     pub ip_address: String,
-    pub connected_since: Option<String>, // ISO 8601 timestamp
+    /// RFC 3339, e.g. `2024-05-01T10:00:00Z`.
+    pub connected_since: Option<String>,
+    /// Detailed tunnel phase (`resolving`, `auth`, `get_config`...) when known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phase: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ipv6_address: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_address: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_port: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interface: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gateway: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub traffic: Option<TrafficDto>,
+    /// Seconds since the tunnel came up, while connected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uptime_seconds: Option<u64>,
+    /// Automatic reconnect attempt in progress (or made before giving up).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reconnect_attempt: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrafficDto {
+    pub bytes_in: u64,
+    pub bytes_out: u64,
+    /// Bytes per second; absent until two samples were taken.
+    pub rate_in: Option<u64>,
+    pub rate_out: Option<u64>,
+    /// RFC 3339 time the counters were read.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sampled_at: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,4 +113,112 @@ pub struct VpnConnectionResponse {
     pub success: bool,
     pub message: String,
     pub vpn: Option<VpnDto>,
-}
\ No newline at end of file
+    /// OpenVPN processes a disconnect stopped.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stopped: Vec<StoppedInstanceDto>,
+    /// Recognized cause of a failed connect.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure: Option<FailureDto>,
+    /// What kind of error a failed command ran into.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorDto>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorDto {
+    /// `not_found`, `connection_failed`, `openvpn_error`, `invalid_profile`,
+    /// `already_exists`, `in_use`, `invalid_input` or `repository_error`;
+    /// `connect` without `--force` also reports `already_connected`.
+    pub kind: String,
+    /// The profile id or message the error carries.
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailureDto {
+    /// `auth_failed`, `tls_handshake_failed`, `certificate_verify_failed`,
+    /// `host_unresolved`, `tun_permission_denied` or `cipher_negotiation_failed`.
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Advice for the user; informative only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoppedInstanceDto {
+    pub pid: u32,
+    /// `management`, `sigterm` or `sigkill`.
+    pub step: String,
+}
+
+/// A password question the daemon passes on to the client that asked to connect.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CredentialRequestDto {
+    pub vpn_id: String,
+    pub vpn_name: String,
+    pub kind: CredentialKindDto,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub retry: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CredentialKindDto {
+    UsernamePassword {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        challenge: Option<ChallengeDto>,
+    },
+    Password { realm: String },
+    ChallengeResponse { challenge: ChallengeDto },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChallengeDto {
+    pub text: String,
+    pub echo: bool,
+}
+
+/// No `Debug`: it carries the password.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CredentialReplyDto {
+    pub username: String,
+    pub password: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<String>,
+    #[serde(default)]
+    pub remember: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ValidationResponse {
+    /// Profiles without errors; under `--strict`, without warnings either.
+    pub valid: usize,
+    pub total: usize,
+    pub reports: Vec<ValidationReportDto>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationReportDto {
+    /// VPN ID or path as given on the command line.
+    pub name: String,
+    pub file: String,
+    pub errors: usize,
+    pub warnings: usize,
+    pub findings: Vec<FindingDto>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FindingDto {
+    /// `error`, `warning` or `info`.
+    pub severity: String,
+    pub rule: String,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
@@ -1,5 +1,16 @@
-use crate::domain::entities::{Vpn, VpnStatus, ConnectionState};
-use crate::application::dtos::{VpnDto, VpnStatusDto, ConnectionStateDto};
+use crate::domain::credentials::{Challenge, CredentialKind, CredentialReply, CredentialRequest, Credentials};
+use crate::domain::entities::{Vpn, VpnError, VpnStatus, ConnectionState, ProfileMetadata, ProfileOptions, TrafficStats};
+use crate::domain::reconnect::RetryPolicy;
+use crate::domain::state_machine::TunnelState;
+use crate::domain::validation::{Severity, ValidationReport};
+use crate::application::dtos::{
+    ChallengeDto, ConnectionStateDto, CredentialKindDto, CredentialReplyDto, CredentialRequestDto, ErrorDto, FailureDto, FindingDto,
+    ProfileMetadataDto, RetryPolicyDto, StoppedInstanceDto, TrafficDto, ValidationReportDto, VpnConnectionResponse, VpnDto,
+    VpnStatusDto,
+};
+use crate::application::services::VpnServiceError;
+use crate::infrastructure::services::{ShutdownStep, StoppedInstance};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 pub struct VpnMapper;
 
@@ -10,6 +21,7 @@ impl VpnMapper {
             display_name: vpn.display_name().to_string(),
             config_path: vpn.config_path().to_string(),
             status: Self::status_to_dto(vpn.status()),
+            metadata: Self::metadata_to_dto(vpn.metadata()),
         }
     }
 
@@ -22,6 +34,7 @@ impl VpnMapper {
         
         let status = Self::status_from_dto(&dto.status);
         vpn.update_status(status);
+        vpn.set_metadata(Self::metadata_from_dto(dto));
         vpn
     }
 
@@ -29,26 +42,97 @@ impl VpnMapper {
         vpns.iter().map(Self::to_dto).collect()
     }
 
-    fn status_to_dto(status: &VpnStatus) -> VpnStatusDto {
+    fn metadata_to_dto(metadata: &ProfileMetadata) -> ProfileMetadataDto {
+        ProfileMetadataDto {
+            description: metadata.description().map(str::to_string),
+            tags: metadata.tags().to_vec(),
+            favorite: metadata.is_favorite(),
+            last_used: metadata.last_used().map(|time| humantime::format_rfc3339_seconds(time).to_string()),
+            connect_timeout_secs: metadata.options().connect_timeout_secs,
+            reconnect: metadata.options().reconnect.as_ref().map(|policy| RetryPolicyDto {
+                enabled: policy.enabled,
+                max_attempts: policy.max_attempts,
+                initial_delay_secs: policy.initial_delay_secs,
+                max_delay_secs: policy.max_delay_secs,
+                jitter_percent: policy.jitter_percent,
+            }),
+            exclusive: metadata.options().exclusive,
+        }
+    }
+
+    /// The display name travels in `VpnDto::display_name`; it is only kept as
+    /// metadata when it differs from the id.
+    fn metadata_from_dto(dto: &VpnDto) -> ProfileMetadata {
+        let mut metadata = ProfileMetadata::default();
+        if dto.display_name != dto.id {
+            metadata.set_display_name(Some(dto.display_name.clone()));
+        }
+        metadata.set_description(dto.metadata.description.clone());
+        metadata.set_tags(dto.metadata.tags.clone());
+        metadata.set_favorite(dto.metadata.favorite);
+        metadata.set_last_used(dto.metadata.last_used.as_deref().and_then(|time| humantime::parse_rfc3339_weak(time).ok()));
+        metadata.set_options(ProfileOptions {
+            connect_timeout_secs: dto.metadata.connect_timeout_secs,
+            reconnect: dto.metadata.reconnect.as_ref().map(|policy| RetryPolicy {
+                enabled: policy.enabled,
+                max_attempts: policy.max_attempts,
+                initial_delay_secs: policy.initial_delay_secs,
+                max_delay_secs: policy.max_delay_secs,
+                jitter_percent: policy.jitter_percent,
+            }),
+            exclusive: dto.metadata.exclusive,
+        });
+        metadata
+    }
+
+    pub fn status_to_dto(status: &VpnStatus) -> VpnStatusDto {
         VpnStatusDto {
             state: Self::connection_state_to_dto(status.state()),
             ip_address: status.ip_address().to_string(),
-            connected_since: status.connected_since()
-                .map(|time| {
-                    // Convert SystemTime to ISO 8601 string
-                    match time.duration_since(std::time::UNIX_EPOCH) {
-                        Ok(duration) => {
-                            format!("{}", duration.as_secs())
-                        },
-                        Err(_) => "unknown".to_string(),
-                    }
-                }),
+            connected_since: status.connected_since().and_then(format_time),
+            phase: status.tunnel_state().map(|phase| phase.name().to_string()),
+            ipv6_address: status.ipv6_address().map(str::to_string),
+            remote_address: status.remote_address().map(str::to_string),
+            remote_port: status.remote_port(),
+            interface: status.interface().map(str::to_string),
+            gateway: status.gateway().map(str::to_string),
+            traffic: status.traffic().map(|traffic| TrafficDto {
+                bytes_in: traffic.bytes_in(),
+                bytes_out: traffic.bytes_out(),
+                rate_in: traffic.rate_in(),
+                rate_out: traffic.rate_out(),
+                sampled_at: format_time(traffic.sampled_at()),
+            }),
+            uptime_seconds: status.session_duration().map(|duration| duration.as_secs()),
+            reconnect_attempt: status.reconnect_attempt(),
         }
     }
 
-    fn status_from_dto(dto: &VpnStatusDto) -> VpnStatus {
+    /// Inverse of [`Self::status_to_dto`]. `uptime_seconds` is derived from
+    /// `connected_since`, so it is not read back.
+    pub fn status_from_dto(dto: &VpnStatusDto) -> VpnStatus {
         let state = Self::connection_state_from_dto(&dto.state);
-        VpnStatus::new(state, dto.ip_address.clone())
+        let reason = match &state {
+            ConnectionState::Error(reason) => reason.as_str(),
+            _ => "",
+        };
+        let mut status = match dto.phase.as_deref().and_then(|phase| TunnelState::from_name(phase, reason)) {
+            Some(phase) => VpnStatus::from_tunnel_state(phase, dto.ip_address.clone()),
+            None => VpnStatus::new(state, dto.ip_address.clone()),
+        };
+        status.set_ipv6_address(dto.ipv6_address.clone());
+        status.set_remote(dto.remote_address.clone(), dto.remote_port);
+        status.set_interface(dto.interface.clone());
+        status.set_gateway(dto.gateway.clone());
+        status.set_traffic(dto.traffic.as_ref().map(|traffic| {
+            let sampled_at = traffic.sampled_at.as_deref().and_then(parse_time).unwrap_or_else(SystemTime::now);
+            TrafficStats::new(traffic.bytes_in, traffic.bytes_out, sampled_at)
+                .with_rates(traffic.rate_in, traffic.rate_out)
+        }));
+        status.set_reconnect_attempt(dto.reconnect_attempt);
+        // Replaces the local clock reading taken when building a connected status.
+        status.set_connected_since(dto.connected_since.as_deref().and_then(parse_time));
+        status
     }
 
     fn connection_state_to_dto(state: &ConnectionState) -> ConnectionStateDto {
@@ -70,4 +154,223 @@ impl VpnMapper {
             ConnectionStateDto::Error(msg) => ConnectionState::Error(msg.clone()),
         }
     }
-}
\ No newline at end of file
+}
+
+/// Last second of year 9999.
+const MAX_RFC3339_SECONDS: u64 = 253_402_300_799;
+
+/// RFC 3339 with as many fractional digits as needed to read back the same
+/// instant. Times outside what RFC 3339 can express are left out.
+fn format_time(time: SystemTime) -> Option<String> {
+    let seconds = time.duration_since(UNIX_EPOCH).ok()?.as_secs();
+    (seconds <= MAX_RFC3339_SECONDS).then(|| humantime::format_rfc3339(time).to_string())
+}
+
+/// Also accepts the bare epoch seconds older versions wrote.
+fn parse_time(text: &str) -> Option<SystemTime> {
+    humantime::parse_rfc3339(text)
+        .ok()
+        .or_else(|| text.parse().ok().map(|seconds| UNIX_EPOCH + Duration::from_secs(seconds)))
+}
+
+pub struct CredentialMapper;
+
+impl CredentialMapper {
+    pub fn request_to_dto(request: &CredentialRequest) -> CredentialRequestDto {
+        let challenge_to_dto = |challenge: &Challenge| ChallengeDto {
+            text: challenge.text.clone(),
+            echo: challenge.echo,
+        };
+        CredentialRequestDto {
+            vpn_id: request.vpn_id.clone(),
+            vpn_name: request.vpn_name.clone(),
+            kind: match &request.kind {
+                CredentialKind::UsernamePassword { challenge } => CredentialKindDto::UsernamePassword {
+                    challenge: challenge.as_ref().map(challenge_to_dto),
+                },
+                CredentialKind::Password { realm } => CredentialKindDto::Password { realm: realm.clone() },
+                CredentialKind::ChallengeResponse { challenge } => CredentialKindDto::ChallengeResponse {
+                    challenge: challenge_to_dto(challenge),
+                },
+            },
+            username: request.username.clone(),
+            retry: request.retry,
+        }
+    }
+
+    pub fn request_from_dto(dto: &CredentialRequestDto) -> CredentialRequest {
+        let challenge_from_dto = |challenge: &ChallengeDto| Challenge {
+            text: challenge.text.clone(),
+            echo: challenge.echo,
+        };
+        CredentialRequest {
+            vpn_id: dto.vpn_id.clone(),
+            vpn_name: dto.vpn_name.clone(),
+            kind: match &dto.kind {
+                CredentialKindDto::UsernamePassword { challenge } => CredentialKind::UsernamePassword {
+                    challenge: challenge.as_ref().map(challenge_from_dto),
+                },
+                CredentialKindDto::Password { realm } => CredentialKind::Password { realm: realm.clone() },
+                CredentialKindDto::ChallengeResponse { challenge } => CredentialKind::ChallengeResponse {
+                    challenge: challenge_from_dto(challenge),
+                },
+            },
+            username: dto.username.clone(),
+            retry: dto.retry,
+        }
+    }
+
+    pub fn reply_to_dto(reply: &CredentialReply) -> CredentialReplyDto {
+        CredentialReplyDto {
+            username: reply.credentials.username().to_string(),
+            password: reply.credentials.password().to_string(),
+            response: reply.response.clone(),
+            remember: reply.remember,
+        }
+    }
+
+    pub fn reply_from_dto(dto: &CredentialReplyDto) -> CredentialReply {
+        CredentialReply {
+            credentials: Credentials::new(dto.username.clone(), dto.password.clone()),
+            response: dto.response.clone(),
+            remember: dto.remember,
+        }
+    }
+}
+
+pub struct ShutdownMapper;
+
+impl ShutdownMapper {
+    pub fn to_dto_list(stopped: &[StoppedInstance]) -> Vec<StoppedInstanceDto> {
+        stopped
+            .iter()
+            .map(|instance| StoppedInstanceDto { pid: instance.pid, step: instance.step.name().to_string() })
+            .collect()
+    }
+
+    /// Entries with a step this build does not know are left out.
+    pub fn from_dto_list(dtos: &[StoppedInstanceDto]) -> Vec<StoppedInstance> {
+        dtos.iter()
+            .filter_map(|dto| Some(StoppedInstance { pid: dto.pid, step: ShutdownStep::from_name(&dto.step)? }))
+            .collect()
+    }
+}
+
+pub struct ErrorMapper;
+
+impl ErrorMapper {
+    /// `None` for errors about the daemon itself, which only a client has.
+    pub fn to_dto(error: &VpnServiceError) -> Option<ErrorDto> {
+        let (kind, detail) = match error {
+            VpnServiceError::VpnNotFound(id) => ("not_found", id.clone()),
+            VpnServiceError::ConnectionFailed(message) => ("connection_failed", message.clone()),
+            VpnServiceError::Diagnosed(error) => ("connection_failed", error.to_string()),
+            VpnServiceError::OpenVpnError(message) => ("openvpn_error", message.clone()),
+            VpnServiceError::InvalidProfile(message) => ("invalid_profile", message.clone()),
+            VpnServiceError::AlreadyExists(id) => ("already_exists", id.clone()),
+            VpnServiceError::ProfileInUse(id) => ("in_use", id.clone()),
+            VpnServiceError::InvalidInput(message) => ("invalid_input", message.clone()),
+            VpnServiceError::RepositoryError(message) => ("repository_error", message.clone()),
+            VpnServiceError::Daemon(_) | VpnServiceError::DaemonUnreachable(_) => return None,
+        };
+        Some(ErrorDto { kind: kind.to_string(), detail })
+    }
+
+    /// `None` for kinds this build does not know.
+    pub fn from_dto(dto: &ErrorDto) -> Option<VpnServiceError> {
+        let detail = dto.detail.clone();
+        match dto.kind.as_str() {
+            "not_found" => Some(VpnServiceError::VpnNotFound(detail)),
+            "connection_failed" => Some(VpnServiceError::ConnectionFailed(detail)),
+            "openvpn_error" => Some(VpnServiceError::OpenVpnError(detail)),
+            "invalid_profile" => Some(VpnServiceError::InvalidProfile(detail)),
+            "already_exists" => Some(VpnServiceError::AlreadyExists(detail)),
+            "in_use" => Some(VpnServiceError::ProfileInUse(detail)),
+            "invalid_input" => Some(VpnServiceError::InvalidInput(detail)),
+            "repository_error" => Some(VpnServiceError::RepositoryError(detail)),
+            _ => None,
+        }
+    }
+}
+
+pub struct FailureMapper;
+
+impl FailureMapper {
+    /// `None` for errors that are not connection failure diagnoses.
+    pub fn to_dto(error: &VpnError) -> Option<FailureDto> {
+        let (kind, detail) = match error {
+            VpnError::AuthFailed => ("auth_failed", None),
+            VpnError::TlsHandshakeFailed => ("tls_handshake_failed", None),
+            VpnError::CertificateVerifyFailed(reason) => ("certificate_verify_failed", Some(reason.clone())),
+            VpnError::HostUnresolved(host) => ("host_unresolved", Some(host.clone())),
+            VpnError::TunPermissionDenied => ("tun_permission_denied", None),
+            VpnError::CipherNegotiationFailed => ("cipher_negotiation_failed", None),
+            _ => return None,
+        };
+        Some(FailureDto { kind: kind.to_string(), detail, hint: error.hint().map(str::to_string) })
+    }
+
+    pub fn from_dto(dto: &FailureDto) -> Option<VpnError> {
+        let detail = || dto.detail.clone().unwrap_or_default();
+        match dto.kind.as_str() {
+            "auth_failed" => Some(VpnError::AuthFailed),
+            "tls_handshake_failed" => Some(VpnError::TlsHandshakeFailed),
+            "certificate_verify_failed" => Some(VpnError::CertificateVerifyFailed(detail())),
+            "host_unresolved" => Some(VpnError::HostUnresolved(detail())),
+            "tun_permission_denied" => Some(VpnError::TunPermissionDenied),
+            "cipher_negotiation_failed" => Some(VpnError::CipherNegotiationFailed),
+            _ => None,
+        }
+    }
+}
+
+pub struct ResponseMapper;
+
+impl ResponseMapper {
+    /// Outcome of a connect or disconnect; `done` is the success message.
+    /// The VPN itself is left for the caller to fill in.
+    pub fn connection_response(outcome: &Result<Vec<StoppedInstance>, VpnServiceError>, done: &str) -> VpnConnectionResponse {
+        match outcome {
+            Ok(stopped) => VpnConnectionResponse {
+                success: true,
+                message: done.to_string(),
+                vpn: None,
+                stopped: ShutdownMapper::to_dto_list(stopped),
+                failure: None,
+                error: None,
+            },
+            Err(e) => VpnConnectionResponse {
+                success: false,
+                message: e.to_string(),
+                vpn: None,
+                stopped: Vec::new(),
+                failure: match e {
+                    VpnServiceError::Diagnosed(error) => FailureMapper::to_dto(error),
+                    _ => None,
+                },
+                error: ErrorMapper::to_dto(e),
+            },
+        }
+    }
+
+    /// `name` is how the profile was named on the command line.
+    pub fn validation_report(name: &str, report: &ValidationReport) -> ValidationReportDto {
+        ValidationReportDto {
+            name: name.to_string(),
+            file: report.file.clone(),
+            errors: report.count(Severity::Error),
+            warnings: report.count(Severity::Warning),
+            findings: report
+                .findings
+                .iter()
+                .map(|finding| FindingDto {
+                    severity: finding.severity.to_string(),
+                    rule: finding.rule.clone(),
+                    message: finding.message.clone(),
+                    line: finding.line,
+                    hint: finding.hint.clone(),
+                })
+                .collect(),
+        }
+    }
+}
@@ -1,11 +1,107 @@
 use crate::domain::{
-    entities::{Vpn, VpnStatus, ConnectionState},
-    repositories::VpnRepository,
-    use_cases::{ConnectVpnUseCase, DisconnectVpnUseCase, ListVpnsUseCase},
+    bundle::{BundleMode, ProfileBundle},
+    credentials::{
+        Challenge, CredentialKind, CredentialPrompt, CredentialReply, CredentialRequest, Credentials, AUTH_REALM,
+        KEY_PASSPHRASE_SECRET, KEY_REALM, TOTP_SECRET,
+    },
+    diagnosis,
+    entities::{Vpn, VpnError, VpnStatus, ConnectionState, TrafficStats},
+    repositories::{CredentialStore, VpnRepository},
+    use_cases::{
+        ConnectVpnUseCase, DisconnectVpnUseCase, ImportProfileUseCase, ListVpnsUseCase, RemoveProfileUseCase,
+        RenameProfileUseCase, SetDisplayNameUseCase, UpdateProfileUseCase, UseCaseError,
+    },
+    lint::ProfileLinter,
+    networkmanager::NmConnection,
+    ovpn::{FileReference, OvpnConfig, FILE_DIRECTIVES},
+    state_machine::{ConnectionStateMachine, TransitionError, TunnelState},
+    totp::TotpGenerator,
+    validation::{ConfigValidator, Finding, Severity, ValidationReport},
 };
-use crate::infrastructure::services::OpenVpnService;
-use std::sync::Arc;
+use crate::infrastructure::archive::{write_archive, ProfileArchive};
+use crate::infrastructure::bundle::{bundle_config, read_bundle};
+use crate::infrastructure::files::write_private_async;
+use crate::infrastructure::management::{
+    DynamicChallenge, LogEvent, ManagementClient, ManagementError, ManagementEvent, PasswordPrompt, StateEvent,
+};
+use crate::infrastructure::logs::{LogLine, LogTail};
+use crate::infrastructure::services::{LaunchSecrets, OpenVpnService, StoppedInstance};
+use async_trait::async_trait;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use thiserror::Error;
+use tokio::sync::{broadcast, watch};
+use tokio::task::JoinSet;
+
+/// How long OpenVPN gets to go from a released hold to `CONNECTED` (or a
+/// definite failure) before the attempt is abandoned, unless the profile's
+/// `connect_timeout_secs` says otherwise.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Upper bound for reading another instance's tunnel details in `list`/`status`.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How often the supervisor checks that a kept-alive OpenVPN still runs.
+const PROCESS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Output lines searched for the cause of a failed connection.
+const DIAGNOSIS_LINES: usize = 200;
+
+/// A profile added by `import_profile` and what happened to its files.
+#[derive(Debug, Clone)]
+pub struct ImportedProfile {
+    pub vpn: Vpn,
+    /// Referenced files now kept with the profile, as written in the source.
+    pub bundled: Vec<String>,
+    /// References that could not be read and still point at the original path.
+    pub unresolved: Vec<FileReference>,
+}
+
+/// A profile found inside a provider archive, ready to be imported.
+#[derive(Debug, Clone)]
+pub struct ArchiveProfile {
+    /// Path of the profile inside the archive.
+    pub entry: String,
+    pub id: String,
+    pub display_name: String,
+    /// `None` when the profile could not be parsed; `errors` says why.
+    pub bundle: Option<ProfileBundle>,
+    pub errors: Vec<Finding>,
+}
+
+/// A profile converted from a NetworkManager keyfile.
+#[derive(Debug, Clone)]
+pub struct NmImport {
+    pub imported: ImportedProfile,
+    /// Stored by NetworkManager but not kept in the profile.
+    pub username: Option<String>,
+    /// `[vpn]` keys with no OpenVPN equivalent.
+    pub skipped: Vec<String>,
+}
+
+/// A profile rendered as one `.ovpn` with its files embedded.
+#[derive(Debug, Clone)]
+pub struct ProfileExport {
+    pub vpn_id: String,
+    pub content: String,
+    /// Secret directives removed on request.
+    pub stripped: Vec<String>,
+    /// References that could not be read and are still paths on this machine.
+    pub unresolved: Vec<FileReference>,
+}
+
+/// A profile converted to a NetworkManager keyfile.
+#[derive(Debug, Clone)]
+pub struct NmExport {
+    pub keyfile: String,
+    /// Inline certificates and keys written out for NetworkManager to read.
+    pub written: Vec<PathBuf>,
+    /// Directives with no NetworkManager equivalent.
+    pub skipped: Vec<String>,
+}
 
 #[derive(Error, Debug)]
 pub enum VpnServiceError {
@@ -17,14 +113,536 @@ pub enum VpnServiceError {
     RepositoryError(String),
     #[error("OpenVPN service error: {0}")]
     OpenVpnError(String),
+    #[error("Invalid profile: {0}")]
+    InvalidProfile(String),
+    #[error("VPN already exists: {0}")]
+    AlreadyExists(String),
+    #[error("VPN {0} is in use; disconnect it first")]
+    ProfileInUse(String),
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+    /// Failure reported by the daemon, already worded for the user.
+    #[error("{0}")]
+    Daemon(String),
+    #[error("Cannot talk to the daemon: {0}")]
+    DaemonUnreachable(String),
+    /// A connection failure recognized from OpenVPN's output.
+    #[error("{0}")]
+    Diagnosed(#[from] VpnError),
+}
+
+impl VpnServiceError {
+    /// Remediation advice for the user, when the cause is known.
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            Self::Diagnosed(error) => error.hint(),
+            _ => None,
+        }
+    }
+}
+
+impl From<UseCaseError> for VpnServiceError {
+    fn from(error: UseCaseError) -> Self {
+        match error {
+            UseCaseError::VpnNotFound(id) => Self::VpnNotFound(id),
+            UseCaseError::AlreadyExists(id) => Self::AlreadyExists(id),
+            UseCaseError::InvalidId(message) | UseCaseError::InvalidProfile(message) => Self::InvalidProfile(message),
+            UseCaseError::ConnectionError(message) => Self::ConnectionFailed(message),
+            UseCaseError::RepositoryError(message) => Self::RepositoryError(message),
+        }
+    }
+}
+
+/// The connection commands, whether they run in this process or in the
+/// daemon that owns the tunnel.
+#[async_trait]
+pub trait VpnControl: Send + Sync {
+    async fn list_vpns(&self) -> Result<Vec<Vpn>, VpnServiceError>;
+    async fn connect_vpn(&self, vpn_id: &str) -> Result<(), VpnServiceError>;
+    /// The disconnect commands report each OpenVPN process they stopped.
+    /// `disconnect_vpn` leaves other tunnels up; `disconnect_current` stops all.
+    async fn disconnect_vpn(&self, vpn_id: &str) -> Result<Vec<StoppedInstance>, VpnServiceError>;
+    async fn disconnect_current(&self) -> Result<Vec<StoppedInstance>, VpnServiceError>;
+    async fn force_kill_all_vpns(&self) -> Result<Vec<StoppedInstance>, VpnServiceError>;
+    /// Resolves when any tunnel moves to another phase.
+    async fn tunnel_state_changed(&self);
+}
+
+/// A tunnel started by this service, kept in step with OpenVPN's own
+/// `>STATE:` reports.
+#[derive(Debug, Clone)]
+struct TrackedTunnel {
+    /// Distinguishes attempts, so a stale event feed cannot touch a newer tunnel.
+    session: u64,
+    vpn_id: String,
+    config_path: String,
+    machine: ConnectionStateMachine,
+    status: VpnStatus,
+    /// OpenVPN is blocked on a password the user has not given yet.
+    awaiting_input: bool,
+    /// Set once connected when the profile wants the tunnel kept up: the
+    /// user request it belongs to, see `VpnApplicationService::intents`.
+    supervised: Option<u64>,
+}
+
+impl TrackedTunnel {
+    fn new(session: u64, vpn: &Vpn) -> Self {
+        let mut tunnel = Self {
+            session,
+            vpn_id: vpn.id().to_string(),
+            config_path: vpn.config_path().to_string(),
+            machine: ConnectionStateMachine::new(),
+            status: VpnStatus::default(),
+            awaiting_input: false,
+            supervised: None,
+        };
+        tunnel.force(TunnelState::Connecting);
+        tunnel
+    }
+
+    /// Brings a new tunnel up to date with what OpenVPN did before `events`
+    /// was subscribed: the log history and the current state. The current
+    /// state comes back as an error when it does not fit.
+    async fn catch_up(
+        &mut self,
+        management: &ManagementClient,
+        events: &mut broadcast::Receiver<ManagementEvent>,
+    ) -> Result<Result<(), TransitionError>, ManagementError> {
+        let history = management.log_history().await?;
+        let current = management.current_state().await?;
+
+        for log in &history {
+            self.apply_log(log);
+        }
+        // State reports queued before the `state` reply are older than the
+        // reply itself; log lines are harmless to apply twice.
+        loop {
+            match events.try_recv() {
+                Ok(ManagementEvent::Log(log)) => self.apply_log(&log),
+                Ok(_) | Err(broadcast::error::TryRecvError::Lagged(_)) => {}
+                Err(_) => break,
+            }
+        }
+        match current {
+            Some(current) => Ok(self.apply(&current)),
+            None => Ok(Ok(())),
+        }
+    }
+
+    /// Whether `event` changed the tunnel; state reports that do not fit
+    /// are errors.
+    fn handle(&mut self, event: &ManagementEvent) -> Result<bool, TransitionError> {
+        match event {
+            ManagementEvent::State(state) => self.apply(state)?,
+            ManagementEvent::Log(log) => self.apply_log(log),
+            ManagementEvent::ByteCount { bytes_in, bytes_out } => {
+                let now = SystemTime::now();
+                let traffic = match self.status.traffic() {
+                    Some(previous) => previous.next(*bytes_in, *bytes_out, now),
+                    None => TrafficStats::new(*bytes_in, *bytes_out, now),
+                };
+                self.status.set_traffic(Some(traffic));
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    fn apply(&mut self, event: &StateEvent) -> Result<(), TransitionError> {
+        // A tunnel failed on our side (e.g. a cancelled password) is shut down afterwards.
+        if matches!(self.machine.state(), TunnelState::Failed(_)) && event.name == "EXITING" {
+            return Ok(());
+        }
+        let state = self.machine.apply_openvpn_state(&event.name, &event.description)?.clone();
+        if state == TunnelState::Exiting {
+            // Someone stopped OpenVPN on purpose; don't bring it back.
+            self.supervised = None;
+        }
+        let connected = state == TunnelState::Connected;
+        self.status.set_tunnel_state(state);
+        if connected {
+            self.status.set_connected_since(Some(UNIX_EPOCH + Duration::from_secs(event.timestamp)));
+        }
+        if let Some(local_ip) = &event.local_ip {
+            self.status.set_ip_address(local_ip.clone());
+        }
+        if event.local_ipv6.is_some() {
+            self.status.set_ipv6_address(event.local_ipv6.clone());
+        }
+        if event.remote_ip.is_some() {
+            self.status.set_remote(event.remote_ip.clone(), event.remote_port);
+        }
+        Ok(())
+    }
+
+    fn apply_log(&mut self, log: &LogEvent) {
+        if let Some(device) = log.opened_device() {
+            self.status.set_interface(Some(device.to_string()));
+        } else if let Some(options) = log.push_reply_options() {
+            if let Some(gateway) = Self::pushed_gateway(&options) {
+                self.status.set_gateway(Some(gateway));
+            }
+        }
+    }
+
+    fn pushed_gateway(options: &[&str]) -> Option<String> {
+        let argument = |name: &str, index: usize| {
+            options
+                .iter()
+                .find_map(|option| option.strip_prefix(name)?.strip_prefix(' '))
+                .and_then(|args| args.split_whitespace().nth(index))
+        };
+        if let Some(gateway) = argument("route-gateway", 0) {
+            return Some(gateway.to_string());
+        }
+        // net30/p2p: `ifconfig <local> <remote peer>`; with subnet the second
+        // argument is a netmask instead.
+        argument("ifconfig", 1)
+            .filter(|peer| !peer.starts_with("255."))
+            .map(str::to_string)
+    }
+
+    fn force(&mut self, state: TunnelState) {
+        if self.machine.transition(state.clone()).is_ok() {
+            self.status.set_tunnel_state(state);
+        }
+    }
+}
+
+/// A supervised tunnel that went down without the user asking.
+struct LostTunnel {
+    vpn_id: String,
+    reason: String,
+    intent: u64,
+}
+
+/// Tracked tunnels by profile id.
+type Tunnels = BTreeMap<String, TrackedTunnel>;
+
+fn tunnel_of(tunnels: &Tunnels, session: u64) -> Option<&TrackedTunnel> {
+    tunnels.values().find(|tunnel| tunnel.session == session)
+}
+
+fn tunnel_of_mut(tunnels: &mut Tunnels, session: u64) -> Option<&mut TrackedTunnel> {
+    tunnels.values_mut().find(|tunnel| tunnel.session == session)
+}
+
+/// A sample from `[0, 1)` for reconnect jitter. Hasher keys are random per
+/// process, which is all the spread retries need.
+fn jitter_sample() -> f64 {
+    use std::hash::{BuildHasher, Hasher};
+
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_u128(SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos());
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Why a question cannot be put to anyone.
+fn unanswerable(kind: &CredentialKind) -> String {
+    match kind {
+        CredentialKind::UsernamePassword { .. } => "the profile needs a username and password".to_string(),
+        CredentialKind::Password { realm } => format!("the profile needs the '{}' password", realm),
+        CredentialKind::ChallengeResponse { challenge } => {
+            format!("the server asks '{}' and nobody can answer", challenge.text)
+        }
+    }
+}
+
+/// What a tunnel nobody stays attached to is told at launch, and where it
+/// came from, so its responder can save or drop it once OpenVPN replies.
+#[derive(Default)]
+struct LaunchAnswers {
+    secrets: LaunchSecrets,
+    saved: bool,
+    saved_passphrase: bool,
+    /// The user asked to keep what they typed.
+    remember: bool,
+    remember_passphrase: bool,
+}
+
+/// Answers OpenVPN's `>PASSWORD:` queries for one tunnel, from the
+/// credential store first and then by asking the user.
+struct CredentialResponder {
+    session: u64,
+    vpn_id: String,
+    vpn_name: String,
+    config_path: String,
+    management: Arc<ManagementClient>,
+    /// Where problems with saved secrets are noted.
+    openvpn_service: Arc<OpenVpnService>,
+    tunnels: Arc<watch::Sender<Tunnels>>,
+    store: Option<Arc<dyn CredentialStore>>,
+    prompt: Option<Arc<dyn CredentialPrompt>>,
+    /// Saved credentials went out and have not been accepted yet.
+    sent_saved: bool,
+    /// The server refused the last answer.
+    rejected: bool,
+    /// Prefilled when the user is asked again.
+    username: Option<String>,
+    /// Credentials to save once the tunnel comes up.
+    pending: Option<Credentials>,
+    /// A `CRV1` challenge to answer on the next username/password query.
+    dynamic: Option<DynamicChallenge>,
+    /// The same three for the private key passphrase, which OpenVPN checks
+    /// locally and asks for again after a wrong one.
+    sent_saved_passphrase: bool,
+    key_rejected: bool,
+    pending_passphrase: Option<String>,
+}
+
+impl CredentialResponder {
+    async fn run(mut self, mut events: broadcast::Receiver<ManagementEvent>) {
+        loop {
+            match events.recv().await {
+                Ok(ManagementEvent::Password(prompt)) => {
+                    if let Err(reason) = self.answer(prompt).await {
+                        self.fail(reason).await;
+                    }
+                }
+                Ok(ManagementEvent::State(state)) if state.name == "CONNECTED" => self.remember().await,
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    }
+
+    async fn answer(&mut self, prompt: PasswordPrompt) -> Result<(), String> {
+        match prompt {
+            PasswordPrompt::VerificationFailed { challenge: Some(challenge), .. } => {
+                // Not a rejection: the password passed and a second factor is due.
+                self.dynamic = Some(challenge);
+                Ok(())
+            }
+            PasswordPrompt::VerificationFailed { realm, challenge: None } if realm == KEY_REALM => {
+                self.key_rejected = true;
+                self.pending_passphrase = None;
+                if std::mem::take(&mut self.sent_saved_passphrase) {
+                    if let Some(store) = &self.store {
+                        let _ = store.remove_secret(&self.vpn_id, KEY_PASSPHRASE_SECRET).await;
+                    }
+                }
+                Ok(())
+            }
+            PasswordPrompt::VerificationFailed { realm, challenge: None } => {
+                self.rejected = true;
+                self.pending = None;
+                self.dynamic = None;
+                if realm == AUTH_REALM && std::mem::take(&mut self.sent_saved) {
+                    // Stale passwords would be refused on every attempt.
+                    if let Some(store) = &self.store {
+                        let _ = store.remove(&self.vpn_id).await;
+                    }
+                }
+                Ok(())
+            }
+            PasswordPrompt::UsernamePassword { realm, static_challenge } => {
+                if let Some(dynamic) = self.dynamic.take() {
+                    let response = match self.totp_code().await {
+                        Some(code) => code,
+                        None => {
+                            let challenge = Challenge { text: dynamic.text.clone(), echo: dynamic.echo };
+                            self.ask_response(challenge).await?
+                        }
+                    };
+                    return self.send(&realm, Some(&dynamic.username), &dynamic.password(&response)).await;
+                }
+
+                let saved = match (&self.store, self.rejected) {
+                    (Some(store), false) => store.load(&self.vpn_id).await.unwrap_or_else(|e| {
+                        self.note(format!("Ignoring saved credentials: {}", e));
+                        None
+                    }),
+                    _ => None,
+                };
+                let totp = match static_challenge {
+                    Some(_) => self.totp_code().await,
+                    None => None,
+                };
+                let challenge = static_challenge
+                    .as_ref()
+                    .map(|sc| Challenge { text: sc.text.clone(), echo: sc.echo });
+                let (credentials, response) = match saved {
+                    Some(credentials) => {
+                        self.sent_saved = true;
+                        (credentials, None)
+                    }
+                    None => {
+                        let kind = CredentialKind::UsernamePassword {
+                            challenge: challenge.clone().filter(|_| totp.is_none()),
+                        };
+                        let reply = self.ask(kind).await?;
+                        (reply.credentials, reply.response)
+                    }
+                };
+
+                let password = match (&static_challenge, challenge) {
+                    (Some(sc), Some(challenge)) => {
+                        let response = match totp.or(response) {
+                            Some(response) => response,
+                            None => self.ask_response(challenge).await?,
+                        };
+                        sc.password(credentials.password(), &response)
+                    }
+                    _ => credentials.password().to_string(),
+                };
+                self.send(&realm, Some(credentials.username()), &password).await
+            }
+            PasswordPrompt::Password { realm } if realm == KEY_REALM => {
+                let saved = match (&self.store, self.key_rejected) {
+                    (Some(store), false) => store
+                        .load_secret(&self.vpn_id, KEY_PASSPHRASE_SECRET)
+                        .await
+                        .unwrap_or_else(|e| {
+                            self.note(format!("Ignoring saved passphrase: {}", e));
+                            None
+                        }),
+                    _ => None,
+                };
+                let passphrase = match saved {
+                    Some(passphrase) => {
+                        self.sent_saved_passphrase = true;
+                        passphrase
+                    }
+                    None => {
+                        let reply = self.ask(CredentialKind::Password { realm: realm.clone() }).await?;
+                        let passphrase = reply.credentials.password().to_string();
+                        self.pending_passphrase = reply.remember.then(|| passphrase.clone());
+                        passphrase
+                    }
+                };
+                self.send(&realm, None, &passphrase).await
+            }
+            PasswordPrompt::Password { realm } => {
+                let reply = self.ask(CredentialKind::Password { realm: realm.clone() }).await?;
+                self.send(&realm, None, reply.credentials.password()).await
+            }
+            PasswordPrompt::Other(_) => Ok(()),
+        }
+    }
+
+    async fn ask(&mut self, kind: CredentialKind) -> Result<CredentialReply, String> {
+        let prompt = self.prompt.clone().ok_or_else(|| unanswerable(&kind))?;
+        // Only `auth-user-pass` answers are kept here; passphrases by the caller.
+        let keep = matches!(kind, CredentialKind::UsernamePassword { .. });
+        let retry = match &kind {
+            CredentialKind::Password { realm } if realm == KEY_REALM => self.key_rejected,
+            _ => self.rejected,
+        };
+        let request = CredentialRequest {
+            vpn_id: self.vpn_id.clone(),
+            vpn_name: self.vpn_name.clone(),
+            kind,
+            username: self.username.clone(),
+            retry,
+        };
+
+        self.set_awaiting_input(true);
+        let reply = prompt.ask(&request).await;
+        self.set_awaiting_input(false);
+
+        let reply = reply.ok_or_else(|| "authentication was cancelled".to_string())?;
+        if keep {
+            self.pending = reply.remember.then(|| reply.credentials.clone());
+        }
+        Ok(reply)
+    }
+
+    async fn ask_response(&mut self, challenge: Challenge) -> Result<String, String> {
+        let reply = self.ask(CredentialKind::ChallengeResponse { challenge }).await?;
+        reply.response.ok_or_else(|| "authentication was cancelled".to_string())
+    }
+
+    /// The current code from the profile's stored TOTP seed, if it has one.
+    async fn totp_code(&self) -> Option<String> {
+        let seed = self.store.as_ref()?.load_secret(&self.vpn_id, TOTP_SECRET).await.unwrap_or_else(|e| {
+            self.note(format!("Ignoring saved TOTP seed: {}", e));
+            None
+        })?;
+        match TotpGenerator::parse(&seed) {
+            Ok(generator) => Some(generator.code()),
+            Err(e) => {
+                self.note(format!("Ignoring saved TOTP seed: {}", e));
+                None
+            }
+        }
+    }
+
+    fn note(&self, text: String) {
+        self.openvpn_service.note(&self.config_path, text);
+    }
+
+    async fn send(&mut self, realm: &str, username: Option<&str>, password: &str) -> Result<(), String> {
+        if let Some(username) = username {
+            self.management.send_username(realm, username).await.map_err(|e| e.to_string())?;
+            self.username = Some(username.to_string());
+        }
+        self.management.send_password(realm, password).await.map_err(|e| e.to_string())
+    }
+
+    async fn remember(&mut self) {
+        self.sent_saved = false;
+        self.rejected = false;
+        self.sent_saved_passphrase = false;
+        self.key_rejected = false;
+        let Some(store) = &self.store else { return };
+        if let Some(credentials) = self.pending.take() {
+            if let Err(e) = store.save(&self.vpn_id, &credentials).await {
+                self.note(format!("Could not save credentials: {}", e));
+            }
+        }
+        if let Some(passphrase) = self.pending_passphrase.take() {
+            if let Err(e) = store.save_secret(&self.vpn_id, KEY_PASSPHRASE_SECRET, &passphrase).await {
+                self.note(format!("Could not save the key passphrase: {}", e));
+            }
+        }
+    }
+
+    fn set_awaiting_input(&self, awaiting: bool) {
+        let session = self.session;
+        self.tunnels.send_if_modified(|tunnels| match tunnel_of_mut(tunnels, session) {
+            Some(tunnel) => {
+                tunnel.awaiting_input = awaiting;
+                true
+            }
+            _ => false,
+        });
+    }
+
+    /// Without an answer OpenVPN would wait forever, so it is stopped.
+    async fn fail(&self, reason: String) {
+        let session = self.session;
+        self.tunnels.send_if_modified(|tunnels| match tunnel_of_mut(tunnels, session) {
+            Some(tunnel) => {
+                tunnel.force(TunnelState::Failed(reason));
+                true
+            }
+            _ => false,
+        });
+        let _ = self.management.signal("SIGTERM").await;
+    }
 }
 
 pub struct VpnApplicationService {
     vpn_repository: Arc<dyn VpnRepository>,
     openvpn_service: Arc<OpenVpnService>,
+    tunnels: Arc<watch::Sender<Tunnels>>,
+    sessions: AtomicU64,
+    /// Bumped by every connect and disconnect the user asks for, so the
+    /// supervisor never overrides a newer decision.
+    intents: AtomicU64,
+    /// The latest of those decisions per profile; reconnects acting on an
+    /// older one give up.
+    wanted: Mutex<HashMap<String, u64>>,
     _connect_use_case: ConnectVpnUseCase,
     _disconnect_use_case: DisconnectVpnUseCase,
     list_use_case: ListVpnsUseCase,
+    import_use_case: ImportProfileUseCase,
+    update_use_case: UpdateProfileUseCase,
+    rename_use_case: RenameProfileUseCase,
+    remove_use_case: RemoveProfileUseCase,
+    set_name_use_case: SetDisplayNameUseCase,
+    credential_store: Option<Arc<dyn CredentialStore>>,
+    credential_prompt: Option<Arc<dyn CredentialPrompt>>,
 }
 
 impl VpnApplicationService {
@@ -35,16 +653,45 @@ impl VpnApplicationService {
         let connect_use_case = ConnectVpnUseCase::new(Arc::clone(&vpn_repository));
         let disconnect_use_case = DisconnectVpnUseCase::new(Arc::clone(&vpn_repository));
         let list_use_case = ListVpnsUseCase::new(Arc::clone(&vpn_repository));
+        let import_use_case = ImportProfileUseCase::new(Arc::clone(&vpn_repository));
+        let update_use_case = UpdateProfileUseCase::new(Arc::clone(&vpn_repository));
+        let rename_use_case = RenameProfileUseCase::new(Arc::clone(&vpn_repository));
+        let remove_use_case = RemoveProfileUseCase::new(Arc::clone(&vpn_repository));
+        let set_name_use_case = SetDisplayNameUseCase::new(Arc::clone(&vpn_repository));
 
         Self {
             vpn_repository,
             openvpn_service,
+            tunnels: Arc::new(watch::Sender::new(BTreeMap::new())),
+            sessions: AtomicU64::new(0),
+            intents: AtomicU64::new(0),
+            wanted: Mutex::new(HashMap::new()),
             _connect_use_case: connect_use_case,
             _disconnect_use_case: disconnect_use_case,
             list_use_case,
+            import_use_case,
+            update_use_case,
+            rename_use_case,
+            remove_use_case,
+            set_name_use_case,
+            credential_store: None,
+            credential_prompt: None,
         }
     }
 
+    /// Where accepted credentials are remembered when the user asks to.
+    pub fn with_credential_store(mut self, store: Arc<dyn CredentialStore>) -> Self {
+        self.credential_store = Some(store);
+        self
+    }
+
+    /// How OpenVPN's password questions reach the user. Without one, profiles
+    /// needing a password only connect with saved credentials.
+    pub fn with_credential_prompt(mut self, prompt: Arc<dyn CredentialPrompt>) -> Self {
+        self.credential_prompt = Some(prompt);
+        self
+    }
+
     pub async fn list_vpns(&self) -> Result<Vec<Vpn>, VpnServiceError> {
         let mut vpns = self.list_use_case
             .execute()
@@ -57,15 +704,31 @@ impl VpnApplicationService {
         Ok(vpns)
     }
 
-    async fn sync_vpn_states(&self, vpns: &mut Vec<Vpn>) -> Result<(), VpnServiceError> {
-        // Get the currently connected VPN config file (if any)
-        let connected_config = self.openvpn_service.get_connected_vpn_config().await;
+    async fn sync_vpn_states(&self, vpns: &mut [Vpn]) -> Result<(), VpnServiceError> {
+        // Config files of every running OpenVPN
+        let connected_configs = self.openvpn_service.connected_vpn_configs().await;
+        let tracked = self.tunnels.borrow().clone();
         
         for vpn in vpns.iter_mut() {
-            let should_be_connected = match &connected_config {
-                Some(config_path) => vpn.config_path() == config_path,
-                None => false,
-            };
+            let should_be_connected = connected_configs.iter().any(|config_path| vpn.config_path() == config_path);
+
+            // A tunnel we started knows its exact phase; trust it while its
+            // process is alive, and keep showing how it ended afterwards.
+            if let Some(tunnel) = tracked.get(vpn.id()) {
+                // Between reconnect attempts there is no process by design.
+                let reconnecting = tunnel.status.reconnect_attempt().is_some();
+                if should_be_connected || reconnecting || !tunnel.machine.state().is_active() {
+                    vpn.update_status(self.with_traffic(tunnel.status.clone()));
+                    continue;
+                }
+                // The process vanished without reporting EXITING (e.g. SIGKILL).
+                self.record_tunnel_state(tunnel.session, TunnelState::Disconnected);
+            } else if should_be_connected {
+                if let Some(status) = self.probe_status(vpn).await {
+                    vpn.update_status(self.with_traffic(status));
+                    continue;
+                }
+            }
             
             // Update VPN status based on actual system state
             let new_state = if should_be_connected {
@@ -83,13 +746,10 @@ impl VpnApplicationService {
         Ok(())
     }
 
+    /// Brings up `vpn_id` next to the tunnels already running. Connecting a
+    /// profile that is up restarts it; exclusive profiles first stop the
+    /// tunnels they conflict with.
     pub async fn connect_vpn(&self, vpn_id: &str) -> Result<(), VpnServiceError> {
-        // ALWAYS force kill all VPN processes to ensure only one connection
-        self.force_kill_all_vpns().await?;
-        
-        // Small delay to ensure processes are fully terminated
-        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-
         // Get VPN configuration
         let vpn = self
             .vpn_repository
@@ -98,6 +758,32 @@ impl VpnApplicationService {
             .map_err(|e| VpnServiceError::RepositoryError(e.to_string()))?
             .ok_or_else(|| VpnServiceError::VpnNotFound(vpn_id.to_string()))?;
 
+        self.stop_conflicting(&vpn).await?;
+        let intent = self.stop_supervising(vpn_id);
+        self.start_tunnel(&vpn, None, intent).await
+    }
+
+    /// An exclusive profile stops every other OpenVPN, like connecting did
+    /// before several tunnels were possible; otherwise only active exclusive
+    /// profiles are stopped.
+    async fn stop_conflicting(&self, vpn: &Vpn) -> Result<(), VpnServiceError> {
+        if vpn.metadata().options().exclusive {
+            self.force_kill_all_vpns().await?;
+            return Ok(());
+        }
+        let vpns = self.list_vpns().await?;
+        for other in vpns.iter().filter(|other| other.is_connected() || other.is_connecting()) {
+            if vpn.conflicts_with(other) {
+                self.disconnect_vpn(other.id()).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Starts OpenVPN for `vpn` and waits until the tunnel is up or has
+    /// failed. `attempt` numbers automatic reconnects. A tunnel that comes up
+    /// is supervised on behalf of `intent` when its retry policy is enabled.
+    async fn start_tunnel(&self, vpn: &Vpn, attempt: Option<u32>, intent: u64) -> Result<(), VpnServiceError> {
         // Update VPN status to connecting
         let mut updated_vpn = vpn.clone();
         updated_vpn.update_status(VpnStatus::new(ConnectionState::Connecting, String::new()));
@@ -106,23 +792,493 @@ impl VpnApplicationService {
             .await
             .map_err(|e| VpnServiceError::RepositoryError(e.to_string()))?;
 
+        // Without a resident client nobody would answer OpenVPN later, so it is told up front.
+        let answers = match self.openvpn_service.has_resident_client() {
+            true => LaunchAnswers::default(),
+            false => self.launch_answers(vpn).await?,
+        };
+
         // Connect using OpenVPN service
-        self.openvpn_service
-            .connect(vpn.config_path())
-            .await
-            .map_err(|e| VpnServiceError::OpenVpnError(e.to_string()))?;
+        if let Err(e) = self.openvpn_service.connect_with_secrets(vpn.config_path(), &answers.secrets).await {
+            return Err(self.diagnose(vpn).unwrap_or_else(|| VpnServiceError::OpenVpnError(e.to_string())));
+        }
 
-        // Update VPN status to connected
-        updated_vpn.update_status(VpnStatus::new(ConnectionState::Connected, String::new()));
+        // The process is up, but only OpenVPN knows when the tunnel is.
+        let tracked = self.track_tunnel(vpn, attempt, answers).await;
+        let session = match tracked {
+            Ok(session) => session,
+            Err(e) => {
+                self.openvpn_service.discard_secrets(vpn.config_path()).await;
+                return Err(e);
+            }
+        };
+        let timeout = vpn
+            .metadata()
+            .options()
+            .connect_timeout_secs
+            .map(Duration::from_secs)
+            .unwrap_or(CONNECT_TIMEOUT);
+        let outcome = self.wait_for_outcome(session, timeout).await;
+        self.openvpn_service.discard_secrets(vpn.config_path()).await;
+
+        let supervised = outcome.is_ok() && vpn.metadata().options().retry_policy().enabled;
+        self.tunnels.send_if_modified(|tunnels| match tunnel_of_mut(tunnels, session) {
+            Some(tunnel) => {
+                updated_vpn.update_status(tunnel.status.clone());
+                tunnel.supervised = supervised.then_some(intent);
+                supervised
+            }
+            _ => false,
+        });
+        if outcome.is_ok() {
+            let mut metadata = updated_vpn.metadata().clone();
+            metadata.set_last_used(Some(SystemTime::now()));
+            updated_vpn.set_metadata(metadata);
+        }
         self.vpn_repository
             .save(&updated_vpn)
             .await
             .map_err(|e| VpnServiceError::RepositoryError(e.to_string()))?;
 
-        Ok(())
+        if outcome.is_err() {
+            // OpenVPN may be retrying forever; don't leave it running behind the error.
+            let _ = self.openvpn_service.disconnect(vpn.config_path()).await;
+            // Once it has exited, its output is complete.
+            if let Some(diagnosed) = self.diagnose(vpn) {
+                return Err(diagnosed);
+            }
+        }
+        outcome
+    }
+
+    /// Asks before launch what OpenVPN would otherwise ask over the management
+    /// interface: `auth-user-pass` credentials and the passphrase of an
+    /// encrypted key, saved ones first. One-time codes cannot be given this
+    /// way.
+    async fn launch_answers(&self, vpn: &Vpn) -> Result<LaunchAnswers, VpnServiceError> {
+        let config_path = Path::new(vpn.config_path());
+        // A broken profile is left to OpenVPN to report.
+        let Ok(config) = Self::read_profile(config_path).await else {
+            return Ok(LaunchAnswers::default());
+        };
+        let mut answers = LaunchAnswers::default();
+
+        let asks_credentials = config.directive("auth-user-pass").is_some_and(|directive| directive.arg(0).is_none())
+            && config.inline_block("auth-user-pass").is_none();
+        if asks_credentials {
+            if config.has_directive("static-challenge") {
+                return Err(VpnServiceError::ConnectionFailed(
+                    "the profile asks for a one-time code, which needs `connect --keep-alive` or the daemon".to_string(),
+                ));
+            }
+            let saved = match &self.credential_store {
+                Some(store) => store.load(vpn.id()).await.unwrap_or_else(|e| {
+                    self.openvpn_service.note(vpn.config_path(), format!("Ignoring saved credentials: {}", e));
+                    None
+                }),
+                None => None,
+            };
+            answers.saved = saved.is_some();
+            answers.secrets.credentials = match saved {
+                Some(credentials) => Some(credentials),
+                None => {
+                    let reply = self
+                        .ask_before_launch(vpn, CredentialKind::UsernamePassword { challenge: None })
+                        .await?;
+                    answers.remember = reply.remember;
+                    Some(reply.credentials)
+                }
+            };
+        }
+
+        if Self::has_encrypted_key(&config, config_path).await {
+            let saved = match &self.credential_store {
+                Some(store) => store.load_secret(vpn.id(), KEY_PASSPHRASE_SECRET).await.unwrap_or_else(|e| {
+                    self.openvpn_service.note(vpn.config_path(), format!("Ignoring saved passphrase: {}", e));
+                    None
+                }),
+                None => None,
+            };
+            answers.saved_passphrase = saved.is_some();
+            answers.secrets.key_passphrase = match saved {
+                Some(passphrase) => Some(passphrase),
+                None => {
+                    let kind = CredentialKind::Password { realm: KEY_REALM.to_string() };
+                    let reply = self.ask_before_launch(vpn, kind).await?;
+                    answers.remember_passphrase = reply.remember;
+                    Some(reply.credentials.password().to_string())
+                }
+            };
+        }
+        Ok(answers)
+    }
+
+    /// Whether OpenVPN will need a passphrase for the client key: it is
+    /// encrypted and `askpass` names no file of its own.
+    async fn has_encrypted_key(config: &OvpnConfig, config_path: &Path) -> bool {
+        if config.directive("askpass").is_some_and(|directive| directive.arg(0).is_some()) {
+            return false;
+        }
+        let key = match (config.inline_block("key"), config.directive("key").and_then(|directive| directive.arg(0))) {
+            (Some(block), _) => block.content().to_string(),
+            (None, Some(file)) => {
+                let file = config_path.parent().unwrap_or(Path::new(".")).join(file);
+                tokio::fs::read_to_string(file).await.unwrap_or_default()
+            }
+            (None, None) => return false,
+        };
+        key.contains("ENCRYPTED")
+    }
+
+    async fn ask_before_launch(&self, vpn: &Vpn, kind: CredentialKind) -> Result<CredentialReply, VpnServiceError> {
+        let prompt = self
+            .credential_prompt
+            .as_ref()
+            .ok_or_else(|| VpnServiceError::ConnectionFailed(unanswerable(&kind)))?;
+        let request = CredentialRequest {
+            vpn_id: vpn.id().to_string(),
+            vpn_name: vpn.display_name().to_string(),
+            kind,
+            username: None,
+            retry: false,
+        };
+        prompt
+            .ask(&request)
+            .await
+            .ok_or_else(|| VpnServiceError::ConnectionFailed("authentication was cancelled".to_string()))
+    }
+
+    /// The failure OpenVPN's latest output of `vpn` points at, if recognized.
+    fn diagnose(&self, vpn: &Vpn) -> Option<VpnServiceError> {
+        let name = OpenVpnService::instance_name(vpn.config_path());
+        let lines = self.openvpn_service.log().recent(&name, DIAGNOSIS_LINES);
+        diagnosis::diagnose(lines.iter().map(|line| line.text.as_str())).map(VpnServiceError::Diagnosed)
+    }
+
+    /// Watches tunnels this service brought up and restarts them when they
+    /// drop, following each profile's retry policy; tunnels are brought back
+    /// independently of each other. Never returns; UIs that stay open spawn
+    /// it once.
+    pub async fn supervise(self: Arc<Self>) {
+        let mut reconnects = JoinSet::new();
+        let mut reconnecting = HashSet::new();
+        loop {
+            tokio::select! {
+                lost = self.wait_for_loss(&reconnecting) => {
+                    reconnecting.insert(lost.vpn_id.clone());
+                    let service = Arc::clone(&self);
+                    reconnects.spawn(async move {
+                        let vpn_id = lost.vpn_id.clone();
+                        service.reconnect(lost).await;
+                        vpn_id
+                    });
+                }
+                Some(Ok(vpn_id)) = reconnects.join_next() => {
+                    reconnecting.remove(&vpn_id);
+                }
+            }
+        }
+    }
+
+    /// The first supervised tunnel found down, skipping those in `reconnecting`.
+    async fn wait_for_loss(&self, reconnecting: &HashSet<String>) -> LostTunnel {
+        let mut updates = self.tunnels.subscribe();
+        loop {
+            let watched: Vec<_> = updates
+                .borrow_and_update()
+                .values()
+                .filter(|tunnel| !reconnecting.contains(&tunnel.vpn_id))
+                .filter_map(|tunnel| {
+                    let intent = tunnel.supervised.filter(|intent| self.is_wanted(&tunnel.vpn_id, *intent))?;
+                    Some((tunnel.vpn_id.clone(), tunnel.config_path.clone(), tunnel.machine.state().clone(), intent))
+                })
+                .collect();
+            for (vpn_id, config_path, state, intent) in watched {
+                let reason = match state {
+                    TunnelState::Failed(reason) => Some(reason),
+                    TunnelState::Disconnected => Some("OpenVPN is no longer running".to_string()),
+                    _ => self
+                        .openvpn_service
+                        .exit_status(&config_path)
+                        .await
+                        .map(|status| format!("OpenVPN exited ({})", status)),
+                };
+                if let Some(reason) = reason {
+                    return LostTunnel { vpn_id, reason, intent };
+                }
+            }
+            // Nothing reports a killed process, so it is also polled.
+            tokio::select! {
+                _ = updates.changed() => {}
+                _ = tokio::time::sleep(PROCESS_POLL_INTERVAL) => {}
+            }
+        }
+    }
+
+    async fn reconnect(&self, lost: LostTunnel) {
+        let Ok(Some(vpn)) = self.vpn_repository.find_by_id(&lost.vpn_id).await else {
+            return;
+        };
+        let policy = vpn.metadata().options().retry_policy();
+        let still_wanted = || self.is_wanted(&lost.vpn_id, lost.intent);
+        let mut reason = lost.reason;
+
+        for attempt in 1.. {
+            if !still_wanted() {
+                return;
+            }
+            if !policy.allows(attempt) {
+                self.update_tunnel_of(&lost.vpn_id, |tunnel| {
+                    tunnel.force(TunnelState::GaveUp(reason.clone()));
+                    tunnel.status.set_reconnect_attempt(Some(attempt - 1));
+                });
+                return;
+            }
+            self.update_tunnel_of(&lost.vpn_id, |tunnel| {
+                tunnel.force(TunnelState::Reconnecting);
+                tunnel.status.set_reconnect_attempt(Some(attempt));
+            });
+            tokio::time::sleep(policy.delay(attempt, jitter_sample())).await;
+            if !still_wanted() {
+                return;
+            }
+            match self.start_tunnel(&vpn, Some(attempt), lost.intent).await {
+                Ok(()) => return,
+                Err(e) => reason = e.to_string(),
+            }
+        }
+    }
+
+    fn update_tunnel_of(&self, vpn_id: &str, update: impl FnOnce(&mut TrackedTunnel)) {
+        self.tunnels.send_if_modified(|tunnels| match tunnels.get_mut(vpn_id) {
+            Some(tunnel) => {
+                update(tunnel);
+                true
+            }
+            None => false,
+        });
+    }
+
+    fn is_wanted(&self, vpn_id: &str, intent: u64) -> bool {
+        let wanted = self.wanted.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        wanted.get(vpn_id) == Some(&intent)
+    }
+
+    /// The user decided what happens next with `vpn_id`, so its pending
+    /// reconnects are abandoned. Returns the intent recording the decision.
+    fn stop_supervising(&self, vpn_id: &str) -> u64 {
+        let intent = self.intents.fetch_add(1, Ordering::SeqCst) + 1;
+        self.wanted
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(vpn_id.to_string(), intent);
+        self.tunnels.send_if_modified(|tunnels| match tunnels.get_mut(vpn_id) {
+            Some(tunnel) if tunnel.status.reconnect_attempt().is_some() && tunnel.machine.state().is_active() => {
+                tunnel.force(TunnelState::Disconnected);
+                true
+            }
+            _ => false,
+        });
+        intent
+    }
+
+    /// Same as [`Self::stop_supervising`], for every profile.
+    fn stop_supervising_all(&self) {
+        self.wanted.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clear();
+        self.tunnels.send_if_modified(|tunnels| {
+            let mut modified = false;
+            for tunnel in tunnels.values_mut() {
+                if tunnel.status.reconnect_attempt().is_some() && tunnel.machine.state().is_active() {
+                    tunnel.force(TunnelState::Disconnected);
+                    modified = true;
+                }
+            }
+            modified
+        });
+    }
+
+    /// Resolves when any tracked tunnel moves to another phase, so UIs can
+    /// refresh as it happens instead of on a timer.
+    pub async fn tunnel_state_changed(&self) {
+        let phases = |tunnels: &Tunnels| {
+            tunnels
+                .values()
+                .map(|tunnel| (tunnel.session, tunnel.machine.state().clone(), tunnel.status.reconnect_attempt()))
+                .collect::<Vec<_>>()
+        };
+        let mut updates = self.tunnels.subscribe();
+        let current = phases(&updates.borrow_and_update());
+        let _ = updates.wait_for(|tunnels| phases(tunnels) != current).await;
     }
 
-    pub async fn disconnect_vpn(&self, vpn_id: &str) -> Result<(), VpnServiceError> {
+    /// Starts following the management interface of the freshly started
+    /// tunnel, releases OpenVPN's hold and returns the session id it is
+    /// tracked under. `answers` are what OpenVPN was told at launch.
+    async fn track_tunnel(&self, vpn: &Vpn, attempt: Option<u32>, answers: LaunchAnswers) -> Result<u64, VpnServiceError> {
+        let vpn_id = vpn.id();
+        let management = self
+            .openvpn_service
+            .management(vpn.config_path())
+            .await
+            .ok_or_else(|| VpnServiceError::OpenVpnError("management interface is not attached".to_string()))?;
+
+        let session = self.sessions.fetch_add(1, Ordering::Relaxed) + 1;
+        let mut tunnel = TrackedTunnel::new(session, vpn);
+        tunnel.status.set_reconnect_attempt(attempt);
+        let mut events = management.subscribe();
+        let caught_up = tunnel
+            .catch_up(&management, &mut events)
+            .await
+            .map_err(|e| VpnServiceError::OpenVpnError(e.to_string()))?;
+        if let Err(e) = caught_up {
+            self.openvpn_service.note(vpn.config_path(), format!("Ignoring OpenVPN state report: {}", e));
+        }
+        self.tunnels.send_modify(|tunnels| {
+            tunnels.insert(vpn_id.to_string(), tunnel);
+        });
+
+        let tracked = Arc::clone(&self.tunnels);
+        let openvpn_service = Arc::clone(&self.openvpn_service);
+        let config_path = vpn.config_path().to_string();
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) => {
+                        let mut rejected = None;
+                        tracked.send_if_modified(|tunnels| match tunnel_of_mut(tunnels, session) {
+                            Some(tunnel) => tunnel.handle(&event).unwrap_or_else(|e| {
+                                rejected = Some(e);
+                                false
+                            }),
+                            None => false,
+                        });
+                        if let Some(e) = rejected {
+                            openvpn_service.note(&config_path, format!("Ignoring OpenVPN state report: {}", e));
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+
+        let responder = CredentialResponder {
+            session,
+            vpn_id: vpn_id.to_string(),
+            vpn_name: vpn.display_name().to_string(),
+            config_path: vpn.config_path().to_string(),
+            management: Arc::clone(&management),
+            openvpn_service: Arc::clone(&self.openvpn_service),
+            tunnels: Arc::clone(&self.tunnels),
+            store: self.credential_store.clone(),
+            prompt: self.credential_prompt.clone(),
+            sent_saved: answers.saved,
+            rejected: false,
+            username: answers.secrets.credentials.as_ref().map(|credentials| credentials.username().to_string()),
+            pending: answers.secrets.credentials.filter(|_| answers.remember),
+            dynamic: None,
+            sent_saved_passphrase: answers.saved_passphrase,
+            key_rejected: false,
+            pending_passphrase: answers.secrets.key_passphrase.filter(|_| answers.remember_passphrase),
+        };
+        tokio::spawn(responder.run(management.subscribe()));
+
+        management
+            .disable_hold()
+            .await
+            .map_err(|e| VpnServiceError::OpenVpnError(e.to_string()))?;
+        management
+            .release_hold()
+            .await
+            .map_err(|e| VpnServiceError::OpenVpnError(e.to_string()))?;
+        Ok(session)
+    }
+
+    /// Status of a tunnel this instance did not start, read from its
+    /// management interface when nobody else is attached to it.
+    async fn probe_status(&self, vpn: &Vpn) -> Option<VpnStatus> {
+        let probe = async {
+            let management = self.openvpn_service.probe_management(vpn.config_path()).await?;
+            let mut events = management.subscribe();
+            let mut tunnel = TrackedTunnel::new(0, vpn);
+            // A report that does not fit leaves the status as far as it got.
+            let _ = tunnel.catch_up(&management, &mut events).await.ok()?;
+            Some(tunnel.status)
+        };
+        tokio::time::timeout(PROBE_TIMEOUT, probe).await.ok().flatten()
+    }
+
+    /// Fills in interface counters when OpenVPN has not reported any yet.
+    fn with_traffic(&self, mut status: VpnStatus) -> VpnStatus {
+        if status.traffic().is_none() && status.state() == &ConnectionState::Connected {
+            let traffic = status.interface().and_then(|interface| self.openvpn_service.interface_traffic(interface));
+            status.set_traffic(traffic);
+        }
+        status
+    }
+
+    async fn wait_for_outcome(&self, session: u64, timeout: Duration) -> Result<(), VpnServiceError> {
+        let mut updates = self.tunnels.subscribe();
+        let mut deadline = tokio::time::Instant::now() + timeout;
+        let state = loop {
+            let settled = updates.wait_for(|tunnels| match tunnel_of(tunnels, session) {
+                Some(tunnel) => {
+                    matches!(tunnel.machine.state(), TunnelState::Connected | TunnelState::Failed(_) | TunnelState::Disconnected)
+                }
+                // Superseded by another attempt.
+                None => true,
+            });
+            match tokio::time::timeout_at(deadline, settled).await {
+                Ok(Ok(tunnels)) => break tunnel_of(&tunnels, session).map(|tunnel| tunnel.machine.state().clone()),
+                Ok(Err(_)) => break None,
+                // Time spent typing a password does not count.
+                Err(_) if self.is_awaiting_input(session) => deadline = tokio::time::Instant::now() + timeout,
+                Err(_) => {
+                    let reason = format!("no connection after {} seconds", timeout.as_secs());
+                    self.record_tunnel_state(session, TunnelState::Failed(reason.clone()));
+                    return Err(VpnServiceError::ConnectionFailed(reason));
+                }
+            }
+        };
+
+        match state {
+            Some(TunnelState::Connected) => Ok(()),
+            Some(TunnelState::Failed(reason)) => Err(VpnServiceError::ConnectionFailed(reason)),
+            Some(_) => Err(VpnServiceError::ConnectionFailed("OpenVPN exited before the tunnel came up".to_string())),
+            None => Err(VpnServiceError::ConnectionFailed("connection attempt was superseded".to_string())),
+        }
+    }
+
+    fn is_awaiting_input(&self, session: u64) -> bool {
+        tunnel_of(&self.tunnels.borrow(), session).is_some_and(|tunnel| tunnel.awaiting_input)
+    }
+
+    /// Moves the tracked tunnel of `session` to `state` when that is a legal
+    /// transition; anything else is left as OpenVPN last reported it.
+    fn record_tunnel_state(&self, session: u64, state: TunnelState) {
+        self.tunnels.send_if_modified(|tunnels| match tunnel_of_mut(tunnels, session) {
+            Some(tunnel) => {
+                tunnel.force(state);
+                true
+            }
+            None => false,
+        });
+    }
+
+    fn record_disconnected(&self, vpn_id: &str) {
+        self.update_tunnel_of(vpn_id, |tunnel| tunnel.force(TunnelState::Disconnected));
+    }
+
+    fn record_all_disconnected(&self) {
+        self.tunnels.send_if_modified(|tunnels| {
+            tunnels.values_mut().for_each(|tunnel| tunnel.force(TunnelState::Disconnected));
+            !tunnels.is_empty()
+        });
+    }
+
+    /// Stops the tunnel of `vpn_id` only; other profiles stay connected.
+    pub async fn disconnect_vpn(&self, vpn_id: &str) -> Result<Vec<StoppedInstance>, VpnServiceError> {
+        self.stop_supervising(vpn_id);
         // Get VPN configuration
         let vpn = self
             .vpn_repository
@@ -140,11 +1296,13 @@ impl VpnApplicationService {
             .map_err(|e| VpnServiceError::RepositoryError(e.to_string()))?;
 
         // Disconnect using OpenVPN service
-        self.openvpn_service
-            .disconnect()
+        let stopped = self.openvpn_service
+            .disconnect(vpn.config_path())
             .await
             .map_err(|e| VpnServiceError::OpenVpnError(e.to_string()))?;
 
+        self.record_disconnected(vpn_id);
+
         // Update VPN status to disconnected
         updated_vpn.update_status(VpnStatus::new(ConnectionState::Disconnected, String::new()));
         self.vpn_repository
@@ -152,20 +1310,24 @@ impl VpnApplicationService {
             .await
             .map_err(|e| VpnServiceError::RepositoryError(e.to_string()))?;
 
-        Ok(())
+        Ok(stopped)
     }
 
-    pub async fn disconnect_current(&self) -> Result<(), VpnServiceError> {
+    /// Stops every active tunnel.
+    pub async fn disconnect_current(&self) -> Result<Vec<StoppedInstance>, VpnServiceError> {
+        self.stop_supervising_all();
         if !self.openvpn_service.is_connected().await {
-            return Ok(());
+            return Ok(Vec::new());
         }
 
         // Disconnect using OpenVPN service
-        self.openvpn_service
-            .disconnect()
+        let stopped = self.openvpn_service
+            .disconnect_all()
             .await
             .map_err(|e| VpnServiceError::OpenVpnError(e.to_string()))?;
 
+        self.record_all_disconnected();
+
         // Update all VPNs status to disconnected
         let vpns = self.list_vpns().await?;
         for mut vpn in vpns {
@@ -178,20 +1340,456 @@ impl VpnApplicationService {
             }
         }
 
+        Ok(stopped)
+    }
+
+    /// Runs the semantic validator over a profile file on disk. Relative
+    /// certificate and key paths are resolved against the file's directory.
+    pub async fn validate_file(&self, config_path: &Path) -> ValidationReport {
+        let file = config_path.to_string_lossy().to_string();
+        let findings = match tokio::fs::read_to_string(config_path).await {
+            Ok(source) => ConfigValidator::for_profile(config_path).validate_source(&source),
+            Err(e) => vec![Finding::new(
+                Severity::Error,
+                "unreadable",
+                format!("cannot read profile: {}", e),
+                None,
+            )],
+        };
+        ValidationReport::new(file, findings)
+    }
+
+    /// Deprecation and hardening findings for OpenVPN 2.6. Syntax errors are
+    /// left to [`Self::validate_file`], so a broken profile lints clean.
+    pub async fn lint_file(&self, config_path: &Path) -> ValidationReport {
+        let findings = match Self::read_profile(config_path).await {
+            Ok(config) => ProfileLinter::lint(&config),
+            Err(_) => Vec::new(),
+        };
+        ValidationReport::new(config_path.to_string_lossy().to_string(), findings)
+    }
+
+    /// The profile text with every fixable lint finding corrected.
+    pub async fn fix_file(&self, config_path: &Path) -> Result<String, VpnServiceError> {
+        let config = Self::read_profile(config_path)
+            .await
+            .map_err(|e| VpnServiceError::InvalidProfile(e.message))?;
+        Ok(ProfileLinter::fix(&config).render())
+    }
+
+    /// Copies the profile at `source` into the profile directory together
+    /// with the certificates and keys it references. The id defaults to the
+    /// file name without its extension.
+    pub async fn import_profile(
+        &self,
+        source: &Path,
+        id: Option<&str>,
+        mode: BundleMode,
+        replace: bool,
+    ) -> Result<ImportedProfile, VpnServiceError> {
+        let id = match id {
+            Some(id) => id.to_string(),
+            None => Self::profile_id_for(source),
+        };
+        if replace {
+            self.ensure_not_in_use(&id).await?;
+        }
+        let bundle = read_bundle(source)
+            .await
+            .map_err(|e| VpnServiceError::InvalidProfile(e.to_string()))?;
+        self.import_bundle(&id, &bundle, mode, replace).await
+    }
+
+    /// Converts a NetworkManager OpenVPN keyfile into a profile, bundling the
+    /// files it points at. The id defaults to the connection name.
+    pub async fn import_nm_connection(
+        &self,
+        keyfile: &Path,
+        id: Option<&str>,
+        mode: BundleMode,
+        replace: bool,
+    ) -> Result<NmImport, VpnServiceError> {
+        let text = tokio::fs::read_to_string(keyfile)
+            .await
+            .map_err(|e| VpnServiceError::InvalidProfile(format!("cannot read {}: {}", keyfile.display(), e)))?;
+        let connection = NmConnection::parse(&text)
+            .map_err(|e| VpnServiceError::InvalidProfile(format!("{}: {}", keyfile.display(), e)))?;
+
+        let name = match connection.name() {
+            "" => keyfile.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default(),
+            name => name.to_string(),
+        };
+        let id = match id {
+            Some(id) => id.to_string(),
+            None => Self::profile_id_for(Path::new(&format!("{}.ovpn", name))),
+        };
+        if replace {
+            self.ensure_not_in_use(&id).await?;
+        }
+
+        let conversion = connection.to_ovpn();
+        let base_dir = keyfile.parent().map(Path::to_path_buf).unwrap_or_default();
+        let bundle = bundle_config(conversion.value, &base_dir).await;
+        let mut imported = self.import_bundle(&id, &bundle, mode, replace).await?;
+        if imported.vpn.metadata().display_name().is_none() && name != id {
+            imported.vpn = self.set_display_name(&id, Some(name)).await?;
+        }
+        Ok(NmImport {
+            imported,
+            username: connection.username().map(str::to_string),
+            skipped: conversion.skipped,
+        })
+    }
+
+    /// The stored profile with every referenced certificate and key inlined,
+    /// optionally without its private keys and secrets.
+    pub async fn export_profile(&self, vpn_id: &str, strip_secrets: bool) -> Result<ProfileExport, VpnServiceError> {
+        let config_path = self.config_path_of(vpn_id).await?;
+        let mut bundle = read_bundle(Path::new(&config_path))
+            .await
+            .map_err(|e| VpnServiceError::InvalidProfile(e.to_string()))?;
+        let stripped = if strip_secrets { bundle.strip_secrets() } else { Vec::new() };
+        let content = bundle.inlined().map_err(|e| VpnServiceError::InvalidProfile(e.to_string()))?;
+        Ok(ProfileExport {
+            vpn_id: vpn_id.to_string(),
+            content,
+            stripped,
+            unresolved: bundle.unresolved().to_vec(),
+        })
+    }
+
+    /// Exports several profiles into one archive as `<id>.ovpn` entries.
+    pub async fn export_archive(
+        &self,
+        vpn_ids: &[String],
+        path: &Path,
+        strip_secrets: bool,
+    ) -> Result<Vec<ProfileExport>, VpnServiceError> {
+        let mut exports = Vec::new();
+        for vpn_id in vpn_ids {
+            exports.push(self.export_profile(vpn_id, strip_secrets).await?);
+        }
+        let files: BTreeMap<String, Vec<u8>> = exports
+            .iter()
+            .map(|export| (format!("{}.ovpn", export.vpn_id), export.content.clone().into_bytes()))
+            .collect();
+
+        let path = path.to_path_buf();
+        tokio::task::spawn_blocking(move || write_archive(&path, &files))
+            .await
+            .map_err(|e| VpnServiceError::RepositoryError(e.to_string()))?
+            .map_err(|e| VpnServiceError::RepositoryError(e.to_string()))?;
+        Ok(exports)
+    }
+
+    /// Converts a profile into a NetworkManager keyfile. NetworkManager only
+    /// reads certificates from files, so inline blocks are written into
+    /// `files_dir` (owner-only) and referenced from there.
+    pub async fn export_nm_connection(&self, vpn_id: &str, files_dir: Option<&Path>) -> Result<NmExport, VpnServiceError> {
+        let vpn = self
+            .list_vpns()
+            .await?
+            .into_iter()
+            .find(|vpn| vpn.id() == vpn_id)
+            .ok_or_else(|| VpnServiceError::VpnNotFound(vpn_id.to_string()))?;
+        let content = tokio::fs::read_to_string(vpn.config_path())
+            .await
+            .map_err(|e| VpnServiceError::InvalidProfile(format!("cannot read {}: {}", vpn.config_path(), e)))?;
+        let config = OvpnConfig::parse(&content).map_err(|e| VpnServiceError::InvalidProfile(e.to_string()))?;
+
+        let mut files = BTreeMap::new();
+        let mut written = Vec::new();
+        let blocks: Vec<_> = config
+            .inline_blocks()
+            .filter(|block| FILE_DIRECTIVES.contains(&block.tag()) && block.tag() != "auth-user-pass")
+            .collect();
+        if !blocks.is_empty() {
+            let dir = files_dir.ok_or_else(|| {
+                VpnServiceError::InvalidProfile(format!(
+                    "{} embeds certificates; give an output path so they can be written next to the keyfile",
+                    vpn_id
+                ))
+            })?;
+            for block in blocks {
+                let path = dir.join(format!("{}-{}.pem", vpn_id, block.tag()));
+                write_private_async(&path, block.content().as_bytes())
+                    .await
+                    .map_err(|e| VpnServiceError::RepositoryError(format!("cannot write {}: {}", path.display(), e)))?;
+                files.insert(block.tag().to_string(), path.to_string_lossy().to_string());
+                written.push(path);
+            }
+        }
+
+        let uuid = Self::new_uuid();
+        let conversion = NmConnection::from_ovpn(vpn.display_name(), &uuid, &config, &files);
+        Ok(NmExport {
+            keyfile: conversion.value.render(),
+            written,
+            skipped: conversion.skipped,
+        })
+    }
+
+    /// Random (v4) UUID from the kernel's generator.
+    fn new_uuid() -> String {
+        std::fs::read_to_string("/proc/sys/kernel/random/uuid")
+            .map(|uuid| uuid.trim().to_string())
+            .unwrap_or_else(|_| {
+                let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos();
+                let hex = format!("{:032x}", nanos ^ ((std::process::id() as u128) << 64));
+                format!("{}-{}-4{}-8{}-{}", &hex[0..8], &hex[8..12], &hex[13..16], &hex[17..20], &hex[20..32])
+            })
+    }
+
+    /// Every profile in a .zip/.tar/.tar.gz provider bundle with its shared
+    /// certificates resolved. Ids come from the file names and only include
+    /// the enclosing folder when two profiles share a name.
+    pub async fn archive_profiles(&self, path: &Path) -> Result<Vec<ArchiveProfile>, VpnServiceError> {
+        let path = path.to_path_buf();
+        let archive = tokio::task::spawn_blocking(move || ProfileArchive::open(&path))
+            .await
+            .map_err(|e| VpnServiceError::InvalidProfile(e.to_string()))?
+            .map_err(|e| VpnServiceError::InvalidProfile(e.to_string()))?;
+
+        let entries = archive.profiles();
+        let stem_of = |entry: &str| Self::profile_id_for(Path::new(entry));
+        // Providers split variants into folders such as `udp/` and `tcp/`.
+        let folder_of = |entry: &str| {
+            let parent = Path::new(entry).parent().and_then(Path::file_name);
+            parent.map(|name| name.to_string_lossy().to_string()).unwrap_or_default()
+        };
+        let mut profiles = Vec::new();
+        for entry in &entries {
+            let stem = stem_of(entry);
+            let folder = folder_of(entry);
+            let shared = entries.iter().filter(|other| stem_of(other) == stem).count() > 1;
+            let (id, display_name) = if shared && !folder.is_empty() {
+                let id = Self::profile_id_for(Path::new(&format!("{}-{}.ovpn", folder, stem)));
+                (id, format!("{} ({})", Self::display_name_for(&stem), folder))
+            } else {
+                (stem.clone(), Self::display_name_for(&stem))
+            };
+
+            let (bundle, errors) = match archive.bundle(entry) {
+                Ok(bundle) => {
+                    let errors = ConfigValidator::new(None)
+                        .validate(bundle.config())
+                        .into_iter()
+                        .filter(|finding| finding.severity == Severity::Error)
+                        .collect();
+                    (Some(bundle), errors)
+                }
+                Err(e) => (None, vec![Finding::new(Severity::Error, "syntax", e.to_string(), None)]),
+            };
+            profiles.push(ArchiveProfile { entry: entry.to_string(), id, display_name, bundle, errors });
+        }
+        Ok(profiles)
+    }
+
+    /// Imports one profile listed by `archive_profiles`. Its display name is
+    /// only applied when the profile does not already have one.
+    pub async fn import_archive_profile(
+        &self,
+        profile: &ArchiveProfile,
+        mode: BundleMode,
+        replace: bool,
+    ) -> Result<ImportedProfile, VpnServiceError> {
+        let bundle = profile.bundle.as_ref().ok_or_else(|| {
+            let reason = profile.errors.first().map(|f| f.message.clone()).unwrap_or_default();
+            VpnServiceError::InvalidProfile(reason)
+        })?;
+        if replace {
+            self.ensure_not_in_use(&profile.id).await?;
+        }
+        let mut imported = self.import_bundle(&profile.id, bundle, mode, replace).await?;
+        if imported.vpn.metadata().display_name().is_none() && profile.display_name != profile.id {
+            imported.vpn = self.set_display_name(&profile.id, Some(profile.display_name.clone())).await?;
+        }
+        Ok(imported)
+    }
+
+    /// Human-friendly name from a profile file name:
+    /// `us-new_york-udp` becomes `US New York UDP`.
+    pub fn display_name_for(stem: &str) -> String {
+        stem.split(['-', '_', ' '])
+            .filter(|word| !word.is_empty())
+            .map(|word| {
+                // Country codes and protocol names read better in capitals.
+                let acronym = word.len() == 2 || ["udp", "tcp", "vpn"].contains(&word.to_lowercase().as_str());
+                if acronym && word.chars().all(|c| c.is_ascii_alphabetic()) {
+                    return word.to_uppercase();
+                }
+                let mut chars = word.chars();
+                match chars.next() {
+                    Some(first) => first.to_uppercase().chain(chars).collect(),
+                    None => String::new(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    async fn import_bundle(
+        &self,
+        id: &str,
+        bundle: &ProfileBundle,
+        mode: BundleMode,
+        replace: bool,
+    ) -> Result<ImportedProfile, VpnServiceError> {
+        let vpn = self.import_use_case.execute(id, bundle, mode, replace).await?;
+        let mut bundled: Vec<String> = bundle.files().iter().map(|file| file.reference.clone()).collect();
+        bundled.dedup();
+        Ok(ImportedProfile {
+            vpn,
+            bundled,
+            unresolved: bundle.unresolved().to_vec(),
+        })
+    }
+
+    /// Id for a profile imported from `path`: its file stem with anything
+    /// outside the id alphabet replaced by '_'.
+    pub fn profile_id_for(path: &Path) -> String {
+        let stem = path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+        let id: String = stem
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') { c } else { '_' })
+            .collect();
+        id.trim_start_matches('.').to_string()
+    }
+
+    /// Replaces the profile text; the running tunnel, if any, keeps the old
+    /// settings until it reconnects.
+    pub async fn update_profile(&self, vpn_id: &str, content: &str) -> Result<Vpn, VpnServiceError> {
+        Ok(self.update_use_case.execute(vpn_id, content).await?)
+    }
+
+    /// Validates edited profile text as if it were already saved in place,
+    /// so relative certificate paths resolve the same way.
+    pub async fn validate_profile_source(&self, vpn_id: &str, content: &str) -> Result<ValidationReport, VpnServiceError> {
+        let config_path = self.config_path_of(vpn_id).await?;
+        let findings = ConfigValidator::for_profile(Path::new(&config_path)).validate_source(content);
+        Ok(ValidationReport::new(config_path, findings))
+    }
+
+    pub async fn rename_profile(&self, vpn_id: &str, new_id: &str) -> Result<Vpn, VpnServiceError> {
+        self.ensure_not_in_use(vpn_id).await?;
+        let vpn = self.rename_use_case.execute(vpn_id, new_id).await?;
+        if let Some(store) = &self.credential_store {
+            store
+                .rename(vpn_id, new_id)
+                .await
+                .map_err(|e| VpnServiceError::RepositoryError(e.to_string()))?;
+        }
+        Ok(vpn)
+    }
+
+    pub async fn remove_profile(&self, vpn_id: &str) -> Result<Vpn, VpnServiceError> {
+        self.ensure_not_in_use(vpn_id).await?;
+        let vpn = self.remove_use_case.execute(vpn_id).await?;
+        self.forget_credentials(vpn_id).await?;
+        Ok(vpn)
+    }
+
+    /// Deletes everything saved for `vpn_id`, TOTP seed included; false when
+    /// there was nothing.
+    pub async fn forget_credentials(&self, vpn_id: &str) -> Result<bool, VpnServiceError> {
+        match &self.credential_store {
+            Some(store) => store
+                .forget(vpn_id)
+                .await
+                .map_err(|e| VpnServiceError::RepositoryError(e.to_string())),
+            None => Ok(false),
+        }
+    }
+
+    /// Stores the TOTP seed (base32 or `otpauth://` URI) used to answer
+    /// `vpn_id`'s challenges without asking. Returns the current code so the
+    /// caller can compare it with the authenticator app.
+    pub async fn set_totp_seed(&self, vpn_id: &str, seed: &str) -> Result<String, VpnServiceError> {
+        let generator = TotpGenerator::parse(seed).map_err(|e| VpnServiceError::InvalidInput(e.to_string()))?;
+        self.config_path_of(vpn_id).await?;
+        self.require_credential_store()?
+            .save_secret(vpn_id, TOTP_SECRET, seed.trim())
+            .await
+            .map_err(|e| VpnServiceError::RepositoryError(e.to_string()))?;
+        Ok(generator.code())
+    }
+
+    pub async fn remove_totp_seed(&self, vpn_id: &str) -> Result<bool, VpnServiceError> {
+        self.require_credential_store()?
+            .remove_secret(vpn_id, TOTP_SECRET)
+            .await
+            .map_err(|e| VpnServiceError::RepositoryError(e.to_string()))
+    }
+
+    fn require_credential_store(&self) -> Result<&Arc<dyn CredentialStore>, VpnServiceError> {
+        self.credential_store
+            .as_ref()
+            .ok_or_else(|| VpnServiceError::InvalidInput("no credential store is configured".to_string()))
+    }
+
+    pub async fn set_display_name(&self, vpn_id: &str, name: Option<String>) -> Result<Vpn, VpnServiceError> {
+        Ok(self.set_name_use_case.execute(vpn_id, name).await?)
+    }
+
+    /// Moving or deleting the file under a running OpenVPN would leave it
+    /// unmanageable, so those operations wait for a disconnect.
+    async fn ensure_not_in_use(&self, vpn_id: &str) -> Result<(), VpnServiceError> {
+        let vpns = self.list_vpns().await?;
+        let in_use = vpns
+            .iter()
+            .any(|vpn| vpn.id() == vpn_id && (vpn.is_connected() || vpn.is_connecting()));
+        if in_use {
+            return Err(VpnServiceError::ProfileInUse(vpn_id.to_string()));
+        }
         Ok(())
     }
 
+    pub async fn config_path_of(&self, vpn_id: &str) -> Result<String, VpnServiceError> {
+        let vpn = self
+            .vpn_repository
+            .find_by_id(vpn_id)
+            .await
+            .map_err(|e| VpnServiceError::RepositoryError(e.to_string()))?
+            .ok_or_else(|| VpnServiceError::VpnNotFound(vpn_id.to_string()))?;
+        Ok(vpn.config_path().to_string())
+    }
+
+    /// OpenVPN output captured for `vpn_id`, optionally only from `since` on.
+    pub async fn logs(&self, vpn_id: &str, since: Option<SystemTime>) -> Result<Vec<LogLine>, VpnServiceError> {
+        let name = OpenVpnService::instance_name(&self.config_path_of(vpn_id).await?);
+        self.openvpn_service
+            .log()
+            .read(&name, since)
+            .map_err(|e| VpnServiceError::RepositoryError(format!("cannot read the log of {}: {}", vpn_id, e)))
+    }
+
+    /// New output of `vpn_id` from now on, whichever process runs the tunnel.
+    pub async fn follow_logs(&self, vpn_id: &str) -> Result<LogTail, VpnServiceError> {
+        let name = OpenVpnService::instance_name(&self.config_path_of(vpn_id).await?);
+        Ok(self.openvpn_service.log().tail(&name))
+    }
+
+    async fn read_profile(config_path: &Path) -> Result<OvpnConfig, Finding> {
+        let source = tokio::fs::read_to_string(config_path).await.map_err(|e| {
+            Finding::new(Severity::Error, "unreadable", format!("cannot read profile: {}", e), None)
+        })?;
+        OvpnConfig::parse(&source)
+            .map_err(|e| Finding::new(Severity::Error, "syntax", e.to_string(), None))
+    }
+
     pub async fn get_connection_status(&self) -> Result<Vec<Vpn>, VpnServiceError> {
         let vpns = self.list_vpns().await?;
         Ok(vpns)
     }
 
-    pub async fn force_kill_all_vpns(&self) -> Result<(), VpnServiceError> {
-        // Force kill all OpenVPN processes using system commands
-        self.openvpn_service
+    pub async fn force_kill_all_vpns(&self) -> Result<Vec<StoppedInstance>, VpnServiceError> {
+        self.stop_supervising_all();
+        // Stop every OpenVPN process, escalating per process
+        let stopped = self.openvpn_service
             .force_kill_all()
             .await
             .map_err(|e| VpnServiceError::OpenVpnError(e.to_string()))?;
+        self.record_all_disconnected();
 
         // Update all VPNs status to disconnected
         let vpns = self.list_use_case
@@ -209,6 +1807,33 @@ impl VpnApplicationService {
             }
         }
 
-        Ok(())
+        Ok(stopped)
+    }
+}
+
+#[async_trait]
+impl VpnControl for VpnApplicationService {
+    async fn list_vpns(&self) -> Result<Vec<Vpn>, VpnServiceError> {
+        VpnApplicationService::list_vpns(self).await
     }
-}
\ No newline at end of file
+
+    async fn connect_vpn(&self, vpn_id: &str) -> Result<(), VpnServiceError> {
+        VpnApplicationService::connect_vpn(self, vpn_id).await
+    }
+
+    async fn disconnect_vpn(&self, vpn_id: &str) -> Result<Vec<StoppedInstance>, VpnServiceError> {
+        VpnApplicationService::disconnect_vpn(self, vpn_id).await
+    }
+
+    async fn disconnect_current(&self) -> Result<Vec<StoppedInstance>, VpnServiceError> {
+        VpnApplicationService::disconnect_current(self).await
+    }
+
+    async fn force_kill_all_vpns(&self) -> Result<Vec<StoppedInstance>, VpnServiceError> {
+        VpnApplicationService::force_kill_all_vpns(self).await
+    }
+
+    async fn tunnel_state_changed(&self) {
+        VpnApplicationService::tunnel_state_changed(self).await
+    }
+}
@@ -0,0 +1,197 @@
+use crate::domain::ovpn::{FileReference, OvpnConfig};
+use std::path::Path;
+use thiserror::Error;
+
+/// Directives holding private keys or shared secrets. Everything else a
+/// profile refers to (CA, client certificate, CRL) is public.
+pub const SECRET_DIRECTIVES: &[&str] = &[
+    "key",
+    "pkcs12",
+    "secret",
+    "tls-auth",
+    "tls-crypt",
+    "tls-crypt-v2",
+    "auth-user-pass",
+    "http-proxy-user-pass",
+    "askpass",
+];
+
+/// Of the secrets, those OpenVPN prompts for when given without a file.
+const PROMPTED_DIRECTIVES: &[&str] = &["auth-user-pass", "askpass"];
+
+/// How the files a profile refers to are kept once it is imported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BundleMode {
+    /// Copied into a directory owned by the profile.
+    #[default]
+    Copy,
+    /// Embedded as `<tag>` blocks so the profile is a single file.
+    Inline,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum BundleError {
+    #[error("'{directive}' file {path} is not text and cannot be inlined")]
+    NotText { directive: String, path: String },
+}
+
+/// A referenced file read so it can travel with the profile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundledFile {
+    pub directive: String,
+    /// Path exactly as written in the profile.
+    pub reference: String,
+    /// Name to store the file under, unique within the bundle.
+    pub name: String,
+    pub content: Vec<u8>,
+}
+
+/// A profile together with the files it refers to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileBundle {
+    config: OvpnConfig,
+    files: Vec<BundledFile>,
+    unresolved: Vec<FileReference>,
+}
+
+impl ProfileBundle {
+    pub fn new(config: OvpnConfig) -> Self {
+        Self {
+            config,
+            files: Vec::new(),
+            unresolved: Vec::new(),
+        }
+    }
+
+    pub fn config(&self) -> &OvpnConfig {
+        &self.config
+    }
+
+    pub fn files(&self) -> &[BundledFile] {
+        &self.files
+    }
+
+    /// References that could not be read and are left untouched.
+    pub fn unresolved(&self) -> &[FileReference] {
+        &self.unresolved
+    }
+
+    pub fn add_file(&mut self, directive: &str, reference: &str, content: Vec<u8>) {
+        let name = match self.files.iter().find(|file| file.reference == reference) {
+            Some(existing) => existing.name.clone(),
+            None => self.unique_name(reference),
+        };
+        self.files.push(BundledFile {
+            directive: directive.to_string(),
+            reference: reference.to_string(),
+            name,
+            content,
+        });
+    }
+
+    pub fn add_unresolved(&mut self, reference: FileReference) {
+        self.unresolved.push(reference);
+    }
+
+    /// Drops every private key and secret, whether referenced, bundled or
+    /// inline. Credential files become bare directives so OpenVPN prompts
+    /// instead. Returns the directives that were removed.
+    pub fn strip_secrets(&mut self) -> Vec<String> {
+        let mut stripped = Vec::new();
+        for &name in SECRET_DIRECTIVES {
+            let had_file = self.config.directives_named(name).any(|d| d.arg(0).is_some());
+            let had_block = self.config.remove_inline_block(name);
+            if !had_file && !had_block {
+                continue;
+            }
+            if PROMPTED_DIRECTIVES.contains(&name) {
+                self.config.set_directive(name, Vec::new());
+            } else {
+                self.config.remove_directive(name);
+            }
+            stripped.push(name.to_string());
+        }
+        self.files.retain(|file| !stripped.contains(&file.directive));
+        self.unresolved.retain(|reference| !stripped.contains(&reference.directive));
+        stripped
+    }
+
+    /// Profile text with every bundled reference pointing into `dir`.
+    pub fn relocated(&self, dir: &Path) -> String {
+        if self.files.is_empty() {
+            return self.config.render();
+        }
+        let mut config = self.config.clone();
+        for file in &self.files {
+            let path = dir.join(&file.name);
+            config.rewrite_file_reference(&file.directive, &file.reference, &path.to_string_lossy());
+        }
+        // References are absolute now; the original working directory may not exist here.
+        config.remove_directive("cd");
+        config.render()
+    }
+
+    /// Profile text with every bundled file embedded as an inline block.
+    pub fn inlined(&self) -> Result<String, BundleError> {
+        if self.files.is_empty() {
+            return Ok(self.config.render());
+        }
+        let mut config = self.config.clone();
+        for file in &self.files {
+            let content = Self::inline_text(file)?;
+            // The key direction is an argument of the file form only.
+            let direction = config
+                .directives_named(&file.directive)
+                .find(|d| d.arg(0) == Some(file.reference.as_str()))
+                .and_then(|d| d.arg(1))
+                .map(str::to_string);
+            if let Some(direction) = direction {
+                if matches!(file.directive.as_str(), "tls-auth" | "secret") && !config.has_directive("key-direction") {
+                    config.insert_directive_before(&file.directive, "key-direction", vec![direction]);
+                }
+            }
+            config.remove_directive(&file.directive);
+            config.set_inline_block(&file.directive, &content);
+        }
+        config.remove_directive("cd");
+        Ok(config.render())
+    }
+
+    fn inline_text(file: &BundledFile) -> Result<String, BundleError> {
+        // OpenVPN expects inline PKCS#12 as base64.
+        if file.directive == "pkcs12" {
+            use base64::Engine;
+            let encoded = base64::engine::general_purpose::STANDARD.encode(&file.content);
+            let mut wrapped = String::new();
+            for chunk in encoded.as_bytes().chunks(64) {
+                wrapped.push_str(std::str::from_utf8(chunk).unwrap_or_default());
+                wrapped.push('\n');
+            }
+            return Ok(wrapped);
+        }
+        String::from_utf8(file.content.clone()).map_err(|_| BundleError::NotText {
+            directive: file.directive.clone(),
+            path: file.reference.clone(),
+        })
+    }
+
+    fn unique_name(&self, reference: &str) -> String {
+        let base = Path::new(reference)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .filter(|name| !name.starts_with('.'))
+            .unwrap_or_else(|| "file".to_string());
+        let taken = |name: &str| self.files.iter().any(|file| file.name == name);
+        if !taken(&base) {
+            return base;
+        }
+        let (stem, extension) = match base.rsplit_once('.') {
+            Some((stem, extension)) => (stem.to_string(), format!(".{}", extension)),
+            None => (base.clone(), String::new()),
+        };
+        (2..)
+            .map(|n| format!("{}-{}{}", stem, n, extension))
+            .find(|name| !taken(name))
+            .unwrap_or(base)
+    }
+}
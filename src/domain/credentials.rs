@@ -0,0 +1,99 @@
+use async_trait::async_trait;
+use std::fmt;
+
+/// OpenVPN's realm for `auth-user-pass`.
+pub const AUTH_REALM: &str = "Auth";
+
+/// OpenVPN's realm for the passphrase of an encrypted client key.
+pub const KEY_REALM: &str = "Private Key";
+
+/// Stored secret holding a profile's private key passphrase.
+pub const KEY_PASSPHRASE_SECRET: &str = "key-passphrase";
+
+/// Stored secret holding a profile's TOTP seed or `otpauth://` URI.
+pub const TOTP_SECRET: &str = "totp";
+
+/// A username and password for `auth-user-pass`. The password never shows
+/// up in `Debug` output.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Credentials {
+    username: String,
+    password: String,
+}
+
+impl Credentials {
+    pub fn new(username: impl Into<String>, password: impl Into<String>) -> Self {
+        Self {
+            username: username.into(),
+            password: password.into(),
+        }
+    }
+
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credentials")
+            .field("username", &self.username)
+            .field("password", &"<redacted>")
+            .finish()
+    }
+}
+
+/// A one-time code the server asks for on top of the password.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Challenge {
+    pub text: String,
+    /// The answer may be shown while typed.
+    pub echo: bool,
+}
+
+/// What OpenVPN is waiting for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CredentialKind {
+    /// Username and password, plus an answer to `challenge` when the
+    /// profile has a `static-challenge`.
+    UsernamePassword { challenge: Option<Challenge> },
+    /// A single secret for `realm`, e.g. `Private Key`.
+    Password { realm: String },
+    /// Only the answer to a challenge; the password was already accepted.
+    ChallengeResponse { challenge: Challenge },
+}
+
+/// A question for the user while a profile connects.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CredentialRequest {
+    pub vpn_id: String,
+    pub vpn_name: String,
+    pub kind: CredentialKind,
+    /// Username to prefill, from the store or the previous attempt.
+    pub username: Option<String>,
+    /// The previous answer was rejected by the server.
+    pub retry: bool,
+}
+
+/// The user's answer to a [`CredentialRequest`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CredentialReply {
+    /// For password-only requests the username is empty; for challenge-only
+    /// requests both are.
+    pub credentials: Credentials,
+    /// The answer to the request's challenge, if it had one.
+    pub response: Option<String>,
+    /// Keep the credentials for the next connection once they are accepted.
+    pub remember: bool,
+}
+
+/// Asks the user for credentials through whatever UI is running.
+#[async_trait]
+pub trait CredentialPrompt: Send + Sync {
+    /// `None` when the user cancelled.
+    async fn ask(&self, request: &CredentialRequest) -> Option<CredentialReply>;
+}
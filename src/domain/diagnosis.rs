@@ -0,0 +1,46 @@
+use crate::domain::entities::VpnError;
+
+/// Recognizes why a connection failed from what OpenVPN printed. Several
+/// signatures often show up together (a bad certificate also fails the TLS
+/// handshake), so the most specific one found anywhere wins.
+pub fn diagnose<'a>(lines: impl IntoIterator<Item = &'a str>) -> Option<VpnError> {
+    lines
+        .into_iter()
+        .filter_map(classify)
+        .min_by_key(|(rank, _)| *rank)
+        .map(|(_, error)| error)
+}
+
+/// `(rank, error)` for a line matching a known failure; lower ranks are more
+/// specific.
+fn classify(line: &str) -> Option<(usize, VpnError)> {
+    let lower = line.to_ascii_lowercase();
+
+    if let Some(detail) = line.split_once("VERIFY ERROR: ").map(|(_, rest)| rest) {
+        // `depth=0, error=certificate has expired: CN=vpn.example.com`
+        let reason = detail.split_once("error=").map_or(detail, |(_, reason)| reason);
+        return Some((0, VpnError::CertificateVerifyFailed(reason.trim().to_string())));
+    }
+    if lower.contains("certificate verify failed") {
+        return Some((0, VpnError::CertificateVerifyFailed("the server certificate was not accepted".to_string())));
+    }
+    if line.contains("AUTH_FAILED") || line.contains("Verification Failed: 'Auth'") {
+        return Some((1, VpnError::AuthFailed));
+    }
+    if lower.contains("failed to negotiate cipher") || lower.contains("no common cipher") {
+        return Some((2, VpnError::CipherNegotiationFailed));
+    }
+    let tun_line = lower.contains("tun") && (lower.contains("tunsetiff") || lower.contains("tun/tap"));
+    if tun_line && (lower.contains("operation not permitted") || lower.contains("permission denied")) {
+        return Some((3, VpnError::TunPermissionDenied));
+    }
+    if let Some((_, rest)) = line.split_once("Cannot resolve host address: ") {
+        // `vpn.example.com:1194 (Name or service not known)`
+        let host = rest.split(" (").next().unwrap_or(rest).trim();
+        return Some((4, VpnError::HostUnresolved(host.to_string())));
+    }
+    if lower.contains("tls handshake failed") || lower.contains("tls key negotiation failed") {
+        return Some((5, VpnError::TlsHandshakeFailed));
+    }
+    None
+}
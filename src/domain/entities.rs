@@ -1,8 +1,13 @@
+use crate::domain::ovpn::OvpnConfig;
+use crate::domain::reconnect::RetryPolicy;
+use crate::domain::state_machine::TunnelState;
 use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime};
 use thiserror::Error;
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum ConnectionState {
+    #[default]
     Disconnected,
     Connecting,
     Connected,
@@ -10,9 +15,77 @@ pub enum ConnectionState {
     Error(String),
 }
 
-impl Default for ConnectionState {
-    fn default() -> Self {
-        Self::Disconnected
+/// Byte counters of a tunnel at one point in time, with the throughput since
+/// the previous sample.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrafficStats {
+    bytes_in: u64,
+    bytes_out: u64,
+    rate_in: Option<u64>,
+    rate_out: Option<u64>,
+    sampled_at: SystemTime,
+}
+
+impl TrafficStats {
+    /// First sample of a session; throughput is unknown until the next one.
+    pub fn new(bytes_in: u64, bytes_out: u64, sampled_at: SystemTime) -> Self {
+        Self {
+            bytes_in,
+            bytes_out,
+            rate_in: None,
+            rate_out: None,
+            sampled_at,
+        }
+    }
+
+    /// The sample following `self`. Counters that went backwards (a new
+    /// session on the same interface) restart the rate calculation.
+    pub fn next(&self, bytes_in: u64, bytes_out: u64, sampled_at: SystemTime) -> Self {
+        let elapsed = sampled_at.duration_since(self.sampled_at).unwrap_or_default().as_millis();
+        if elapsed == 0 {
+            return Self { bytes_in, bytes_out, ..self.clone() };
+        }
+        let rate = |previous: u64, current: u64| {
+            current.checked_sub(previous).map(|delta| (delta as u128 * 1000 / elapsed) as u64)
+        };
+        Self {
+            bytes_in,
+            bytes_out,
+            rate_in: rate(self.bytes_in, bytes_in),
+            rate_out: rate(self.bytes_out, bytes_out),
+            sampled_at,
+        }
+    }
+
+    /// Restores rates computed elsewhere, e.g. by another process.
+    pub fn with_rates(mut self, rate_in: Option<u64>, rate_out: Option<u64>) -> Self {
+        self.rate_in = rate_in;
+        self.rate_out = rate_out;
+        self
+    }
+
+    /// Bytes received from the server.
+    pub fn bytes_in(&self) -> u64 {
+        self.bytes_in
+    }
+
+    /// Bytes sent to the server.
+    pub fn bytes_out(&self) -> u64 {
+        self.bytes_out
+    }
+
+    /// Download throughput in bytes per second.
+    pub fn rate_in(&self) -> Option<u64> {
+        self.rate_in
+    }
+
+    /// Upload throughput in bytes per second.
+    pub fn rate_out(&self) -> Option<u64> {
+        self.rate_out
+    }
+
+    pub fn sampled_at(&self) -> SystemTime {
+        self.sampled_at
     }
 }
 
@@ -20,13 +93,21 @@ impl Default for ConnectionState {
 pub struct VpnStatus {
     state: ConnectionState,
     ip_address: String,
-    connected_since: Option<std::time::SystemTime>,
+    connected_since: Option<SystemTime>,
+    tunnel_state: Option<TunnelState>,
+    ipv6_address: Option<String>,
+    remote_address: Option<String>,
+    remote_port: Option<u16>,
+    interface: Option<String>,
+    gateway: Option<String>,
+    traffic: Option<TrafficStats>,
+    reconnect_attempt: Option<u32>,
 }
 
 impl VpnStatus {
     pub fn new(state: ConnectionState, ip_address: String) -> Self {
         let connected_since = match state {
-            ConnectionState::Connected => Some(std::time::SystemTime::now()),
+            ConnectionState::Connected => Some(SystemTime::now()),
             _ => None,
         };
 
@@ -34,9 +115,17 @@ impl VpnStatus {
             state,
             ip_address,
             connected_since,
+            ..Self::default()
         }
     }
 
+    /// Status derived from the detailed state OpenVPN reported.
+    pub fn from_tunnel_state(tunnel_state: TunnelState, ip_address: String) -> Self {
+        let mut status = Self::new(tunnel_state.connection_state(), ip_address);
+        status.tunnel_state = Some(tunnel_state);
+        status
+    }
+
     pub fn state(&self) -> &ConnectionState {
         &self.state
     }
@@ -45,21 +134,127 @@ impl VpnStatus {
         &self.ip_address
     }
 
-    pub fn connected_since(&self) -> Option<std::time::SystemTime> {
+    pub fn connected_since(&self) -> Option<SystemTime> {
         self.connected_since
     }
 
+    /// Handshake phase, when the tunnel is tracked through the management interface.
+    pub fn tunnel_state(&self) -> Option<&TunnelState> {
+        self.tunnel_state.as_ref()
+    }
+
+    pub fn ipv6_address(&self) -> Option<&str> {
+        self.ipv6_address.as_deref()
+    }
+
+    /// Address of the OpenVPN server actually in use (after `remote-random`
+    /// and DNS resolution).
+    pub fn remote_address(&self) -> Option<&str> {
+        self.remote_address.as_deref()
+    }
+
+    pub fn remote_port(&self) -> Option<u16> {
+        self.remote_port
+    }
+
+    /// tun/tap device name, e.g. `tun0`.
+    pub fn interface(&self) -> Option<&str> {
+        self.interface.as_deref()
+    }
+
+    /// Gateway pushed by the server (`route-gateway`, or the peer address
+    /// with `topology net30`/`p2p`).
+    pub fn gateway(&self) -> Option<&str> {
+        self.gateway.as_deref()
+    }
+
+    pub fn traffic(&self) -> Option<&TrafficStats> {
+        self.traffic.as_ref()
+    }
+
+    /// Which automatic reconnect attempt is under way after the tunnel
+    /// dropped, or how many were made before giving up.
+    pub fn reconnect_attempt(&self) -> Option<u32> {
+        self.reconnect_attempt
+    }
+
+    /// Time since the tunnel came up, while it is connected.
+    pub fn session_duration(&self) -> Option<Duration> {
+        let since = self.connected_since.filter(|_| self.state == ConnectionState::Connected)?;
+        Some(SystemTime::now().duration_since(since).unwrap_or_default())
+    }
+
     pub fn set_state(&mut self, state: ConnectionState) {
         self.connected_since = match state {
-            ConnectionState::Connected => Some(std::time::SystemTime::now()),
+            ConnectionState::Connected => Some(SystemTime::now()),
             _ => None,
         };
+        if !matches!(state, ConnectionState::Connected | ConnectionState::Connecting) {
+            self.clear_network_details();
+        }
         self.state = state;
+        self.tunnel_state = None;
+    }
+
+    pub fn set_tunnel_state(&mut self, tunnel_state: TunnelState) {
+        let was_connected = self.state == ConnectionState::Connected;
+        self.state = tunnel_state.connection_state();
+        if self.state != ConnectionState::Connected {
+            self.connected_since = None;
+        } else if !was_connected {
+            self.connected_since = Some(SystemTime::now());
+            self.reconnect_attempt = None;
+        }
+        if !tunnel_state.is_active() {
+            self.clear_network_details();
+        }
+        self.tunnel_state = Some(tunnel_state);
+    }
+
+    /// Overrides the local clock reading taken on the transition, e.g. with
+    /// the time OpenVPN itself reported.
+    pub fn set_connected_since(&mut self, since: Option<SystemTime>) {
+        self.connected_since = since;
     }
 
     pub fn set_ip_address(&mut self, ip: String) {
         self.ip_address = ip;
     }
+
+    pub fn set_ipv6_address(&mut self, ip: Option<String>) {
+        self.ipv6_address = ip;
+    }
+
+    pub fn set_remote(&mut self, address: Option<String>, port: Option<u16>) {
+        self.remote_address = address;
+        self.remote_port = port;
+    }
+
+    pub fn set_interface(&mut self, interface: Option<String>) {
+        self.interface = interface;
+    }
+
+    pub fn set_gateway(&mut self, gateway: Option<String>) {
+        self.gateway = gateway;
+    }
+
+    pub fn set_traffic(&mut self, traffic: Option<TrafficStats>) {
+        self.traffic = traffic;
+    }
+
+    pub fn set_reconnect_attempt(&mut self, attempt: Option<u32>) {
+        self.reconnect_attempt = attempt;
+    }
+
+    fn clear_network_details(&mut self) {
+        self.ip_address.clear();
+        self.ipv6_address = None;
+        self.remote_address = None;
+        self.remote_port = None;
+        self.interface = None;
+        self.gateway = None;
+        self.traffic = None;
+    }
 }
 
 impl Default for VpnStatus {
@@ -68,6 +263,14 @@ impl Default for VpnStatus {
             state: ConnectionState::Disconnected,
             ip_address: String::new(),
             connected_since: None,
+            tunnel_state: None,
+            ipv6_address: None,
+            remote_address: None,
+            remote_port: None,
+            interface: None,
+            gateway: None,
+            traffic: None,
+            reconnect_attempt: None,
         }
     }
 }
@@ -76,12 +279,145 @@ impl Default for VpnStatus {
 pub enum VpnError {
     #[error("VPN ID cannot be empty")]
     EmptyId,
+    #[error("Invalid VPN ID '{0}': use letters, digits, '.', '-' and '_' only")]
+    InvalidId(String),
     #[error("Config path cannot be empty")]
     EmptyConfigPath,
     #[error("Invalid config file: {0}")]
     InvalidConfigFile(String),
     #[error("Connection failed: {0}")]
     ConnectionFailed(String),
+    #[error("Authentication failed: the server rejected the credentials")]
+    AuthFailed,
+    #[error("TLS handshake with the server failed")]
+    TlsHandshakeFailed,
+    #[error("Certificate verification failed: {0}")]
+    CertificateVerifyFailed(String),
+    #[error("Cannot resolve the server address {0}")]
+    HostUnresolved(String),
+    #[error("Not allowed to create the tun device")]
+    TunPermissionDenied,
+    #[error("No data cipher in common with the server")]
+    CipherNegotiationFailed,
+}
+
+impl VpnError {
+    /// What the user can do about a connection failure.
+    pub fn hint(&self) -> Option<&'static str> {
+        let hint = match self {
+            Self::AuthFailed => {
+                "check the username and password (`ui-openvpn forget <id>` drops saved ones); with two-factor auth, use a fresh code"
+            }
+            Self::TlsHandshakeFailed => {
+                "the server did not complete the handshake: check the remote host and port, udp vs tcp, firewalls, and the tls-auth/tls-crypt key"
+            }
+            Self::CertificateVerifyFailed(_) => {
+                "make sure the profile's CA belongs to this server, the system clock is right and no certificate has expired"
+            }
+            Self::HostUnresolved(_) => "check the `remote` lines of the profile and that DNS works on this machine",
+            Self::TunPermissionDenied => {
+                "OpenVPN needs root to create the tunnel: accept the pkexec/sudo prompt and check that /dev/net/tun exists (`modprobe tun`)"
+            }
+            Self::CipherNegotiationFailed => {
+                "add the server's cipher to `data-ciphers`; `ui-openvpn validate <id> --lint` suggests a modern list"
+            }
+            _ => return None,
+        };
+        Some(hint)
+    }
+}
+
+/// Per-profile behaviour overrides.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileOptions {
+    /// Seconds to wait for `CONNECTED` before giving up.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connect_timeout_secs: Option<u64>,
+    /// What to do when the tunnel drops; `None` uses the default policy.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reconnect: Option<RetryPolicy>,
+    /// No other tunnel may run alongside this profile's.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub exclusive: bool,
+}
+
+impl ProfileOptions {
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.reconnect.clone().unwrap_or_default()
+    }
+}
+
+/// What the user knows about a profile beyond its .ovpn file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileMetadata {
+    display_name: Option<String>,
+    description: Option<String>,
+    tags: Vec<String>,
+    favorite: bool,
+    last_used: Option<SystemTime>,
+    options: ProfileOptions,
+}
+
+impl ProfileMetadata {
+    /// Name chosen by the user; `None` falls back to the profile id.
+    pub fn display_name(&self) -> Option<&str> {
+        self.display_name.as_deref()
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    pub fn is_favorite(&self) -> bool {
+        self.favorite
+    }
+
+    pub fn last_used(&self) -> Option<SystemTime> {
+        self.last_used
+    }
+
+    pub fn options(&self) -> &ProfileOptions {
+        &self.options
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    pub fn set_display_name(&mut self, name: Option<String>) {
+        self.display_name = name.filter(|name| !name.trim().is_empty());
+    }
+
+    pub fn set_description(&mut self, description: Option<String>) {
+        self.description = description.filter(|description| !description.trim().is_empty());
+    }
+
+    /// Replaces the tags, dropping blanks and duplicates.
+    pub fn set_tags(&mut self, tags: Vec<String>) {
+        let mut unique: Vec<String> = Vec::new();
+        for tag in tags.into_iter().map(|tag| tag.trim().to_string()) {
+            if !tag.is_empty() && !unique.contains(&tag) {
+                unique.push(tag);
+            }
+        }
+        self.tags = unique;
+    }
+
+    pub fn set_favorite(&mut self, favorite: bool) {
+        self.favorite = favorite;
+    }
+
+    pub fn set_last_used(&mut self, last_used: Option<SystemTime>) {
+        self.last_used = last_used;
+    }
+
+    pub fn set_options(&mut self, options: ProfileOptions) {
+        self.options = options;
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -90,6 +426,8 @@ pub struct Vpn {
     display_name: String,
     config_path: String,
     status: VpnStatus,
+    config: Option<OvpnConfig>,
+    metadata: ProfileMetadata,
 }
 
 impl Vpn {
@@ -99,9 +437,21 @@ impl Vpn {
             display_name,
             config_path,
             status: VpnStatus::default(),
+            config: None,
+            metadata: ProfileMetadata::default(),
         }
     }
 
+    /// Attaches stored metadata; a display name recorded there wins over the
+    /// one given to [`Self::new`].
+    pub fn with_metadata(mut self, metadata: ProfileMetadata) -> Self {
+        if let Some(name) = metadata.display_name() {
+            self.display_name = name.to_string();
+        }
+        self.metadata = metadata;
+        self
+    }
+
     pub fn try_new(id: String, display_name: String, config_path: String) -> Result<Self, VpnError> {
         if id.trim().is_empty() {
             return Err(VpnError::EmptyId);
@@ -114,6 +464,19 @@ impl Vpn {
         Ok(Self::new(id, display_name, config_path))
     }
 
+    /// Ids double as file names, so they are restricted to a portable,
+    /// shell-friendly character set and may not be hidden files.
+    pub fn validate_id(id: &str) -> Result<(), VpnError> {
+        if id.trim().is_empty() {
+            return Err(VpnError::EmptyId);
+        }
+        let portable = id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+        if !portable || id.starts_with('.') || id.len() > 128 {
+            return Err(VpnError::InvalidId(id.to_string()));
+        }
+        Ok(())
+    }
+
     pub fn id(&self) -> &str {
         &self.id
     }
@@ -130,6 +493,25 @@ impl Vpn {
         &self.status
     }
 
+    /// Parsed profile contents, when the repository was able to read them.
+    pub fn config(&self) -> Option<&OvpnConfig> {
+        self.config.as_ref()
+    }
+
+    pub fn set_config(&mut self, config: Option<OvpnConfig>) {
+        self.config = config;
+    }
+
+    pub fn metadata(&self) -> &ProfileMetadata {
+        &self.metadata
+    }
+
+    /// Replaces the metadata. Clearing its display name falls back to the id.
+    pub fn set_metadata(&mut self, metadata: ProfileMetadata) {
+        self.display_name = metadata.display_name().unwrap_or(&self.id).to_string();
+        self.metadata = metadata;
+    }
+
     pub fn update_status(&mut self, status: VpnStatus) {
         self.status = status;
     }
@@ -145,4 +527,10 @@ impl Vpn {
     pub fn is_disconnected(&self) -> bool {
         matches!(self.status.state, ConnectionState::Disconnected)
     }
+
+    /// Whether `other` has to go down before `self` comes up: either one
+    /// being exclusive rules out running both.
+    pub fn conflicts_with(&self, other: &Vpn) -> bool {
+        self.id != other.id && (self.metadata.options().exclusive || other.metadata.options().exclusive)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,160 @@
+use crate::domain::ovpn::{Directive, OvpnConfig};
+use crate::domain::validation::{Finding, Severity};
+
+/// AEAD ciphers every OpenVPN 2.5+ server negotiates.
+const MODERN_DATA_CIPHERS: &str = "AES-256-GCM:AES-128-GCM:CHACHA20-POLY1305";
+
+/// 64-bit block ciphers vulnerable to SWEET32 and dropped from OpenSSL 3 defaults.
+const WEAK_CIPHERS: &[&str] = &["BF-CBC", "DES-CBC", "DES-EDE-CBC", "DES-EDE3-CBC", "CAST5-CBC", "RC2-CBC", "DESX-CBC"];
+
+/// Flags directives that OpenVPN 2.6 deprecates or that weaken the tunnel,
+/// and produces a corrected copy of the profile.
+pub struct ProfileLinter;
+
+impl ProfileLinter {
+    pub fn lint(config: &OvpnConfig) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        for directive in config.directives() {
+            if let Some(finding) = Self::check(config, directive) {
+                findings.push(finding);
+            }
+        }
+
+        findings
+    }
+
+    /// Returns a copy of `config` with every fixable finding applied. Lines
+    /// that need no change are preserved exactly.
+    pub fn fix(config: &OvpnConfig) -> OvpnConfig {
+        let mut fixed = config.clone();
+
+        if fixed.has_directive("comp-lzo") || fixed.directives_named("compress").any(Self::is_compressing) {
+            fixed.remove_directive("comp-lzo");
+            fixed.remove_directive("compress");
+        }
+
+        if fixed.has_directive("ncp-ciphers") {
+            if fixed.has_directive("data-ciphers") {
+                fixed.remove_directive("ncp-ciphers");
+            } else {
+                fixed.rename_directive("ncp-ciphers", "data-ciphers");
+            }
+        }
+
+        if !fixed.has_directive("data-ciphers") && !fixed.has_directive("secret") {
+            if let Some(cipher) = fixed.directive("cipher").and_then(|d| d.arg(0)).map(str::to_string) {
+                let is_modern = MODERN_DATA_CIPHERS.split(':').any(|c| c.eq_ignore_ascii_case(&cipher));
+                fixed.insert_directive_before("cipher", "data-ciphers", vec![MODERN_DATA_CIPHERS.to_string()]);
+                if Self::is_weak_cipher(&cipher) || is_modern {
+                    fixed.remove_directive("cipher");
+                } else {
+                    // Keeps talking to servers that predate cipher negotiation.
+                    fixed.rename_directive("cipher", "data-ciphers-fallback");
+                }
+            }
+        }
+
+        if let Some(directive) = fixed.directive("tls-version-min") {
+            if Self::is_outdated_tls(directive) {
+                let mut args = directive.args().to_vec();
+                args[0] = "1.2".to_string();
+                fixed.set_directive("tls-version-min", args);
+            }
+        }
+
+        if fixed.directive("auth").is_some_and(Self::is_weak_auth) {
+            fixed.set_directive("auth", vec!["SHA256".to_string()]);
+        }
+
+        if fixed.directive("script-security").and_then(|d| d.arg(0)) == Some("3") {
+            fixed.set_directive("script-security", vec!["2".to_string()]);
+        }
+
+        fixed
+    }
+
+    fn check(config: &OvpnConfig, directive: &Directive) -> Option<Finding> {
+        let line = Some(directive.line());
+        let finding = match directive.name() {
+            "comp-lzo" => Finding::new(
+                Severity::Warning,
+                "deprecated-compression",
+                "'comp-lzo' is deprecated since OpenVPN 2.4 and will be removed".to_string(),
+                line,
+            )
+            .with_hint("Compression over an encrypted tunnel enables VORACLE-style attacks. Remove the line; OpenVPN 2.6 still accepts compressed packets from old servers by default (allow-compression asym)."),
+            "compress" if Self::is_compressing(directive) => Finding::new(
+                Severity::Warning,
+                "deprecated-compression",
+                "'compress' enables data channel compression, which OpenVPN 2.6 disables by default".to_string(),
+                line,
+            )
+            .with_hint("Compression leaks plaintext length information (VORACLE). Remove the line unless the server strictly requires it."),
+            "ncp-ciphers" => Finding::new(
+                Severity::Warning,
+                "deprecated-option",
+                "'ncp-ciphers' was renamed to 'data-ciphers' in OpenVPN 2.5".to_string(),
+                line,
+            )
+            .with_hint("Rename the directive to 'data-ciphers'; the value format is unchanged."),
+            "cipher" if !config.has_directive("data-ciphers") && !config.has_directive("secret") => {
+                let cipher = directive.arg(0).unwrap_or("");
+                let (severity, message) = if Self::is_weak_cipher(cipher) {
+                    (Severity::Error, format!("'cipher {}' is a 64-bit block cipher that OpenVPN 2.6 rejects", cipher))
+                } else {
+                    (Severity::Warning, format!("'cipher {}' without 'data-ciphers' is ignored for negotiation in OpenVPN 2.6", cipher))
+                };
+                Finding::new(severity, "cipher-without-data-ciphers", message, line).with_hint(
+                    "Since 2.5 the data channel cipher is negotiated from 'data-ciphers'. Use 'data-ciphers AES-256-GCM:AES-128-GCM:CHACHA20-POLY1305' and keep the old cipher as 'data-ciphers-fallback' only if the server is older than 2.4.",
+                )
+            }
+            "tls-version-min" if Self::is_outdated_tls(directive) => Finding::new(
+                Severity::Warning,
+                "outdated-tls",
+                format!("'tls-version-min {}' allows TLS versions older than 1.2", directive.arg(0).unwrap_or("")),
+                line,
+            )
+            .with_hint("TLS 1.0 and 1.1 are deprecated (RFC 8996) and OpenVPN 2.6 defaults to 1.2. Raise the minimum to 1.2."),
+            "auth" if Self::is_weak_auth(directive) => {
+                let digest = directive.arg(0).unwrap_or("");
+                let severity = if digest.eq_ignore_ascii_case("SHA1") { Severity::Warning } else { Severity::Error };
+                Finding::new(
+                    severity,
+                    "weak-auth-digest",
+                    format!("'auth {}' uses a broken or missing HMAC digest", digest),
+                    line,
+                )
+                .with_hint("MD5 and SHA1 have practical collision attacks. Use 'auth SHA256' or newer (AEAD ciphers ignore it for data packets, but the TLS control channel still uses it with tls-auth).")
+            }
+            "script-security" if directive.arg(0) == Some("3") => Finding::new(
+                Severity::Warning,
+                "script-security",
+                "'script-security 3' passes passwords to scripts through environment variables".to_string(),
+                line,
+            )
+            .with_hint("Level 2 is enough to run up/down scripts. Only use 3 when a script really needs the password, and review that script."),
+            _ => return None,
+        };
+        Some(finding)
+    }
+
+    fn is_compressing(directive: &Directive) -> bool {
+        // `compress` alone or with `stub`/`stub-v2`/`migrate` only frames packets.
+        !matches!(directive.arg(0), None | Some("stub") | Some("stub-v2") | Some("migrate"))
+    }
+
+    fn is_weak_cipher(cipher: &str) -> bool {
+        WEAK_CIPHERS.iter().any(|weak| weak.eq_ignore_ascii_case(cipher))
+    }
+
+    fn is_outdated_tls(directive: &Directive) -> bool {
+        matches!(directive.arg(0), Some("1.0") | Some("1.1"))
+    }
+
+    fn is_weak_auth(directive: &Directive) -> bool {
+        directive
+            .arg(0)
+            .is_some_and(|digest| ["MD5", "SHA1", "none"].iter().any(|weak| weak.eq_ignore_ascii_case(digest)))
+    }
+}
@@ -1,3 +1,13 @@
 pub mod entities;
 pub mod use_cases;
-pub mod repositories;
\ No newline at end of file
+pub mod repositories;
+pub mod ovpn;
+pub mod validation;
+pub mod lint;
+pub mod state_machine;
+pub mod reconnect;
+pub mod bundle;
+pub mod networkmanager;
+pub mod credentials;
+pub mod totp;
+pub mod diagnosis;
\ No newline at end of file
@@ -0,0 +1,565 @@
+use crate::domain::ovpn::{OvpnConfig, INLINE_FILE};
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+/// `service-type` of connections handled by the NetworkManager OpenVPN plugin.
+pub const NM_OPENVPN_SERVICE: &str = "org.freedesktop.NetworkManager.openvpn";
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum NmKeyfileError {
+    #[error("line {line}: expected 'key=value' or '[section]'")]
+    Syntax { line: usize },
+    #[error("not a VPN connection (type={0})")]
+    NotVpn(String),
+    #[error("VPN service '{0}' is not OpenVPN")]
+    NotOpenVpn(String),
+    #[error("connection has no [vpn] section")]
+    MissingVpnSection,
+}
+
+/// A converted profile plus everything that did not carry over.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NmConversion<T> {
+    pub value: T,
+    /// Keys or directives with no equivalent on the other side.
+    pub skipped: Vec<String>,
+}
+
+/// An OpenVPN connection as NetworkManager stores it in
+/// `system-connections/*.nmconnection`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct NmConnection {
+    name: String,
+    uuid: Option<String>,
+    data: BTreeMap<String, String>,
+    never_default: bool,
+}
+
+/// Directives implied by NetworkManager's own handling that need no key.
+const IMPLIED_DIRECTIVES: &[&str] = &[
+    "client", "tls-client", "pull", "nobind", "persist-key", "persist-tun", "resolv-retry", "verb", "mute",
+    "auth-nocache", "auth-retry", "script-security", "key-direction", "setenv", "mute-replay-warnings",
+    "redirect-gateway", "explicit-exit-notify", "remote-cert-eku",
+];
+
+/// Plain directive ↔ key pairs whose value is copied verbatim.
+const SIMPLE_OPTIONS: &[(&str, &str)] = &[
+    ("cipher", "cipher"),
+    ("data-ciphers", "data-ciphers"),
+    ("data-ciphers-fallback", "data-ciphers-fallback"),
+    ("auth", "auth"),
+    ("tls-cipher", "tls-cipher"),
+    ("tls-version-min", "tls-version-min"),
+    ("tls-version-max", "tls-version-max"),
+    ("remote-cert-tls", "remote-cert-tls"),
+    ("ns-cert-type", "ns-cert-type"),
+    ("tun-mtu", "tunnel-mtu"),
+    ("fragment", "fragment-size"),
+    ("reneg-sec", "reneg-seconds"),
+    ("ping", "ping"),
+    ("ping-exit", "ping-exit"),
+    ("ping-restart", "ping-restart"),
+    ("connect-timeout", "connect-timeout"),
+    ("keysize", "keysize"),
+    ("mtu-disc", "mtu-disc"),
+    ("max-routes", "max-routes"),
+    ("allow-compression", "allow-compression"),
+];
+
+/// Flags NetworkManager stores as `key=yes`.
+const FLAG_OPTIONS: &[(&str, &str)] = &[
+    ("float", "float"),
+    ("remote-random", "remote-random"),
+    ("push-peer-info", "push-peer-info"),
+];
+
+/// NetworkManager bookkeeping keys without an OpenVPN meaning.
+const IGNORED_KEYS: &[&str] = &[
+    "service-type", "username", "password", "cert-pass", "password-flags", "cert-pass-flags",
+    "http-proxy-password-flags", "challenge-response-flags",
+];
+
+impl NmConnection {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn uuid(&self) -> Option<&str> {
+        self.uuid.as_deref()
+    }
+
+    /// The `[vpn]` data items, e.g. `remote` or `connection-type`.
+    pub fn data(&self) -> &BTreeMap<String, String> {
+        &self.data
+    }
+
+    pub fn username(&self) -> Option<&str> {
+        self.data.get("username").map(String::as_str)
+    }
+
+    pub fn parse(input: &str) -> Result<Self, NmKeyfileError> {
+        let sections = Self::parse_keyfile(input)?;
+        let empty = BTreeMap::new();
+        let connection = sections.get("connection").unwrap_or(&empty);
+
+        let kind = connection.get("type").cloned().unwrap_or_default();
+        if kind != "vpn" {
+            return Err(NmKeyfileError::NotVpn(kind));
+        }
+        let data = sections.get("vpn").cloned().ok_or(NmKeyfileError::MissingVpnSection)?;
+        let service = data.get("service-type").cloned().unwrap_or_default();
+        if !service.ends_with(".openvpn") {
+            return Err(NmKeyfileError::NotOpenVpn(service));
+        }
+
+        let never_default = sections
+            .get("ipv4")
+            .and_then(|ipv4| ipv4.get("never-default"))
+            .is_some_and(|value| value == "true");
+        Ok(Self {
+            name: connection.get("id").cloned().unwrap_or_default(),
+            uuid: connection.get("uuid").cloned(),
+            data,
+            never_default,
+        })
+    }
+
+    /// Keyfile text with the `[vpn-secrets]` section left out on purpose:
+    /// passwords are asked for by NetworkManager's agent instead.
+    pub fn render(&self) -> String {
+        let mut out = String::from("[connection]\n");
+        out.push_str(&format!("id={}\n", Self::escape(&self.name)));
+        if let Some(uuid) = &self.uuid {
+            out.push_str(&format!("uuid={}\n", uuid));
+        }
+        out.push_str("type=vpn\nautoconnect=false\n\n[vpn]\n");
+        for (key, value) in &self.data {
+            out.push_str(&format!("{}={}\n", key, Self::escape(value)));
+        }
+        out.push_str("\n[ipv4]\nmethod=auto\n");
+        if self.never_default {
+            out.push_str("never-default=true\n");
+        }
+        out.push_str("\n[ipv6]\naddr-gen-mode=default\nmethod=auto\n");
+        out
+    }
+
+    /// Converts to an `.ovpn` client profile. File paths are kept as
+    /// NetworkManager stored them.
+    pub fn to_ovpn(&self) -> NmConversion<OvpnConfig> {
+        let mut lines = vec![format!("# Imported from NetworkManager connection \"{}\"", self.name)];
+        let mut used: Vec<&str> = IGNORED_KEYS.to_vec();
+        let get = |key: &str| self.data.get(key).map(String::as_str).filter(|value| !value.is_empty());
+        let connection_type = get("connection-type").unwrap_or("tls");
+        used.push("connection-type");
+
+        if connection_type != "static-key" {
+            lines.push("client".to_string());
+        }
+        let dev_type = get("dev-type").unwrap_or(if get("tap-dev") == Some("yes") { "tap" } else { "tun" });
+        lines.push(format!("dev {}", get("dev").unwrap_or(dev_type)));
+        if get("dev").is_some() {
+            lines.push(format!("dev-type {}", dev_type));
+        }
+        used.extend(["dev", "dev-type", "tap-dev"]);
+
+        let tcp = get("proto-tcp") == Some("yes");
+        lines.push(format!("proto {}", if tcp { "tcp-client" } else { "udp" }));
+        let default_port = get("port").unwrap_or("1194");
+        for remote in get("remote").unwrap_or_default().split([',', ' ']).filter(|r| !r.is_empty()) {
+            lines.push(format!("remote {}", Self::ovpn_remote(remote, default_port)));
+        }
+        used.extend(["proto-tcp", "port", "remote"]);
+
+        let quoted = Self::quote;
+        let tls = matches!(connection_type, "tls" | "password-tls");
+        if let Some(ca) = get("ca") {
+            lines.push(format!("ca {}", quoted(ca)));
+        }
+        if tls {
+            match (get("cert"), get("key")) {
+                (Some(cert), Some(key)) if cert == key => lines.push(format!("pkcs12 {}", quoted(cert))),
+                (cert, key) => {
+                    if let Some(cert) = cert {
+                        lines.push(format!("cert {}", quoted(cert)));
+                    }
+                    if let Some(key) = key {
+                        lines.push(format!("key {}", quoted(key)));
+                    }
+                }
+            }
+        }
+        if matches!(connection_type, "password" | "password-tls") {
+            lines.push("auth-user-pass".to_string());
+        }
+        used.extend(["ca", "cert", "key"]);
+
+        if connection_type == "static-key" {
+            if let Some(key) = get("static-key") {
+                let direction = get("static-key-direction").map(|d| format!(" {}", d)).unwrap_or_default();
+                lines.push(format!("secret {}{}", quoted(key), direction));
+            }
+            if let (Some(local), Some(remote)) = (get("local-ip"), get("remote-ip")) {
+                lines.push(format!("ifconfig {} {}", local, remote));
+            }
+        }
+        used.extend(["static-key", "static-key-direction", "local-ip", "remote-ip"]);
+
+        if let Some(ta) = get("ta") {
+            let direction = get("ta-dir").map(|d| format!(" {}", d)).unwrap_or_default();
+            lines.push(format!("tls-auth {}{}", quoted(ta), direction));
+        }
+        for name in ["tls-crypt", "tls-crypt-v2"] {
+            if let Some(path) = get(name) {
+                lines.push(format!("{} {}", name, quoted(path)));
+            }
+        }
+        if let Some(crl) = get("crl-verify-file") {
+            lines.push(format!("crl-verify {}", quoted(crl)));
+        } else if let Some(crl) = get("crl-verify-dir") {
+            lines.push(format!("crl-verify {} dir", quoted(crl)));
+        }
+        used.extend(["ta", "ta-dir", "tls-crypt", "tls-crypt-v2", "crl-verify-file", "crl-verify-dir"]);
+
+        if let Some(verify) = get("verify-x509-name") {
+            // Stored as `type:name`; OpenVPN takes `name type`.
+            match verify.split_once(':') {
+                Some((kind, name)) => lines.push(format!("verify-x509-name {} {}", quoted(name), kind)),
+                None => lines.push(format!("verify-x509-name {}", quoted(verify))),
+            }
+        }
+        used.push("verify-x509-name");
+
+        match get("comp-lzo") {
+            Some("no-by-default") | Some("no") => lines.push("comp-lzo no".to_string()),
+            Some(mode) => lines.push(format!("comp-lzo {}", mode)),
+            None => {}
+        }
+        match get("compress") {
+            Some("yes") => lines.push("compress".to_string()),
+            Some(algorithm) => lines.push(format!("compress {}", algorithm)),
+            None => {}
+        }
+        match get("mssfix") {
+            Some("yes") => lines.push("mssfix".to_string()),
+            Some(value) => lines.push(format!("mssfix {}", value)),
+            None => {}
+        }
+        used.extend(["comp-lzo", "compress", "mssfix"]);
+
+        for (directive, key) in SIMPLE_OPTIONS {
+            if let Some(value) = get(key) {
+                lines.push(format!("{} {}", directive, value));
+            }
+            used.push(key);
+        }
+        for (directive, key) in FLAG_OPTIONS {
+            if get(key) == Some("yes") {
+                lines.push(directive.to_string());
+            }
+            used.push(key);
+        }
+
+        if let (Some(kind), Some(server)) = (get("proxy-type"), get("proxy-server")) {
+            let port = get("proxy-port").unwrap_or("8080");
+            let directive = if kind == "socks" { "socks-proxy" } else { "http-proxy" };
+            lines.push(format!("{} {} {}", directive, server, port));
+            if get("proxy-retry") == Some("yes") {
+                lines.push(format!("{}-retry", directive));
+            }
+        }
+        used.extend(["proxy-type", "proxy-server", "proxy-port", "proxy-retry"]);
+
+        if self.never_default {
+            lines.push("pull-filter ignore redirect-gateway".to_string());
+        }
+        lines.extend(["nobind", "persist-key", "persist-tun"].map(str::to_string));
+
+        let skipped = self
+            .data
+            .keys()
+            .filter(|key| !used.contains(&key.as_str()) && !key.ends_with("-flags"))
+            .cloned()
+            .collect();
+        let text = lines.join("\n") + "\n";
+        // Every line was produced above from quoted values, so parsing cannot fail.
+        let config = OvpnConfig::parse(&text).unwrap_or_default();
+        NmConversion { value: config, skipped }
+    }
+
+    /// Builds a NetworkManager connection from an `.ovpn` profile. Inline
+    /// blocks have no keyfile form, so `files` maps each embedded `<tag>` to
+    /// the path it was written to.
+    pub fn from_ovpn(
+        name: &str,
+        uuid: &str,
+        config: &OvpnConfig,
+        files: &BTreeMap<String, String>,
+    ) -> NmConversion<NmConnection> {
+        let mut data = BTreeMap::new();
+        let mut skipped = Vec::new();
+        data.insert("service-type".to_string(), NM_OPENVPN_SERVICE.to_string());
+
+        let file_of = |tag: &str| -> Option<String> {
+            config
+                .directive(tag)
+                .and_then(|d| d.arg(0))
+                .filter(|path| *path != INLINE_FILE)
+                .map(str::to_string)
+                .or_else(|| files.get(tag).cloned())
+        };
+
+        let proto = config.directive("proto").and_then(|d| d.arg(0)).unwrap_or("udp");
+        let port = config.directive("port").and_then(|d| d.arg(0));
+        let remotes: Vec<String> = config
+            .directives_named("remote")
+            .filter_map(|remote| {
+                let host = remote.arg(0)?;
+                let port = remote.arg(1).or(port).unwrap_or("1194");
+                Some(match remote.arg(2) {
+                    Some(proto) => format!("{}:{}:{}", host, port, proto),
+                    None => format!("{}:{}", host, port),
+                })
+            })
+            .collect();
+        if !remotes.is_empty() {
+            data.insert("remote".to_string(), remotes.join(", "));
+        }
+        if proto.starts_with("tcp") {
+            data.insert("proto-tcp".to_string(), "yes".to_string());
+        }
+
+        let dev = config.directive("dev").and_then(|d| d.arg(0)).unwrap_or("tun");
+        let dev_type = config
+            .directive("dev-type")
+            .and_then(|d| d.arg(0))
+            .unwrap_or(if dev.starts_with("tap") { "tap" } else { "tun" });
+        data.insert("dev-type".to_string(), dev_type.to_string());
+        if dev != "tun" && dev != "tap" {
+            data.insert("dev".to_string(), dev.to_string());
+        }
+
+        let password = config.has_directive("auth-user-pass") || config.inline_block("auth-user-pass").is_some();
+        let pkcs12 = file_of("pkcs12");
+        let has_cert = pkcs12.is_some() || file_of("cert").is_some();
+        let connection_type = if config.has_directive("secret") || config.inline_block("secret").is_some() {
+            "static-key"
+        } else if password && has_cert {
+            "password-tls"
+        } else if password {
+            "password"
+        } else {
+            "tls"
+        };
+        data.insert("connection-type".to_string(), connection_type.to_string());
+        if password {
+            // NetworkManager asks for the password through its secret agent.
+            data.insert("password-flags".to_string(), "2".to_string());
+        }
+        if let Some(ca) = file_of("ca") {
+            data.insert("ca".to_string(), ca);
+        }
+        match pkcs12 {
+            Some(p12) => {
+                data.insert("cert".to_string(), p12.clone());
+                data.insert("key".to_string(), p12);
+            }
+            None => {
+                for tag in ["cert", "key"] {
+                    if let Some(path) = file_of(tag) {
+                        data.insert(tag.to_string(), path);
+                    }
+                }
+            }
+        }
+
+        let key_direction = config.directive("key-direction").and_then(|d| d.arg(0));
+        if let Some(ta) = file_of("tls-auth") {
+            data.insert("ta".to_string(), ta);
+            let direction = config.directive("tls-auth").and_then(|d| d.arg(1)).or(key_direction);
+            if let Some(direction) = direction {
+                data.insert("ta-dir".to_string(), direction.to_string());
+            }
+        }
+        if let Some(secret) = file_of("secret") {
+            data.insert("static-key".to_string(), secret);
+            let direction = config.directive("secret").and_then(|d| d.arg(1)).or(key_direction);
+            if let Some(direction) = direction {
+                data.insert("static-key-direction".to_string(), direction.to_string());
+            }
+            if let Some(ifconfig) = config.directive("ifconfig") {
+                if let (Some(local), Some(remote)) = (ifconfig.arg(0), ifconfig.arg(1)) {
+                    data.insert("local-ip".to_string(), local.to_string());
+                    data.insert("remote-ip".to_string(), remote.to_string());
+                }
+            }
+        }
+        for tag in ["tls-crypt", "tls-crypt-v2"] {
+            if let Some(path) = file_of(tag) {
+                data.insert(tag.to_string(), path);
+            }
+        }
+        if let Some(crl) = config.directive("crl-verify").and_then(|d| d.arg(0)) {
+            let key = if config.directive("crl-verify").and_then(|d| d.arg(1)) == Some("dir") {
+                "crl-verify-dir"
+            } else {
+                "crl-verify-file"
+            };
+            data.insert(key.to_string(), crl.to_string());
+        } else if let Some(crl) = files.get("crl-verify") {
+            data.insert("crl-verify-file".to_string(), crl.clone());
+        }
+
+        if let Some(verify) = config.directive("verify-x509-name") {
+            if let Some(name) = verify.arg(0) {
+                let kind = verify.arg(1).unwrap_or("subject");
+                data.insert("verify-x509-name".to_string(), format!("{}:{}", kind, name));
+            }
+        }
+
+        let mut never_default = false;
+        for directive in config.directives() {
+            let name = directive.name();
+            let value = directive.args().join(" ");
+            match name {
+                "comp-lzo" => {
+                    let mode = match directive.arg(0) {
+                        Some("no") => "no-by-default",
+                        Some(mode) => mode,
+                        None => "adaptive",
+                    };
+                    data.insert("comp-lzo".to_string(), mode.to_string());
+                }
+                "compress" => {
+                    data.insert("compress".to_string(), directive.arg(0).unwrap_or("yes").to_string());
+                }
+                "mssfix" => {
+                    data.insert("mssfix".to_string(), directive.arg(0).unwrap_or("yes").to_string());
+                }
+                "http-proxy" | "socks-proxy" => {
+                    let kind = if name == "socks-proxy" { "socks" } else { "http" };
+                    data.insert("proxy-type".to_string(), kind.to_string());
+                    if let Some(server) = directive.arg(0) {
+                        data.insert("proxy-server".to_string(), server.to_string());
+                    }
+                    if let Some(port) = directive.arg(1) {
+                        data.insert("proxy-port".to_string(), port.to_string());
+                    }
+                }
+                "http-proxy-retry" | "socks-proxy-retry" => {
+                    data.insert("proxy-retry".to_string(), "yes".to_string());
+                }
+                "pull-filter" if value == "ignore redirect-gateway" => never_default = true,
+                _ => {
+                    if let Some((_, key)) = FLAG_OPTIONS.iter().find(|(directive, _)| *directive == name) {
+                        data.insert(key.to_string(), "yes".to_string());
+                    } else if let Some((_, key)) = SIMPLE_OPTIONS.iter().find(|(directive, _)| *directive == name) {
+                        data.insert(key.to_string(), value);
+                    } else if !Self::handled_elsewhere(name) {
+                        skipped.push(name.to_string());
+                    }
+                }
+            }
+        }
+
+        let connection = NmConnection {
+            name: name.to_string(),
+            uuid: Some(uuid.to_string()),
+            data,
+            never_default,
+        };
+        NmConversion { value: connection, skipped }
+    }
+
+    fn handled_elsewhere(name: &str) -> bool {
+        IMPLIED_DIRECTIVES.contains(&name)
+            || [
+                "remote", "proto", "port", "dev", "dev-type", "ca", "cert", "key", "pkcs12", "tls-auth", "tls-crypt",
+                "tls-crypt-v2", "secret", "ifconfig", "crl-verify", "verify-x509-name", "auth-user-pass",
+            ]
+            .contains(&name)
+    }
+
+    /// `host[:port[:proto]]` from a NetworkManager remote entry.
+    fn ovpn_remote(remote: &str, default_port: &str) -> String {
+        // Bracketed IPv6 literals contain colons of their own.
+        let (host, rest) = match remote.strip_prefix('[').and_then(|r| r.split_once(']')) {
+            Some((host, rest)) => (host, rest.trim_start_matches(':')),
+            None => match remote.split_once(':') {
+                Some((host, rest)) => (host, rest),
+                None => (remote, ""),
+            },
+        };
+        let mut parts = rest.split(':').filter(|part| !part.is_empty());
+        let port = parts.next().unwrap_or(default_port);
+        match parts.next() {
+            Some(proto) => format!("{} {} {}", host, port, proto),
+            None => format!("{} {}", host, port),
+        }
+    }
+
+    fn quote(value: &str) -> String {
+        if value.is_empty() || value.chars().any(|c| c.is_whitespace() || matches!(c, '"' | '\\' | '#' | ';')) {
+            format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+        } else {
+            value.to_string()
+        }
+    }
+
+    fn parse_keyfile(input: &str) -> Result<BTreeMap<String, BTreeMap<String, String>>, NmKeyfileError> {
+        let mut sections: BTreeMap<String, BTreeMap<String, String>> = BTreeMap::new();
+        let mut current = String::new();
+        for (index, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                current = section.to_string();
+                continue;
+            }
+            let (key, value) = line.split_once('=').ok_or(NmKeyfileError::Syntax { line: index + 1 })?;
+            sections
+                .entry(current.clone())
+                .or_default()
+                .insert(key.trim().to_string(), Self::unescape(value.trim_start()));
+        }
+        Ok(sections)
+    }
+
+    /// GKeyFile value escapes: `\s`, `\n`, `\t`, `\r` and `\\`.
+    fn unescape(value: &str) -> String {
+        let mut out = String::new();
+        let mut chars = value.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                out.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('s') => out.push(' '),
+                Some('n') => out.push('\n'),
+                Some('t') => out.push('\t'),
+                Some('r') => out.push('\r'),
+                Some(other) => out.push(other),
+                None => out.push('\\'),
+            }
+        }
+        out
+    }
+
+    fn escape(value: &str) -> String {
+        let mut out = String::new();
+        for (index, c) in value.chars().enumerate() {
+            match c {
+                '\\' => out.push_str("\\\\"),
+                '\n' => out.push_str("\\n"),
+                '\t' => out.push_str("\\t"),
+                '\r' => out.push_str("\\r"),
+                ' ' if index == 0 => out.push_str("\\s"),
+                c => out.push(c),
+            }
+        }
+        out
+    }
+}
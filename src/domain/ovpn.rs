@@ -0,0 +1,508 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum OvpnParseError {
+    #[error("line {line}: unterminated quoted argument")]
+    UnterminatedQuote { line: usize },
+    #[error("line {line}: inline block <{tag}> is never closed")]
+    UnclosedBlock { tag: String, line: usize },
+    #[error("line {line}: closing tag </{tag}> without a matching opening tag")]
+    UnexpectedClosingTag { tag: String, line: usize },
+}
+
+/// Directives whose first argument names a file OpenVPN reads at startup.
+/// Each of them may instead be supplied as an inline `<tag>` block.
+pub const FILE_DIRECTIVES: &[&str] = &[
+    "ca",
+    "cert",
+    "key",
+    "extra-certs",
+    "dh",
+    "pkcs12",
+    "tls-auth",
+    "tls-crypt",
+    "tls-crypt-v2",
+    "secret",
+    "crl-verify",
+    "auth-user-pass",
+    "http-proxy-user-pass",
+];
+
+/// Marker OpenVPN accepts in place of a path when the file is embedded.
+pub const INLINE_FILE: &str = "[inline]";
+
+/// A file-valued directive that points outside the profile.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileReference {
+    pub directive: String,
+    pub path: String,
+    pub line: usize,
+}
+
+/// A single `name arg1 arg2 ...` option line.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Directive {
+    name: String,
+    args: Vec<String>,
+    line: usize,
+}
+
+impl Directive {
+    pub fn new(name: &str, args: Vec<String>) -> Self {
+        Self {
+            name: name.trim_start_matches("--").to_string(),
+            args,
+            line: 0,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    pub fn arg(&self, index: usize) -> Option<&str> {
+        self.args.get(index).map(String::as_str)
+    }
+
+    /// 1-based line number in the source file, or 0 for directives added in memory.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+/// An embedded file such as `<ca>...</ca>` or `<tls-auth>...</tls-auth>`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InlineBlock {
+    tag: String,
+    content: String,
+    line: usize,
+}
+
+impl InlineBlock {
+    pub fn new(tag: &str, content: String) -> Self {
+        Self {
+            tag: tag.to_string(),
+            content,
+            line: 0,
+        }
+    }
+
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// Raw text between the opening and closing tags.
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+
+    /// 1-based line number of the opening tag, or 0 for blocks added in memory.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Comment {
+    text: String,
+    line: usize,
+}
+
+impl Comment {
+    /// Comment text without the leading `#` or `;` marker.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OvpnEntry {
+    Blank,
+    Comment(Comment),
+    Directive(Directive),
+    InlineBlock(InlineBlock),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Node {
+    entry: OvpnEntry,
+    // Exact source text including line terminators, so untouched entries
+    // are written back byte for byte.
+    raw: String,
+}
+
+/// Parsed `.ovpn` profile that renders back to its original text.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct OvpnConfig {
+    nodes: Vec<Node>,
+    line_ending: String,
+}
+
+impl OvpnConfig {
+    pub fn parse(input: &str) -> Result<Self, OvpnParseError> {
+        let line_ending = if input.contains("\r\n") { "\r\n" } else { "\n" };
+        let mut nodes = Vec::new();
+        let mut lines = input.split_inclusive('\n').enumerate();
+
+        while let Some((index, raw_line)) = lines.next() {
+            let line = index + 1;
+            let text = raw_line.trim_end_matches(['\r', '\n']);
+            let trimmed = text.trim();
+
+            if trimmed.is_empty() {
+                nodes.push(Node { entry: OvpnEntry::Blank, raw: raw_line.to_string() });
+                continue;
+            }
+
+            if let Some(rest) = trimmed.strip_prefix('#').or_else(|| trimmed.strip_prefix(';')) {
+                let comment = Comment { text: rest.to_string(), line };
+                nodes.push(Node { entry: OvpnEntry::Comment(comment), raw: raw_line.to_string() });
+                continue;
+            }
+
+            if let Some(tag) = Self::closing_tag(trimmed) {
+                return Err(OvpnParseError::UnexpectedClosingTag { tag: tag.to_string(), line });
+            }
+
+            if let Some(tag) = Self::opening_tag(trimmed) {
+                let mut raw = raw_line.to_string();
+                let mut content = String::new();
+                let mut closed = false;
+
+                for (_, inner) in lines.by_ref() {
+                    raw.push_str(inner);
+                    if Self::closing_tag(inner.trim()) == Some(tag) {
+                        closed = true;
+                        break;
+                    }
+                    content.push_str(inner);
+                }
+
+                if !closed {
+                    return Err(OvpnParseError::UnclosedBlock { tag: tag.to_string(), line });
+                }
+
+                let block = InlineBlock { tag: tag.to_string(), content, line };
+                nodes.push(Node { entry: OvpnEntry::InlineBlock(block), raw });
+                continue;
+            }
+
+            let tokens = Self::tokenize(text).ok_or(OvpnParseError::UnterminatedQuote { line })?;
+            let entry = match tokens.split_first() {
+                Some((name, args)) => OvpnEntry::Directive(Directive {
+                    name: name.trim_start_matches("--").to_string(),
+                    args: args.to_vec(),
+                    line,
+                }),
+                // A line holding only a trailing comment such as `  ; note`
+                None => OvpnEntry::Blank,
+            };
+            nodes.push(Node { entry, raw: raw_line.to_string() });
+        }
+
+        Ok(Self { nodes, line_ending: line_ending.to_string() })
+    }
+
+    pub fn render(&self) -> String {
+        self.nodes.iter().map(|node| node.raw.as_str()).collect()
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &OvpnEntry> {
+        self.nodes.iter().map(|node| &node.entry)
+    }
+
+    pub fn directives(&self) -> impl Iterator<Item = &Directive> {
+        self.entries().filter_map(|entry| match entry {
+            OvpnEntry::Directive(directive) => Some(directive),
+            _ => None,
+        })
+    }
+
+    pub fn directives_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Directive> {
+        self.directives().filter(move |directive| directive.name == name)
+    }
+
+    pub fn directive(&self, name: &str) -> Option<&Directive> {
+        self.directives().find(|directive| directive.name == name)
+    }
+
+    pub fn has_directive(&self, name: &str) -> bool {
+        self.directive(name).is_some()
+    }
+
+    pub fn inline_blocks(&self) -> impl Iterator<Item = &InlineBlock> {
+        self.entries().filter_map(|entry| match entry {
+            OvpnEntry::InlineBlock(block) => Some(block),
+            _ => None,
+        })
+    }
+
+    pub fn inline_block(&self, tag: &str) -> Option<&InlineBlock> {
+        self.inline_blocks().find(|block| block.tag == tag)
+    }
+
+    pub fn comments(&self) -> impl Iterator<Item = &Comment> {
+        self.entries().filter_map(|entry| match entry {
+            OvpnEntry::Comment(comment) => Some(comment),
+            _ => None,
+        })
+    }
+
+    /// Every file path referenced by a [`FILE_DIRECTIVES`] entry, skipping
+    /// `[inline]` markers and directives used without a file argument.
+    pub fn file_references(&self) -> Vec<FileReference> {
+        self.directives()
+            .filter(|directive| FILE_DIRECTIVES.contains(&directive.name.as_str()))
+            .filter_map(|directive| {
+                let path = directive.arg(0)?;
+                // `auth-user-pass` alone prompts; `stdin` is OpenVPN's explicit prompt marker.
+                if path == INLINE_FILE || path == "stdin" {
+                    return None;
+                }
+                Some(FileReference {
+                    directive: directive.name.clone(),
+                    path: path.to_string(),
+                    line: directive.line,
+                })
+            })
+            .collect()
+    }
+
+    /// Sets a single-valued directive: the first occurrence is rewritten in
+    /// place and later duplicates are dropped. Appends when absent.
+    pub fn set_directive(&mut self, name: &str, args: Vec<String>) {
+        let mut directive = Directive::new(name, args);
+        let mut positions = self.directive_positions(&directive.name).into_iter();
+
+        match positions.next() {
+            Some(first) => {
+                if let OvpnEntry::Directive(existing) = &self.nodes[first].entry {
+                    directive.line = existing.line;
+                }
+                self.nodes[first] = self.directive_node(directive);
+                let duplicates: Vec<usize> = positions.collect();
+                for index in duplicates.into_iter().rev() {
+                    self.nodes.remove(index);
+                }
+            }
+            None => self.push_directive(name, directive.args),
+        }
+    }
+
+    /// Rewrites the first `from` directive as `to`, keeping its arguments and
+    /// position. Returns false when `from` is absent.
+    pub fn rename_directive(&mut self, from: &str, to: &str) -> bool {
+        let index = match self.directive_positions(from.trim_start_matches("--")).first() {
+            Some(index) => *index,
+            None => return false,
+        };
+        if let OvpnEntry::Directive(existing) = &self.nodes[index].entry {
+            let mut directive = Directive::new(to, existing.args.clone());
+            directive.line = existing.line;
+            self.nodes[index] = self.directive_node(directive);
+        }
+        true
+    }
+
+    /// Points every `name` directive whose file argument is `from` at `to`,
+    /// keeping any further arguments. Returns how many were rewritten.
+    pub fn rewrite_file_reference(&mut self, name: &str, from: &str, to: &str) -> usize {
+        let mut rewritten = 0;
+        for index in self.directive_positions(name.trim_start_matches("--")) {
+            if let OvpnEntry::Directive(existing) = &self.nodes[index].entry {
+                if existing.arg(0) != Some(from) {
+                    continue;
+                }
+                let mut args = existing.args.clone();
+                args[0] = to.to_string();
+                let mut directive = Directive::new(name, args);
+                directive.line = existing.line;
+                self.nodes[index] = self.directive_node(directive);
+                rewritten += 1;
+            }
+        }
+        rewritten
+    }
+
+    /// Inserts a directive just above the first `anchor` directive, or at the
+    /// end when `anchor` is absent.
+    pub fn insert_directive_before(&mut self, anchor: &str, name: &str, args: Vec<String>) {
+        match self.directive_positions(anchor.trim_start_matches("--")).first() {
+            Some(&index) => {
+                let node = self.directive_node(Directive::new(name, args));
+                self.nodes.insert(index, node);
+            }
+            None => self.push_directive(name, args),
+        }
+    }
+
+    pub fn push_directive(&mut self, name: &str, args: Vec<String>) {
+        self.ensure_trailing_newline();
+        let node = self.directive_node(Directive::new(name, args));
+        self.nodes.push(node);
+    }
+
+    /// Removes every occurrence of `name`, returning how many were dropped.
+    pub fn remove_directive(&mut self, name: &str) -> usize {
+        let name = name.trim_start_matches("--");
+        let before = self.nodes.len();
+        self.nodes.retain(|node| !matches!(&node.entry, OvpnEntry::Directive(d) if d.name == name));
+        before - self.nodes.len()
+    }
+
+    /// Replaces the content of the `<tag>` block, appending a new block when absent.
+    pub fn set_inline_block(&mut self, tag: &str, content: &str) {
+        let mut block = InlineBlock::new(tag, content.to_string());
+        if !block.content.is_empty() && !block.content.ends_with('\n') {
+            block.content.push_str(&self.line_ending);
+        }
+
+        let existing = self
+            .nodes
+            .iter()
+            .position(|node| matches!(&node.entry, OvpnEntry::InlineBlock(b) if b.tag == tag));
+
+        match existing {
+            Some(index) => {
+                if let OvpnEntry::InlineBlock(old) = &self.nodes[index].entry {
+                    block.line = old.line;
+                }
+                self.nodes[index] = self.block_node(block);
+            }
+            None => {
+                self.ensure_trailing_newline();
+                let node = self.block_node(block);
+                self.nodes.push(node);
+            }
+        }
+    }
+
+    pub fn remove_inline_block(&mut self, tag: &str) -> bool {
+        let before = self.nodes.len();
+        self.nodes.retain(|node| !matches!(&node.entry, OvpnEntry::InlineBlock(b) if b.tag == tag));
+        before != self.nodes.len()
+    }
+
+    fn directive_positions(&self, name: &str) -> Vec<usize> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| matches!(&node.entry, OvpnEntry::Directive(d) if d.name == name))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    fn directive_node(&self, directive: Directive) -> Node {
+        let mut raw = directive.name.clone();
+        for arg in &directive.args {
+            raw.push(' ');
+            raw.push_str(&Self::quote(arg));
+        }
+        raw.push_str(&self.line_ending);
+        Node { entry: OvpnEntry::Directive(directive), raw }
+    }
+
+    fn block_node(&self, block: InlineBlock) -> Node {
+        let raw = format!(
+            "<{tag}>{eol}{content}</{tag}>{eol}",
+            tag = block.tag,
+            content = block.content,
+            eol = self.line_ending,
+        );
+        Node { entry: OvpnEntry::InlineBlock(block), raw }
+    }
+
+    fn ensure_trailing_newline(&mut self) {
+        if let Some(last) = self.nodes.last_mut() {
+            if !last.raw.ends_with('\n') {
+                last.raw.push_str(&self.line_ending);
+            }
+        }
+    }
+
+    fn opening_tag(line: &str) -> Option<&str> {
+        let tag = line.strip_prefix('<')?.strip_suffix('>')?;
+        Self::is_tag_name(tag).then_some(tag)
+    }
+
+    fn closing_tag(line: &str) -> Option<&str> {
+        let tag = line.strip_prefix("</")?.strip_suffix('>')?;
+        Self::is_tag_name(tag).then_some(tag)
+    }
+
+    fn is_tag_name(tag: &str) -> bool {
+        !tag.is_empty() && tag.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    }
+
+    /// Splits a line the way OpenVPN does: whitespace separated, double quotes
+    /// with backslash escapes, literal single quotes, and `#`/`;` starting a
+    /// comment at a token boundary. Returns `None` on an unterminated quote.
+    fn tokenize(line: &str) -> Option<Vec<String>> {
+        let mut tokens = Vec::new();
+        let mut chars = line.chars().peekable();
+
+        loop {
+            while chars.peek().is_some_and(|c| c.is_whitespace()) {
+                chars.next();
+            }
+            match chars.peek() {
+                None | Some('#') | Some(';') => break,
+                _ => {}
+            }
+
+            let mut token = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() {
+                    break;
+                }
+                chars.next();
+                match c {
+                    '\\' => token.push(chars.next().unwrap_or('\\')),
+                    '"' => loop {
+                        match chars.next()? {
+                            '"' => break,
+                            '\\' => token.push(chars.next()?),
+                            other => token.push(other),
+                        }
+                    },
+                    '\'' => loop {
+                        match chars.next()? {
+                            '\'' => break,
+                            other => token.push(other),
+                        }
+                    },
+                    other => token.push(other),
+                }
+            }
+            tokens.push(token);
+        }
+
+        Some(tokens)
+    }
+
+    fn quote(arg: &str) -> String {
+        let needs_quotes = arg.is_empty()
+            || arg.chars().any(|c| c.is_whitespace() || matches!(c, '"' | '\'' | '\\' | '#' | ';'));
+        if !needs_quotes {
+            return arg.to_string();
+        }
+        let escaped = arg.replace('\\', "\\\\").replace('"', "\\\"");
+        format!("\"{}\"", escaped)
+    }
+}
+
+impl fmt::Display for OvpnConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render())
+    }
+}
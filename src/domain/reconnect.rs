@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// How a tunnel that dropped is brought back. Delays double from
+/// `initial_delay_secs` up to `max_delay_secs`, each spread by up to
+/// `jitter_percent` either way so clients of one server don't retry in
+/// lockstep after an outage.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    pub enabled: bool,
+    /// `None` keeps trying until the user disconnects.
+    pub max_attempts: Option<u32>,
+    pub initial_delay_secs: u64,
+    pub max_delay_secs: u64,
+    pub jitter_percent: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_attempts: Some(10),
+            initial_delay_secs: 2,
+            max_delay_secs: 300,
+            jitter_percent: 20,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn disabled() -> Self {
+        Self { enabled: false, ..Self::default() }
+    }
+
+    /// Whether reconnect attempt `attempt` (counted from 1) may be made.
+    pub fn allows(&self, attempt: u32) -> bool {
+        self.enabled && attempt > 0 && self.max_attempts.is_none_or(|max| attempt <= max)
+    }
+
+    /// Wait before attempt `attempt`. `random` is a sample from `[0, 1)`
+    /// that places the delay within the jitter band.
+    pub fn delay(&self, attempt: u32, random: f64) -> Duration {
+        let doublings = attempt.saturating_sub(1).min(32);
+        let base = self
+            .initial_delay_secs
+            .saturating_mul(1u64 << doublings)
+            .min(self.max_delay_secs.max(self.initial_delay_secs)) as f64;
+        let spread = base * f64::from(self.jitter_percent.min(100)) / 100.0;
+        let seconds = base - spread + 2.0 * spread * random.clamp(0.0, 1.0);
+        Duration::from_secs_f64(seconds.max(0.0))
+    }
+}
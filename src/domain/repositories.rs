@@ -1,10 +1,40 @@
-use crate::domain::entities::Vpn;
+use crate::domain::{bundle::BundledFile, credentials::Credentials, entities::Vpn};
 use async_trait::async_trait;
 use std::error::Error;
+use std::path::PathBuf;
 
 #[async_trait]
 pub trait VpnRepository: Send + Sync {
     async fn find_by_id(&self, id: &str) -> Result<Option<Vpn>, Box<dyn Error>>;
     async fn save(&self, vpn: &Vpn) -> Result<(), Box<dyn Error>>;
     async fn list_all(&self) -> Result<Vec<Vpn>, Box<dyn Error>>;
-}
\ No newline at end of file
+    /// Stores a new profile from its .ovpn text. Fails when `id` is taken.
+    async fn create(&self, id: &str, content: &str) -> Result<Vpn, Box<dyn Error>>;
+    /// Replaces the .ovpn text of an existing profile, keeping its metadata.
+    async fn update_config(&self, id: &str, content: &str) -> Result<Vpn, Box<dyn Error>>;
+    /// Moves a profile and its metadata to `new_id`.
+    async fn rename(&self, id: &str, new_id: &str) -> Result<Vpn, Box<dyn Error>>;
+    /// Replaces the files kept alongside profile `id` and returns the
+    /// directory holding them. An empty list removes the directory.
+    async fn store_files(&self, id: &str, files: &[BundledFile]) -> Result<PathBuf, Box<dyn Error>>;
+    /// Deletes the profile file, its stored files and its metadata.
+    async fn delete(&self, id: &str) -> Result<(), Box<dyn Error>>;
+}
+
+/// Secrets saved per profile: `auth-user-pass` credentials, plus named
+/// values such as a TOTP seed.
+#[async_trait]
+pub trait CredentialStore: Send + Sync {
+    async fn load(&self, vpn_id: &str) -> Result<Option<Credentials>, Box<dyn Error + Send + Sync>>;
+    async fn save(&self, vpn_id: &str, credentials: &Credentials) -> Result<(), Box<dyn Error + Send + Sync>>;
+    /// Forgets the username and password of `vpn_id`, keeping other secrets.
+    /// Returns whether there were any.
+    async fn remove(&self, vpn_id: &str) -> Result<bool, Box<dyn Error + Send + Sync>>;
+    async fn load_secret(&self, vpn_id: &str, name: &str) -> Result<Option<String>, Box<dyn Error + Send + Sync>>;
+    async fn save_secret(&self, vpn_id: &str, name: &str, value: &str) -> Result<(), Box<dyn Error + Send + Sync>>;
+    async fn remove_secret(&self, vpn_id: &str, name: &str) -> Result<bool, Box<dyn Error + Send + Sync>>;
+    /// Forgets everything stored for `vpn_id`.
+    async fn forget(&self, vpn_id: &str) -> Result<bool, Box<dyn Error + Send + Sync>>;
+    /// Moves stored secrets along with a renamed profile.
+    async fn rename(&self, vpn_id: &str, new_id: &str) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
@@ -0,0 +1,208 @@
+use crate::domain::entities::ConnectionState;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use thiserror::Error;
+
+/// Detailed tunnel lifecycle, following the states OpenVPN reports over its
+/// management interface.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TunnelState {
+    Disconnected,
+    /// Process started, waiting for OpenVPN's first state report.
+    Connecting,
+    Resolving,
+    Waiting,
+    Auth,
+    GetConfig,
+    AssignIp,
+    AddRoutes,
+    Connected,
+    Reconnecting,
+    Exiting,
+    Failed(String),
+    /// Automatic reconnection ran out of attempts; holds the last failure.
+    GaveUp(String),
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TransitionError {
+    #[error("illegal tunnel state transition from {from} to {to}")]
+    IllegalTransition { from: TunnelState, to: TunnelState },
+}
+
+impl TunnelState {
+    /// Maps an OpenVPN state name (`>STATE:` second field) to a tunnel state.
+    /// `description` disambiguates why OpenVPN is exiting.
+    pub fn from_openvpn(name: &str, description: &str) -> Option<Self> {
+        let state = match name {
+            "CONNECTING" => Self::Connecting,
+            "RESOLVE" => Self::Resolving,
+            "TCP_CONNECT" | "WAIT" => Self::Waiting,
+            "AUTH" | "AUTH_PENDING" => Self::Auth,
+            "GET_CONFIG" => Self::GetConfig,
+            "ASSIGN_IP" => Self::AssignIp,
+            "ADD_ROUTES" => Self::AddRoutes,
+            "CONNECTED" => Self::Connected,
+            "RECONNECTING" => Self::Reconnecting,
+            "EXITING" => match description {
+                "" | "SIGTERM" | "SIGINT" | "exit-with-notification" => Self::Exiting,
+                reason => Self::Failed(reason.to_string()),
+            },
+            _ => return None,
+        };
+        Some(state)
+    }
+
+    /// Stable snake_case name, used in DTOs and CLI output.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Disconnected => "disconnected",
+            Self::Connecting => "connecting",
+            Self::Resolving => "resolving",
+            Self::Waiting => "waiting",
+            Self::Auth => "auth",
+            Self::GetConfig => "get_config",
+            Self::AssignIp => "assign_ip",
+            Self::AddRoutes => "add_routes",
+            Self::Connected => "connected",
+            Self::Reconnecting => "reconnecting",
+            Self::Exiting => "exiting",
+            Self::Failed(_) => "failed",
+            Self::GaveUp(_) => "gave_up",
+        }
+    }
+
+    /// Inverse of [`Self::name`]; `reason` fills in `Failed`.
+    pub fn from_name(name: &str, reason: &str) -> Option<Self> {
+        let state = match name {
+            "disconnected" => Self::Disconnected,
+            "connecting" => Self::Connecting,
+            "resolving" => Self::Resolving,
+            "waiting" => Self::Waiting,
+            "auth" => Self::Auth,
+            "get_config" => Self::GetConfig,
+            "assign_ip" => Self::AssignIp,
+            "add_routes" => Self::AddRoutes,
+            "connected" => Self::Connected,
+            "reconnecting" => Self::Reconnecting,
+            "exiting" => Self::Exiting,
+            "failed" => Self::Failed(reason.to_string()),
+            "gave_up" => Self::GaveUp(reason.to_string()),
+            _ => return None,
+        };
+        Some(state)
+    }
+
+    pub fn connection_state(&self) -> ConnectionState {
+        match self {
+            Self::Disconnected => ConnectionState::Disconnected,
+            Self::Connected => ConnectionState::Connected,
+            Self::Exiting => ConnectionState::Disconnecting,
+            Self::Failed(reason) | Self::GaveUp(reason) => ConnectionState::Error(reason.clone()),
+            _ => ConnectionState::Connecting,
+        }
+    }
+
+    /// Position in the handshake, for states that belong to it.
+    fn handshake_step(&self) -> Option<u8> {
+        let step = match self {
+            Self::Connecting => 0,
+            Self::Resolving => 1,
+            Self::Waiting => 2,
+            Self::Auth => 3,
+            Self::GetConfig => 4,
+            Self::AssignIp => 5,
+            Self::AddRoutes => 6,
+            Self::Connected => 7,
+            _ => return None,
+        };
+        Some(step)
+    }
+
+    pub fn is_active(&self) -> bool {
+        !matches!(self, Self::Disconnected | Self::Failed(_) | Self::GaveUp(_))
+    }
+
+    fn can_transition_to(&self, next: &TunnelState) -> bool {
+        use TunnelState::*;
+
+        if self == next {
+            return true;
+        }
+        match (self, next) {
+            (Disconnected, Connecting) => true,
+            (Failed(_) | GaveUp(_), Connecting | Disconnected) => true,
+            (Exiting, Disconnected | Failed(_)) => true,
+            // A dropped tunnel waits for the supervisor's next attempt, or
+            // for it to give up.
+            (Disconnected | Failed(_), Reconnecting) => true,
+            (Reconnecting | Disconnected | Failed(_), GaveUp(_)) => true,
+            (Disconnected | Failed(_) | GaveUp(_) | Exiting, _) => false,
+            // The process can die or be told to stop at any point.
+            (_, Exiting | Failed(_) | Disconnected) => true,
+            // SIGUSR1, ping-restart and TLS errors restart the handshake.
+            (_, Reconnecting) => true,
+            (Reconnecting, next) => next.handshake_step().is_some(),
+            // Steps may be skipped (no DNS lookup for literal IPs, UDP has no
+            // TCP_CONNECT) but never repeated without reconnecting.
+            (current, next) => match (current.handshake_step(), next.handshake_step()) {
+                (Some(from), Some(to)) => to > from,
+                _ => false,
+            },
+        }
+    }
+}
+
+impl fmt::Display for TunnelState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Failed(reason) if !reason.is_empty() => write!(f, "failed ({})", reason),
+            Self::GaveUp(reason) if !reason.is_empty() => write!(f, "gave up ({})", reason),
+            state => f.write_str(state.name()),
+        }
+    }
+}
+
+/// Guards tunnel state changes so impossible sequences are rejected instead
+/// of silently overwriting what the UI shows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectionStateMachine {
+    state: TunnelState,
+}
+
+impl ConnectionStateMachine {
+    pub fn new() -> Self {
+        Self { state: TunnelState::Disconnected }
+    }
+
+    pub fn state(&self) -> &TunnelState {
+        &self.state
+    }
+
+    pub fn connection_state(&self) -> ConnectionState {
+        self.state.connection_state()
+    }
+
+    pub fn transition(&mut self, next: TunnelState) -> Result<&TunnelState, TransitionError> {
+        if !self.state.can_transition_to(&next) {
+            return Err(TransitionError::IllegalTransition { from: self.state.clone(), to: next });
+        }
+        self.state = next;
+        Ok(&self.state)
+    }
+
+    /// Applies an OpenVPN state report. Unknown state names are ignored and
+    /// leave the machine unchanged.
+    pub fn apply_openvpn_state(&mut self, name: &str, description: &str) -> Result<&TunnelState, TransitionError> {
+        match TunnelState::from_openvpn(name, description) {
+            Some(next) => self.transition(next),
+            None => Ok(&self.state),
+        }
+    }
+}
+
+impl Default for ConnectionStateMachine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,143 @@
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TotpError {
+    #[error("TOTP seed is not valid base32")]
+    InvalidSeed,
+    #[error("invalid otpauth URI: {0}")]
+    InvalidUri(String),
+    #[error("unsupported TOTP algorithm {0}")]
+    UnsupportedAlgorithm(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TotpAlgorithm {
+    #[default]
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+/// RFC 6238 time-based one-time codes, as produced by authenticator apps.
+#[derive(Clone, PartialEq, Eq)]
+pub struct TotpGenerator {
+    secret: Vec<u8>,
+    digits: u32,
+    period: u64,
+    algorithm: TotpAlgorithm,
+}
+
+impl std::fmt::Debug for TotpGenerator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TotpGenerator")
+            .field("digits", &self.digits)
+            .field("period", &self.period)
+            .field("algorithm", &self.algorithm)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TotpGenerator {
+    pub fn new(secret: Vec<u8>, digits: u32, period: u64, algorithm: TotpAlgorithm) -> Self {
+        Self {
+            secret,
+            digits,
+            period: period.max(1),
+            algorithm,
+        }
+    }
+
+    /// Accepts a bare base32 seed (spaces and case ignored, as apps print
+    /// them) or the `otpauth://totp/...` URI behind an enrolment QR code.
+    pub fn parse(input: &str) -> Result<Self, TotpError> {
+        let input = input.trim();
+        if !input.starts_with("otpauth://") {
+            return Ok(Self::new(decode_base32(input)?, 6, 30, TotpAlgorithm::Sha1));
+        }
+
+        let rest = input
+            .strip_prefix("otpauth://totp/")
+            .ok_or_else(|| TotpError::InvalidUri("only otpauth://totp/ is supported".to_string()))?;
+        let query = rest
+            .split_once('?')
+            .map(|(_, query)| query)
+            .ok_or_else(|| TotpError::InvalidUri("missing secret".to_string()))?;
+
+        let mut secret = None;
+        let mut generator = Self::new(Vec::new(), 6, 30, TotpAlgorithm::Sha1);
+        for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+            let invalid = || TotpError::InvalidUri(format!("invalid {}", key));
+            match key.to_ascii_lowercase().as_str() {
+                "secret" => secret = Some(decode_base32(value)?),
+                "digits" => generator.digits = value.parse().ok().filter(|d| (6..=10).contains(d)).ok_or_else(invalid)?,
+                "period" => generator.period = value.parse().ok().filter(|p| *p > 0).ok_or_else(invalid)?,
+                "algorithm" => {
+                    generator.algorithm = match value.to_ascii_uppercase().as_str() {
+                        "SHA1" => TotpAlgorithm::Sha1,
+                        "SHA256" => TotpAlgorithm::Sha256,
+                        "SHA512" => TotpAlgorithm::Sha512,
+                        other => return Err(TotpError::UnsupportedAlgorithm(other.to_string())),
+                    }
+                }
+                _ => {}
+            }
+        }
+        generator.secret = secret.ok_or_else(|| TotpError::InvalidUri("missing secret".to_string()))?;
+        Ok(generator)
+    }
+
+    pub fn code(&self) -> String {
+        self.code_at(SystemTime::now())
+    }
+
+    pub fn code_at(&self, time: SystemTime) -> String {
+        let counter = time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs() / self.period;
+        let message = counter.to_be_bytes();
+        let mac = match self.algorithm {
+            TotpAlgorithm::Sha1 => hmac::<Sha1>(&self.secret, &message),
+            TotpAlgorithm::Sha256 => hmac::<Sha256>(&self.secret, &message),
+            TotpAlgorithm::Sha512 => hmac::<Sha512>(&self.secret, &message),
+        };
+        // Dynamic truncation, RFC 4226 section 5.3.
+        let offset = (mac[mac.len() - 1] & 0x0f) as usize;
+        let binary = u32::from_be_bytes([mac[offset] & 0x7f, mac[offset + 1], mac[offset + 2], mac[offset + 3]]);
+        let code = binary as u64 % 10u64.pow(self.digits);
+        format!("{:0width$}", code, width = self.digits as usize)
+    }
+}
+
+fn hmac<D: Digest + sha2::digest::core_api::BlockSizeUser>(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let block_size = D::block_size();
+    let mut block = if key.len() > block_size { D::digest(key).to_vec() } else { key.to_vec() };
+    block.resize(block_size, 0);
+
+    let pad = |byte: u8| block.iter().map(|b| b ^ byte).collect::<Vec<u8>>();
+    let inner = D::new().chain_update(pad(0x36)).chain_update(message).finalize();
+    D::new().chain_update(pad(0x5c)).chain_update(inner).finalize().to_vec()
+}
+
+fn decode_base32(input: &str) -> Result<Vec<u8>, TotpError> {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+    let mut bits: u64 = 0;
+    let mut bit_count = 0;
+    let mut bytes = Vec::new();
+    for c in input.chars().filter(|c| !c.is_whitespace() && *c != '-' && *c != '=') {
+        let value = ALPHABET
+            .iter()
+            .position(|&a| a == c.to_ascii_uppercase() as u8)
+            .ok_or(TotpError::InvalidSeed)?;
+        bits = (bits << 5) | value as u64;
+        bit_count += 5;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            bytes.push((bits >> bit_count) as u8);
+        }
+    }
+    if bytes.is_empty() {
+        return Err(TotpError::InvalidSeed);
+    }
+    Ok(bytes)
+}
@@ -1,5 +1,7 @@
 use crate::domain::{
+    bundle::{BundleMode, ProfileBundle},
     entities::{Vpn, VpnStatus, ConnectionState},
+    ovpn::OvpnConfig,
     repositories::VpnRepository,
 };
 use std::sync::Arc;
@@ -13,6 +15,12 @@ pub enum UseCaseError {
     RepositoryError(String),
     #[error("Connection error: {0}")]
     ConnectionError(String),
+    #[error("VPN already exists: {0}")]
+    AlreadyExists(String),
+    #[error("{0}")]
+    InvalidId(String),
+    #[error("Invalid profile: {0}")]
+    InvalidProfile(String),
 }
 
 pub struct ConnectVpnUseCase {
@@ -90,12 +98,184 @@ impl ListVpnsUseCase {
     }
 }
 
+pub struct ImportProfileUseCase {
+    repository: Arc<dyn VpnRepository>,
+}
+
+impl ImportProfileUseCase {
+    pub fn new(repository: Arc<dyn VpnRepository>) -> Self {
+        Self { repository }
+    }
+
+    /// Adds `bundle` as profile `id`, keeping its referenced files as `mode`
+    /// says. An existing profile is only overwritten with `replace`, and
+    /// keeps its metadata when it is.
+    pub async fn execute(
+        &self,
+        id: &str,
+        bundle: &ProfileBundle,
+        mode: BundleMode,
+        replace: bool,
+    ) -> Result<Vpn, UseCaseError> {
+        Vpn::validate_id(id).map_err(|e| UseCaseError::InvalidId(e.to_string()))?;
+
+        let existing = self
+            .repository
+            .find_by_id(id)
+            .await
+            .map_err(|e| UseCaseError::RepositoryError(e.to_string()))?;
+        if existing.is_some() && !replace {
+            return Err(UseCaseError::AlreadyExists(id.to_string()));
+        }
+
+        let content = match mode {
+            BundleMode::Inline => bundle.inlined().map_err(|e| UseCaseError::InvalidProfile(e.to_string()))?,
+            BundleMode::Copy => String::new(),
+        };
+        // Inline mode still clears files left by an earlier copy-mode import.
+        let files = if mode == BundleMode::Copy { bundle.files() } else { &[] };
+        let dir = self
+            .repository
+            .store_files(id, files)
+            .await
+            .map_err(|e| UseCaseError::RepositoryError(e.to_string()))?;
+        let content = match mode {
+            BundleMode::Inline => content,
+            BundleMode::Copy => bundle.relocated(&dir),
+        };
+
+        let result = match existing {
+            Some(_) => self.repository.update_config(id, &content).await,
+            None => self.repository.create(id, &content).await,
+        };
+        result.map_err(|e| UseCaseError::RepositoryError(e.to_string()))
+    }
+}
+
+pub struct UpdateProfileUseCase {
+    repository: Arc<dyn VpnRepository>,
+}
+
+impl UpdateProfileUseCase {
+    pub fn new(repository: Arc<dyn VpnRepository>) -> Self {
+        Self { repository }
+    }
+
+    pub async fn execute(&self, id: &str, content: &str) -> Result<Vpn, UseCaseError> {
+        self.repository
+            .find_by_id(id)
+            .await
+            .map_err(|e| UseCaseError::RepositoryError(e.to_string()))?
+            .ok_or_else(|| UseCaseError::VpnNotFound(id.to_string()))?;
+        OvpnConfig::parse(content).map_err(|e| UseCaseError::InvalidProfile(e.to_string()))?;
+
+        self.repository
+            .update_config(id, content)
+            .await
+            .map_err(|e| UseCaseError::RepositoryError(e.to_string()))
+    }
+}
+
+pub struct RenameProfileUseCase {
+    repository: Arc<dyn VpnRepository>,
+}
+
+impl RenameProfileUseCase {
+    pub fn new(repository: Arc<dyn VpnRepository>) -> Self {
+        Self { repository }
+    }
+
+    pub async fn execute(&self, id: &str, new_id: &str) -> Result<Vpn, UseCaseError> {
+        Vpn::validate_id(new_id).map_err(|e| UseCaseError::InvalidId(e.to_string()))?;
+        let vpn = self
+            .repository
+            .find_by_id(id)
+            .await
+            .map_err(|e| UseCaseError::RepositoryError(e.to_string()))?
+            .ok_or_else(|| UseCaseError::VpnNotFound(id.to_string()))?;
+        if id == new_id {
+            return Ok(vpn);
+        }
+
+        let taken = self
+            .repository
+            .find_by_id(new_id)
+            .await
+            .map_err(|e| UseCaseError::RepositoryError(e.to_string()))?;
+        if taken.is_some() {
+            return Err(UseCaseError::AlreadyExists(new_id.to_string()));
+        }
+
+        self.repository
+            .rename(id, new_id)
+            .await
+            .map_err(|e| UseCaseError::RepositoryError(e.to_string()))
+    }
+}
+
+pub struct RemoveProfileUseCase {
+    repository: Arc<dyn VpnRepository>,
+}
+
+impl RemoveProfileUseCase {
+    pub fn new(repository: Arc<dyn VpnRepository>) -> Self {
+        Self { repository }
+    }
+
+    /// Returns the profile as it was before removal.
+    pub async fn execute(&self, id: &str) -> Result<Vpn, UseCaseError> {
+        let vpn = self
+            .repository
+            .find_by_id(id)
+            .await
+            .map_err(|e| UseCaseError::RepositoryError(e.to_string()))?
+            .ok_or_else(|| UseCaseError::VpnNotFound(id.to_string()))?;
+
+        self.repository
+            .delete(id)
+            .await
+            .map_err(|e| UseCaseError::RepositoryError(e.to_string()))?;
+        Ok(vpn)
+    }
+}
+
+pub struct SetDisplayNameUseCase {
+    repository: Arc<dyn VpnRepository>,
+}
+
+impl SetDisplayNameUseCase {
+    pub fn new(repository: Arc<dyn VpnRepository>) -> Self {
+        Self { repository }
+    }
+
+    /// `None` (or a blank name) goes back to showing the id.
+    pub async fn execute(&self, id: &str, name: Option<String>) -> Result<Vpn, UseCaseError> {
+        let mut vpn = self
+            .repository
+            .find_by_id(id)
+            .await
+            .map_err(|e| UseCaseError::RepositoryError(e.to_string()))?
+            .ok_or_else(|| UseCaseError::VpnNotFound(id.to_string()))?;
+
+        let mut metadata = vpn.metadata().clone();
+        metadata.set_display_name(name);
+        vpn.set_metadata(metadata);
+
+        self.repository
+            .save(&vpn)
+            .await
+            .map_err(|e| UseCaseError::RepositoryError(e.to_string()))?;
+        Ok(vpn)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::entities::Vpn;
+    use crate::domain::{bundle::BundledFile, entities::Vpn};
     use async_trait::async_trait;
     use std::collections::HashMap;
+    use std::path::PathBuf;
     use std::sync::Mutex;
 
     pub struct MockVpnRepository {
@@ -132,5 +312,60 @@ mod tests {
             let vpns = self.vpns.lock().unwrap();
             Ok(vpns.values().cloned().collect())
         }
+
+        async fn create(&self, id: &str, _content: &str) -> Result<Vpn, Box<dyn std::error::Error>> {
+            let vpn = Vpn::new(id.to_string(), id.to_string(), format!("/{}.ovpn", id));
+            self.add_vpn(vpn.clone());
+            Ok(vpn)
+        }
+
+        async fn update_config(&self, id: &str, _content: &str) -> Result<Vpn, Box<dyn std::error::Error>> {
+            let vpns = self.vpns.lock().unwrap();
+            Ok(vpns.get(id).cloned().ok_or("not found")?)
+        }
+
+        async fn rename(&self, id: &str, new_id: &str) -> Result<Vpn, Box<dyn std::error::Error>> {
+            let mut vpns = self.vpns.lock().unwrap();
+            let old = vpns.remove(id).ok_or("not found")?;
+            let vpn = Vpn::new(new_id.to_string(), old.display_name().to_string(), format!("/{}.ovpn", new_id));
+            vpns.insert(new_id.to_string(), vpn.clone());
+            Ok(vpn)
+        }
+
+        async fn store_files(&self, id: &str, _files: &[BundledFile]) -> Result<PathBuf, Box<dyn std::error::Error>> {
+            Ok(PathBuf::from(format!("/{}.d", id)))
+        }
+
+        async fn delete(&self, id: &str) -> Result<(), Box<dyn std::error::Error>> {
+            self.vpns.lock().unwrap().remove(id);
+            Ok(())
+        }
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn import_refuses_to_overwrite_without_replace() {
+        let repo = Arc::new(MockVpnRepository::new());
+        repo.add_vpn(Vpn::new("vpn1".to_string(), "VPN 1".to_string(), "/path1".to_string()));
+        let use_case = ImportProfileUseCase::new(repo);
+
+        let bundle = ProfileBundle::new(OvpnConfig::parse("client\n").unwrap());
+        let error = use_case.execute("vpn1", &bundle, BundleMode::Copy, false).await.unwrap_err();
+        assert!(matches!(error, UseCaseError::AlreadyExists(_)));
+        assert!(use_case.execute("vpn1", &bundle, BundleMode::Copy, true).await.is_ok());
+        assert!(matches!(
+            use_case.execute("../etc", &bundle, BundleMode::Inline, false).await.unwrap_err(),
+            UseCaseError::InvalidId(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn rename_rejects_taken_ids() {
+        let repo = Arc::new(MockVpnRepository::new());
+        repo.add_vpn(Vpn::new("vpn1".to_string(), "VPN 1".to_string(), "/path1".to_string()));
+        repo.add_vpn(Vpn::new("vpn2".to_string(), "VPN 2".to_string(), "/path2".to_string()));
+        let use_case = RenameProfileUseCase::new(repo);
+
+        assert!(matches!(use_case.execute("vpn1", "vpn2").await.unwrap_err(), UseCaseError::AlreadyExists(_)));
+        assert_eq!(use_case.execute("vpn1", "office").await.unwrap().id(), "office");
+    }
+}
@@ -0,0 +1,467 @@
+use crate::domain::ovpn::{OvpnConfig, OvpnParseError, FILE_DIRECTIVES, INLINE_FILE};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        f.write_str(label)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Finding {
+    pub severity: Severity,
+    /// Stable rule identifier, e.g. `missing-remote`.
+    pub rule: String,
+    pub message: String,
+    /// 1-based line the finding refers to, when it points at a specific line.
+    pub line: Option<usize>,
+    /// Why the finding matters and how to address it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+impl Finding {
+    pub fn new(severity: Severity, rule: &str, message: String, line: Option<usize>) -> Self {
+        Self {
+            severity,
+            rule: rule.to_string(),
+            message,
+            line,
+            hint: None,
+        }
+    }
+
+    pub fn with_hint(mut self, hint: &str) -> Self {
+        self.hint = Some(hint.to_string());
+        self
+    }
+}
+
+/// All findings for one profile file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationReport {
+    pub file: String,
+    pub findings: Vec<Finding>,
+}
+
+impl ValidationReport {
+    pub fn new(file: String, mut findings: Vec<Finding>) -> Self {
+        findings.sort_by_key(|finding| (finding.line.unwrap_or(0), std::cmp::Reverse(finding.severity)));
+        Self { file, findings }
+    }
+
+    /// Adds findings from another pass over the same file.
+    pub fn merge(&mut self, findings: Vec<Finding>) {
+        let merged = std::mem::take(&mut self.findings).into_iter().chain(findings).collect();
+        *self = Self::new(std::mem::take(&mut self.file), merged);
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.count(Severity::Error) > 0
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.findings.iter().filter(|f| f.severity == severity).count()
+    }
+
+    /// `file:line: severity: message [rule]`, the format editors and CI logs understand.
+    pub fn format_finding(&self, finding: &Finding) -> String {
+        let location = match finding.line {
+            Some(line) => format!("{}:{}", self.file, line),
+            None => self.file.clone(),
+        };
+        format!("{}: {}: {} [{}]", location, finding.severity, finding.message, finding.rule)
+    }
+}
+
+/// Rule-based checks that catch profiles OpenVPN would refuse or misbehave on.
+pub struct ConfigValidator {
+    base_dir: Option<PathBuf>,
+}
+
+impl ConfigValidator {
+    /// `base_dir` is where relative file references are resolved, normally the
+    /// profile's own directory. Without it file existence checks are skipped.
+    pub fn new(base_dir: Option<PathBuf>) -> Self {
+        Self { base_dir }
+    }
+
+    pub fn for_profile(config_path: &Path) -> Self {
+        Self::new(config_path.parent().map(Path::to_path_buf))
+    }
+
+    /// Parses and validates raw profile text; parse failures become findings.
+    pub fn validate_source(&self, source: &str) -> Vec<Finding> {
+        match OvpnConfig::parse(source) {
+            Ok(config) => self.validate(&config),
+            Err(e) => {
+                let line = match &e {
+                    OvpnParseError::UnterminatedQuote { line }
+                    | OvpnParseError::UnclosedBlock { line, .. }
+                    | OvpnParseError::UnexpectedClosingTag { line, .. } => *line,
+                };
+                vec![Finding::new(Severity::Error, "syntax", e.to_string(), Some(line))]
+            }
+        }
+    }
+
+    pub fn validate(&self, config: &OvpnConfig) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        self.check_unknown_options(config, &mut findings);
+        self.check_remote(config, &mut findings);
+        self.check_credentials(config, &mut findings);
+        self.check_file_references(config, &mut findings);
+        self.check_conflicts(config, &mut findings);
+        self.check_duplicates(config, &mut findings);
+        self.check_dev_and_proto(config, &mut findings);
+        findings
+    }
+
+    fn is_client(config: &OvpnConfig) -> bool {
+        config.has_directive("client") || config.has_directive("tls-client") || config.has_directive("pull")
+    }
+
+    fn has_material(config: &OvpnConfig, name: &str) -> bool {
+        config.has_directive(name) || config.inline_block(name).is_some()
+    }
+
+    fn check_unknown_options(&self, config: &OvpnConfig, findings: &mut Vec<Finding>) {
+        for directive in config.directives() {
+            if !KNOWN_OPTIONS.contains(&directive.name()) {
+                findings.push(Finding::new(
+                    Severity::Warning,
+                    "unknown-option",
+                    format!("unknown option '{}'", directive.name()),
+                    Some(directive.line()),
+                ));
+            }
+        }
+        for block in config.inline_blocks() {
+            if !INLINE_TAGS.contains(&block.tag()) {
+                findings.push(Finding::new(
+                    Severity::Warning,
+                    "unknown-option",
+                    format!("unknown inline block <{}>", block.tag()),
+                    Some(block.line()),
+                ));
+            }
+        }
+    }
+
+    fn check_remote(&self, config: &OvpnConfig, findings: &mut Vec<Finding>) {
+        if !Self::is_client(config) || config.has_directive("remote") || config.inline_block("connection").is_some() {
+            return;
+        }
+        findings.push(Finding::new(
+            Severity::Error,
+            "missing-remote",
+            "client profile has no 'remote' server to connect to".to_string(),
+            None,
+        ));
+    }
+
+    fn check_credentials(&self, config: &OvpnConfig, findings: &mut Vec<Finding>) {
+        if !Self::is_client(config) || config.has_directive("secret") {
+            return;
+        }
+
+        let has_pkcs12 = Self::has_material(config, "pkcs12");
+        if !has_pkcs12 && !Self::has_material(config, "ca") {
+            findings.push(Finding::new(
+                Severity::Error,
+                "missing-ca",
+                "no 'ca' certificate to verify the server (directive or <ca> block)".to_string(),
+                None,
+            ));
+        }
+
+        let has_cert = Self::has_material(config, "cert");
+        let has_key = Self::has_material(config, "key");
+        // Hardware tokens supply both halves of the client identity.
+        let external_key = config.has_directive("pkcs11-id") || config.has_directive("management-external-key");
+        match (has_cert, has_key || external_key) {
+            (true, false) => findings.push(Finding::new(
+                Severity::Error,
+                "missing-key",
+                "'cert' is set but there is no matching 'key'".to_string(),
+                config.directive("cert").map(|d| d.line()),
+            )),
+            (false, true) if !external_key => findings.push(Finding::new(
+                Severity::Error,
+                "missing-cert",
+                "'key' is set but there is no matching 'cert'".to_string(),
+                config.directive("key").map(|d| d.line()),
+            )),
+            (false, false) if !has_pkcs12 && !Self::has_material(config, "auth-user-pass") => {
+                findings.push(Finding::new(
+                    Severity::Error,
+                    "missing-cert",
+                    "no client credentials: expected 'cert'/'key', 'pkcs12' or 'auth-user-pass'".to_string(),
+                    None,
+                ))
+            }
+            _ => {}
+        }
+    }
+
+    fn check_file_references(&self, config: &OvpnConfig, findings: &mut Vec<Finding>) {
+        let base_dir = match &self.base_dir {
+            Some(dir) => dir.clone(),
+            None => return,
+        };
+        // An absolute `cd` moves OpenVPN's working directory before files are opened.
+        let base_dir = match config.directive("cd").and_then(|d| d.arg(0)) {
+            Some(cd) => base_dir.join(cd),
+            None => base_dir,
+        };
+
+        for reference in config.file_references() {
+            let path = base_dir.join(&reference.path);
+            if !path.exists() {
+                findings.push(Finding::new(
+                    Severity::Error,
+                    "missing-file",
+                    format!("'{}' refers to {} which does not exist", reference.directive, path.display()),
+                    Some(reference.line),
+                ));
+            }
+        }
+
+        for directive in config.directives() {
+            let uses_inline = directive.arg(0) == Some(INLINE_FILE);
+            if uses_inline && config.inline_block(directive.name()).is_none() {
+                findings.push(Finding::new(
+                    Severity::Error,
+                    "missing-file",
+                    format!("'{}' is marked [inline] but there is no <{}> block", directive.name(), directive.name()),
+                    Some(directive.line()),
+                ));
+            }
+        }
+    }
+
+    fn check_conflicts(&self, config: &OvpnConfig, findings: &mut Vec<Finding>) {
+        for (first, second) in CONFLICTING_OPTIONS {
+            if let (Some(_), Some(line)) = (Self::material_line(config, first), Self::material_line(config, second)) {
+                findings.push(Finding::new(
+                    Severity::Error,
+                    "conflicting-options",
+                    format!("'{}' cannot be combined with '{}'", second, first),
+                    Some(line),
+                ));
+            }
+        }
+
+        for tag in FILE_DIRECTIVES {
+            let directive = config
+                .directive(tag)
+                .filter(|d| d.arg(0).is_some_and(|arg| arg != INLINE_FILE));
+            if let (Some(directive), Some(_)) = (directive, config.inline_block(tag)) {
+                findings.push(Finding::new(
+                    Severity::Error,
+                    "conflicting-options",
+                    format!("'{}' is given both as a file and as an inline <{}> block", tag, tag),
+                    Some(directive.line()),
+                ));
+            }
+        }
+    }
+
+    /// Line where `name` is configured, either as a directive or an inline block.
+    fn material_line(config: &OvpnConfig, name: &str) -> Option<usize> {
+        config
+            .directive(name)
+            .map(|directive| directive.line())
+            .or_else(|| config.inline_block(name).map(|block| block.line()))
+    }
+
+    fn check_duplicates(&self, config: &OvpnConfig, findings: &mut Vec<Finding>) {
+        for name in SINGLE_VALUED_OPTIONS {
+            for duplicate in config.directives_named(name).skip(1) {
+                findings.push(Finding::new(
+                    Severity::Warning,
+                    "duplicate-option",
+                    format!("'{}' is set more than once; only the last value takes effect", name),
+                    Some(duplicate.line()),
+                ));
+            }
+        }
+    }
+
+    fn check_dev_and_proto(&self, config: &OvpnConfig, findings: &mut Vec<Finding>) {
+        let dev = config.directive("dev");
+        let dev_name = dev.and_then(|d| d.arg(0));
+        if dev.is_none() {
+            findings.push(Finding::new(
+                Severity::Error,
+                "missing-dev",
+                "no 'dev' directive; OpenVPN needs 'dev tun' or 'dev tap'".to_string(),
+                None,
+            ));
+        }
+
+        let dev_type = config.directive("dev-type");
+        let declared = dev_type.and_then(|d| d.arg(0));
+        let implied = dev_name.and_then(|name| ["tun", "tap"].into_iter().find(|kind| name.starts_with(kind)));
+        match (dev_name, declared, implied) {
+            (_, Some(declared), _) if !["tun", "tap"].contains(&declared) => findings.push(Finding::new(
+                Severity::Error,
+                "dev-mismatch",
+                format!("invalid dev-type '{}'", declared),
+                dev_type.map(|d| d.line()),
+            )),
+            (Some(name), None, None) => findings.push(Finding::new(
+                Severity::Error,
+                "dev-mismatch",
+                format!("device '{}' is neither tun nor tap; add 'dev-type tun' or 'dev-type tap'", name),
+                dev.map(|d| d.line()),
+            )),
+            (Some(name), Some(declared), Some(implied)) if declared != implied => findings.push(Finding::new(
+                Severity::Error,
+                "dev-mismatch",
+                format!("device '{}' conflicts with 'dev-type {}'", name, declared),
+                dev_type.map(|d| d.line()),
+            )),
+            _ => {}
+        }
+        let kind = declared.or(implied);
+
+        if kind == Some("tap") {
+            if let Some(topology) = config.directive("topology") {
+                findings.push(Finding::new(
+                    Severity::Warning,
+                    "dev-mismatch",
+                    "'topology' only applies to tun devices".to_string(),
+                    Some(topology.line()),
+                ));
+            }
+        }
+
+        let mut protocols = Vec::new();
+        if let Some(proto) = config.directive("proto") {
+            match proto.arg(0) {
+                Some(value) if VALID_PROTOCOLS.contains(&value) => protocols.push((value, proto.line())),
+                value => findings.push(Finding::new(
+                    Severity::Error,
+                    "proto-mismatch",
+                    format!("invalid protocol '{}'", value.unwrap_or("")),
+                    Some(proto.line()),
+                )),
+            }
+        }
+        for remote in config.directives_named("remote") {
+            match remote.arg(2) {
+                Some(value) if VALID_PROTOCOLS.contains(&value) => protocols.push((value, remote.line())),
+                Some(value) => findings.push(Finding::new(
+                    Severity::Error,
+                    "proto-mismatch",
+                    format!("invalid protocol '{}' on remote", value),
+                    Some(remote.line()),
+                )),
+                None => {}
+            }
+        }
+
+        let uses_tcp = protocols.iter().any(|(p, _)| p.starts_with("tcp"));
+        let uses_udp = protocols.is_empty() || protocols.iter().any(|(p, _)| p.starts_with("udp"));
+        for (name, udp_only) in [("fragment", true), ("explicit-exit-notify", false)] {
+            let directive = match config.directive(name) {
+                Some(directive) => directive,
+                None => continue,
+            };
+            if uses_tcp && !uses_udp {
+                findings.push(Finding::new(
+                    if udp_only { Severity::Error } else { Severity::Warning },
+                    "proto-mismatch",
+                    format!("'{}' only works over UDP but this profile uses TCP", name),
+                    Some(directive.line()),
+                ));
+            }
+        }
+    }
+}
+
+const VALID_PROTOCOLS: &[&str] = &[
+    "udp", "udp4", "udp6", "tcp", "tcp4", "tcp6", "tcp-client", "tcp4-client", "tcp6-client", "tcp-server",
+    "tcp4-server", "tcp6-server",
+];
+
+const CONFLICTING_OPTIONS: &[(&str, &str)] = &[
+    ("client", "server"),
+    ("tls-auth", "tls-crypt"),
+    ("tls-auth", "tls-crypt-v2"),
+    ("tls-crypt", "tls-crypt-v2"),
+    ("pkcs12", "cert"),
+    ("secret", "client"),
+    ("secret", "tls-client"),
+];
+
+const SINGLE_VALUED_OPTIONS: &[&str] = &[
+    "dev", "dev-type", "proto", "ca", "cert", "key", "pkcs12", "tls-auth", "tls-crypt", "cipher", "auth",
+    "verb", "topology", "tun-mtu", "port", "key-direction", "remote-cert-tls",
+];
+
+const INLINE_TAGS: &[&str] = &[
+    "ca", "cert", "key", "extra-certs", "dh", "pkcs12", "tls-auth", "tls-crypt", "tls-crypt-v2", "secret",
+    "crl-verify", "auth-user-pass", "http-proxy-user-pass", "connection",
+];
+
+/// Options understood by OpenVPN 2.6, used to flag typos and foreign syntax.
+const KNOWN_OPTIONS: &[&str] = &[
+    "allow-compression", "allow-pull-fqdn", "allow-recursive-routing", "askpass", "auth", "auth-gen-token",
+    "auth-gen-token-secret", "auth-nocache", "auth-retry", "auth-token", "auth-token-user", "auth-user-pass",
+    "auth-user-pass-optional", "auth-user-pass-verify", "bcast-buffers", "bind", "block-ipv6", "block-outside-dns",
+    "ca", "capath", "cd", "cert", "chroot", "cipher", "client", "client-cert-not-required", "client-config-dir",
+    "client-connect", "client-crresponse", "client-disconnect", "client-nat", "client-to-client", "comp-lzo",
+    "comp-noadapt", "compat-mode", "compress", "config", "connect-freq", "connect-retry", "connect-retry-max",
+    "connect-timeout", "crl-verify", "cryptoapicert", "daemon", "data-ciphers", "data-ciphers-fallback", "dh",
+    "dhcp-option", "dev", "dev-node", "dev-type", "disable", "disable-dco", "disable-occ", "down", "down-pre",
+    "duplicate-cn", "ecdh-curve", "engine", "errors-to-stderr", "explicit-exit-notify", "extra-certs", "fast-io",
+    "float", "force-tls-key-material-export", "fragment", "group", "hand-window", "hash-size", "http-proxy",
+    "http-proxy-option", "http-proxy-user-pass", "ifconfig", "ifconfig-ipv6", "ifconfig-ipv6-pool",
+    "ifconfig-ipv6-push", "ifconfig-noexec", "ifconfig-nowarn", "ifconfig-pool", "ifconfig-pool-persist",
+    "ifconfig-push", "ignore-unknown-option", "inactive", "ip-remote-hint", "ipchange", "iroute", "iroute-ipv6",
+    "keepalive", "key", "key-direction", "key-method", "keying-material-exporter", "learn-address", "link-mtu",
+    "lladdr", "local", "log", "log-append", "lport", "machine-readable-output", "management",
+    "management-client", "management-client-auth", "management-client-group", "management-client-user",
+    "management-external-cert", "management-external-key", "management-forget-disconnect", "management-hold",
+    "management-log-cache", "management-query-passwords", "management-query-proxy", "management-query-remote",
+    "management-signal", "management-up-down", "mark", "max-clients", "max-routes-per-client", "mlock", "mode",
+    "mssfix", "mtu-disc", "mtu-test", "multihome", "mute", "mute-replay-warnings", "ncp-ciphers", "ncp-disable",
+    "netns", "nice", "nobind", "ns-cert-type", "opt-verify", "passtos", "peer-fingerprint", "peer-id",
+    "persist-key", "persist-local-ip", "persist-remote-ip", "persist-tun", "ping", "ping-exit", "ping-restart",
+    "ping-timer-rem", "pkcs11-cert-private", "pkcs11-id", "pkcs11-id-management", "pkcs11-pin-cache",
+    "pkcs11-private-mode", "pkcs11-protected-authentication", "pkcs11-providers", "pkcs12", "plugin", "port",
+    "port-share", "preresolve", "prng", "proto", "proto-force", "pull", "pull-filter", "push",
+    "push-continuation", "push-peer-info", "push-remove", "push-reset", "rcvbuf", "redirect-gateway",
+    "redirect-private", "remap-usr1", "remote", "remote-cert-eku", "remote-cert-ku", "remote-cert-tls",
+    "remote-random", "remote-random-hostname", "renegotiate-bytes", "renegotiate-packets", "renegotiate-seconds",
+    "replay-persist", "replay-window", "reneg-bytes", "reneg-pkts", "reneg-sec", "resolv-retry", "route",
+    "route-delay", "route-gateway", "route-ipv6", "route-ipv6-gateway", "route-metric", "route-noexec",
+    "route-nopull", "route-pre-down", "route-up", "rport", "script-security", "secret", "server",
+    "server-bridge", "server-ipv6", "server-poll-timeout", "session-timeout", "setcon", "setenv", "setenv-safe",
+    "shaper", "show-net-up", "single-session", "sndbuf", "socket-flags", "socks-proxy", "stale-routes-check",
+    "static-challenge", "status", "status-version", "suppress-timestamps", "syslog", "tcp-nodelay",
+    "tcp-queue-limit", "test-crypto", "tls-auth", "tls-cert-profile", "tls-cipher", "tls-ciphersuites",
+    "tls-client", "tls-crypt", "tls-crypt-v2", "tls-crypt-v2-verify", "tls-exit", "tls-export-cert",
+    "tls-groups", "tls-server", "tls-timeout", "tls-verify", "tls-version-max", "tls-version-min", "tmp-dir",
+    "topology", "tran-window", "tun-ipv6", "tun-max-mtu", "tun-mtu", "tun-mtu-extra", "txqueuelen", "up",
+    "up-delay", "up-restart", "use-prediction-resistance", "user", "username-as-common-name", "verb",
+    "verify-client-cert", "verify-hash", "verify-x509-name", "vlan-accept", "vlan-pvid", "vlan-tagging",
+    "win-sys", "writepid", "x509-track", "x509-username-field",
+];
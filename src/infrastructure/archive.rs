@@ -0,0 +1,281 @@
+use crate::domain::{
+    bundle::ProfileBundle,
+    ovpn::{OvpnConfig, OvpnParseError},
+};
+use crate::infrastructure::files::write_private;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use thiserror::Error;
+
+/// Provider bundles hold a few hundred small text files; these limits keep a
+/// hostile archive from exhausting memory.
+const MAX_ENTRY_SIZE: u64 = 1024 * 1024;
+const MAX_TOTAL_SIZE: u64 = 64 * 1024 * 1024;
+const MAX_ENTRIES: usize = 10_000;
+
+#[derive(Error, Debug)]
+pub enum ArchiveError {
+    #[error("cannot read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("{path}: {source}")]
+    Zip {
+        path: PathBuf,
+        #[source]
+        source: zip::result::ZipError,
+    },
+    #[error("{0}: unsupported archive, expected .zip, .tar, .tar.gz or .tgz")]
+    Unsupported(PathBuf),
+    #[error("{0}: archive is too large to import")]
+    TooLarge(PathBuf),
+    #[error("{entry} is not in the archive")]
+    MissingEntry { entry: String },
+    #[error("{entry}: profile is not UTF-8 text")]
+    NotText { entry: String },
+    #[error("{entry}: {source}")]
+    Parse {
+        entry: String,
+        #[source]
+        source: OvpnParseError,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+impl ArchiveFormat {
+    /// Format implied by the file name, if it is an archive at all.
+    pub fn detect(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_string_lossy().to_lowercase();
+        if name.ends_with(".zip") {
+            Some(Self::Zip)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(Self::Tar)
+        } else {
+            None
+        }
+    }
+}
+
+/// Writes `files` (relative path → content) as a .zip, .tar or .tar.gz
+/// chosen by the extension of `path`. Entries and the archive itself are
+/// owner-only, since exported profiles may carry keys.
+pub fn write_archive(path: &Path, files: &BTreeMap<String, Vec<u8>>) -> Result<(), ArchiveError> {
+    let format = ArchiveFormat::detect(path).ok_or_else(|| ArchiveError::Unsupported(path.to_path_buf()))?;
+    let io_error = |source| ArchiveError::Io { path: path.to_path_buf(), source };
+
+    // Built in memory, like the files themselves, and written in one go.
+    let archive = match format {
+        ArchiveFormat::Zip => {
+            let zip_error = |source| ArchiveError::Zip { path: path.to_path_buf(), source };
+            let options = zip::write::SimpleFileOptions::default().unix_permissions(0o600);
+            let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+            for (name, content) in files {
+                zip.start_file(name.as_str(), options).map_err(zip_error)?;
+                zip.write_all(content).map_err(io_error)?;
+            }
+            zip.finish().map_err(zip_error)?.into_inner()
+        }
+        ArchiveFormat::Tar => write_tar(Vec::new(), files).map_err(io_error)?,
+        ArchiveFormat::TarGz => {
+            let encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            write_tar(encoder, files).and_then(|encoder| encoder.finish()).map_err(io_error)?
+        }
+    };
+    write_private(path, &archive).map_err(io_error)
+}
+
+fn write_tar<W: Write>(writer: W, files: &BTreeMap<String, Vec<u8>>) -> std::io::Result<W> {
+    let mtime = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+    let mut tar = tar::Builder::new(writer);
+    for (name, content) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o600);
+        header.set_mtime(mtime);
+        header.set_cksum();
+        tar.append_data(&mut header, name, content.as_slice())?;
+    }
+    tar.into_inner()
+}
+
+/// The regular files of a provider archive, read into memory and keyed by
+/// their normalized relative path. Nothing is extracted to disk.
+#[derive(Debug)]
+pub struct ProfileArchive {
+    path: PathBuf,
+    files: BTreeMap<String, Vec<u8>>,
+}
+
+impl ProfileArchive {
+    pub fn is_archive(path: &Path) -> bool {
+        ArchiveFormat::detect(path).is_some()
+    }
+
+    pub fn open(path: &Path) -> Result<Self, ArchiveError> {
+        let format = ArchiveFormat::detect(path).ok_or_else(|| ArchiveError::Unsupported(path.to_path_buf()))?;
+        let io_error = |source| ArchiveError::Io { path: path.to_path_buf(), source };
+        let file = File::open(path).map_err(io_error)?;
+
+        let mut archive = Self { path: path.to_path_buf(), files: BTreeMap::new() };
+        let mut total = 0;
+        match format {
+            ArchiveFormat::Zip => {
+                let zip_error = |source| ArchiveError::Zip { path: path.to_path_buf(), source };
+                let mut zip = zip::ZipArchive::new(file).map_err(zip_error)?;
+                for index in 0..zip.len() {
+                    let entry = zip.by_index(index).map_err(zip_error)?;
+                    if !entry.is_file() {
+                        continue;
+                    }
+                    let Some(name) = entry.enclosed_name() else { continue };
+                    let size = entry.size();
+                    archive.add_entry(&name, size, entry, &mut total)?;
+                }
+            }
+            ArchiveFormat::Tar => archive.read_tar(file, &mut total)?,
+            ArchiveFormat::TarGz => archive.read_tar(flate2::read::GzDecoder::new(file), &mut total)?,
+        }
+        Ok(archive)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Paths of the OpenVPN profiles in the archive, in path order.
+    pub fn profiles(&self) -> Vec<&str> {
+        self.files
+            .iter()
+            .filter(|(name, content)| Self::is_profile(name, content))
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    /// The profile at `entry` with its references resolved inside the archive.
+    pub fn bundle(&self, entry: &str) -> Result<ProfileBundle, ArchiveError> {
+        let bytes = self
+            .files
+            .get(entry)
+            .ok_or_else(|| ArchiveError::MissingEntry { entry: entry.to_string() })?;
+        let text = std::str::from_utf8(bytes).map_err(|_| ArchiveError::NotText { entry: entry.to_string() })?;
+        let config = OvpnConfig::parse(text).map_err(|source| ArchiveError::Parse { entry: entry.to_string(), source })?;
+
+        let dir = entry.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
+        let mut bundle = ProfileBundle::new(config.clone());
+        for reference in config.file_references() {
+            match self.resolve(dir, &reference.path) {
+                Some(content) => bundle.add_file(&reference.directive, &reference.path, content.clone()),
+                None => bundle.add_unresolved(reference),
+            }
+        }
+        Ok(bundle)
+    }
+
+    fn read_tar<R: Read>(&mut self, reader: R, total: &mut u64) -> Result<(), ArchiveError> {
+        let path = self.path.clone();
+        let io_error = |source| ArchiveError::Io { path: path.clone(), source };
+        let mut tar = tar::Archive::new(reader);
+        for entry in tar.entries().map_err(io_error)? {
+            let entry = entry.map_err(io_error)?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let name = entry.path().map_err(io_error)?.into_owned();
+            let size = entry.size();
+            self.add_entry(&name, size, entry, total)?;
+        }
+        Ok(())
+    }
+
+    fn add_entry(&mut self, name: &Path, size: u64, reader: impl Read, total: &mut u64) -> Result<(), ArchiveError> {
+        let Some(name) = Self::normalize(name) else { return Ok(()) };
+        // macOS resource forks shadow every real file with binary junk.
+        if name.starts_with("__MACOSX/") || size > MAX_ENTRY_SIZE {
+            return Ok(());
+        }
+        *total += size;
+        if *total > MAX_TOTAL_SIZE || self.files.len() >= MAX_ENTRIES {
+            return Err(ArchiveError::TooLarge(self.path.clone()));
+        }
+
+        let mut content = Vec::with_capacity(size as usize);
+        reader
+            .take(MAX_ENTRY_SIZE)
+            .read_to_end(&mut content)
+            .map_err(|source| ArchiveError::Io { path: self.path.clone(), source })?;
+        self.files.insert(name, content);
+        Ok(())
+    }
+
+    /// Relative `/`-separated path, or `None` for entries escaping the root.
+    fn normalize(path: &Path) -> Option<String> {
+        let mut parts = Vec::new();
+        for component in path.components() {
+            match component {
+                Component::Normal(part) => parts.push(part.to_string_lossy().to_string()),
+                Component::CurDir | Component::RootDir => {}
+                Component::ParentDir => {
+                    parts.pop()?;
+                }
+                Component::Prefix(_) => return None,
+            }
+        }
+        (!parts.is_empty()).then(|| parts.join("/"))
+    }
+
+    fn is_profile(name: &str, content: &[u8]) -> bool {
+        let lower = name.to_lowercase();
+        let file_name = lower.rsplit('/').next().unwrap_or(&lower);
+        if file_name.starts_with('.') {
+            return false;
+        }
+        if lower.ends_with(".ovpn") {
+            return true;
+        }
+        // `.conf` is also used for unrelated files; only client profiles count.
+        lower.ends_with(".conf")
+            && std::str::from_utf8(content)
+                .ok()
+                .and_then(|text| OvpnConfig::parse(text).ok())
+                .is_some_and(|config| config.has_directive("client") || config.has_directive("remote"))
+    }
+
+    /// Looks `reference` up next to the profile, falling back to a file with
+    /// the same name elsewhere in the archive when exactly one exists. The
+    /// fallback covers profiles written for a flat install directory.
+    fn resolve(&self, profile_dir: &str, reference: &str) -> Option<&Vec<u8>> {
+        let reference_path = Path::new(reference);
+        if reference_path.is_relative() {
+            if let Some(path) = Self::normalize(&Path::new(profile_dir).join(reference_path)) {
+                if let Some(content) = self.files.get(&path) {
+                    return Some(content);
+                }
+            }
+        }
+
+        let file_name = reference_path.file_name()?.to_string_lossy().to_string();
+        let mut candidates = self
+            .files
+            .iter()
+            .filter(|(path, _)| path.rsplit('/').next() == Some(file_name.as_str()));
+        match (candidates.next(), candidates.next()) {
+            (Some((_, content)), None) => Some(content),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use crate::domain::{
+    bundle::ProfileBundle,
+    ovpn::{OvpnConfig, OvpnParseError},
+};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use tokio::fs;
+
+/// Anything bigger is not a certificate or key and is left where it is.
+const MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+#[derive(Error, Debug)]
+pub enum BundleReadError {
+    #[error("cannot read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("{path}: {source}")]
+    Parse {
+        path: PathBuf,
+        #[source]
+        source: OvpnParseError,
+    },
+}
+
+/// Reads a profile from disk along with every file it references, resolved
+/// the way OpenVPN would from the profile's directory.
+pub async fn read_bundle(source: &Path) -> Result<ProfileBundle, BundleReadError> {
+    let content = fs::read_to_string(source).await.map_err(|e| BundleReadError::Io {
+        path: source.to_path_buf(),
+        source: e,
+    })?;
+    let config = OvpnConfig::parse(&content).map_err(|e| BundleReadError::Parse {
+        path: source.to_path_buf(),
+        source: e,
+    })?;
+
+    let mut base_dir = source.parent().map(Path::to_path_buf).unwrap_or_default();
+    if let Some(cd) = config.directive("cd").and_then(|d| d.arg(0)) {
+        base_dir = base_dir.join(cd);
+    }
+    Ok(bundle_config(config, &base_dir).await)
+}
+
+/// Bundles an in-memory profile, resolving relative references against `base_dir`.
+pub async fn bundle_config(config: OvpnConfig, base_dir: &Path) -> ProfileBundle {
+    let mut bundle = ProfileBundle::new(config.clone());
+    for reference in config.file_references() {
+        match read_referenced_file(&base_dir.join(&reference.path)).await {
+            Some(content) => bundle.add_file(&reference.directive, &reference.path, content),
+            None => bundle.add_unresolved(reference),
+        }
+    }
+    bundle
+}
+
+/// Regular files only: `crl-verify <dir> dir` and device nodes stay as references.
+async fn read_referenced_file(path: &Path) -> Option<Vec<u8>> {
+    let metadata = fs::metadata(path).await.ok()?;
+    if !metadata.is_file() || metadata.len() > MAX_FILE_SIZE {
+        return None;
+    }
+    fs::read(path).await.ok()
+}
@@ -0,0 +1,263 @@
+use crate::domain::{credentials::Credentials, repositories::CredentialStore};
+use crate::infrastructure::files::write_private_async;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use tokio::fs;
+use tokio::sync::Mutex;
+
+pub const CREDENTIALS_FILE: &str = "credentials.enc";
+const KEY_FILE: &str = "credentials.key";
+const MAGIC: &[u8] = b"UOVPNCR1";
+const NONCE_LEN: usize = 12;
+const KEY_LEN: usize = 32;
+
+#[derive(Error, Debug)]
+pub enum CredentialStoreError {
+    #[error("cannot access {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("{0} is not a credential store")]
+    Corrupt(PathBuf),
+    #[error("{path} cannot be decrypted with {key_path}; remove it to start over")]
+    WrongKey { path: PathBuf, key_path: PathBuf },
+    #[error("{0} does not hold a valid key")]
+    InvalidKey(PathBuf),
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct CredentialRecord {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    password: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    secrets: BTreeMap<String, String>,
+}
+
+impl CredentialRecord {
+    fn is_empty(&self) -> bool {
+        self.username.is_none() && self.password.is_none() && self.secrets.is_empty()
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct CredentialFile {
+    #[serde(default)]
+    profiles: BTreeMap<String, CredentialRecord>,
+}
+
+/// Credentials encrypted with AES-256-GCM. The key lives apart from the
+/// profiles, so copying `~/.connectvpn.conf` to another machine or a backup
+/// does not carry usable passwords with it.
+#[derive(Debug)]
+pub struct EncryptedCredentialStore {
+    path: PathBuf,
+    key_path: PathBuf,
+    /// Serialises read-modify-write cycles within this process.
+    lock: Mutex<()>,
+}
+
+impl EncryptedCredentialStore {
+    pub fn new(path: PathBuf, key_path: PathBuf) -> Self {
+        Self {
+            path,
+            key_path,
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// `credentials.enc` in `config_dir`, keyed from `$XDG_DATA_HOME/ui-openvpn`.
+    pub fn for_user(config_dir: &Path) -> Self {
+        let data_dir = match std::env::var("XDG_DATA_HOME") {
+            Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => {
+                let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+                PathBuf::from(home).join(".local/share")
+            }
+        };
+        Self::new(config_dir.join(CREDENTIALS_FILE), data_dir.join("ui-openvpn").join(KEY_FILE))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn io_error(path: &Path) -> impl FnOnce(std::io::Error) -> CredentialStoreError + '_ {
+        move |source| CredentialStoreError::Io { path: path.to_path_buf(), source }
+    }
+
+    async fn read_key(&self) -> Result<Option<Key<Aes256Gcm>>, CredentialStoreError> {
+        match fs::read(&self.key_path).await {
+            Ok(bytes) if bytes.len() == KEY_LEN => Ok(Some(*Key::<Aes256Gcm>::from_slice(&bytes))),
+            Ok(_) => Err(CredentialStoreError::InvalidKey(self.key_path.clone())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(Self::io_error(&self.key_path)(e)),
+        }
+    }
+
+    async fn read_or_create_key(&self) -> Result<Key<Aes256Gcm>, CredentialStoreError> {
+        if let Some(key) = self.read_key().await? {
+            return Ok(key);
+        }
+        let key = Aes256Gcm::generate_key(OsRng);
+        if let Some(dir) = self.key_path.parent() {
+            create_private_dir(dir).await.map_err(Self::io_error(dir))?;
+        }
+        write_private_async(&self.key_path, key.as_slice()).await.map_err(Self::io_error(&self.key_path))?;
+        Ok(key)
+    }
+
+    async fn read_file(&self) -> Result<CredentialFile, CredentialStoreError> {
+        let bytes = match fs::read(&self.path).await {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(CredentialFile::default()),
+            Err(e) => return Err(Self::io_error(&self.path)(e)),
+        };
+        let wrong_key = || CredentialStoreError::WrongKey {
+            path: self.path.clone(),
+            key_path: self.key_path.clone(),
+        };
+        let key = self.read_key().await?.ok_or_else(wrong_key)?;
+
+        let sealed = bytes
+            .strip_prefix(MAGIC)
+            .filter(|sealed| sealed.len() > NONCE_LEN)
+            .ok_or_else(|| CredentialStoreError::Corrupt(self.path.clone()))?;
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        let plaintext = Aes256Gcm::new(&key)
+            .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: MAGIC })
+            .map_err(|_| wrong_key())?;
+        serde_json::from_slice(&plaintext).map_err(|_| CredentialStoreError::Corrupt(self.path.clone()))
+    }
+
+    /// Applies `change` to the record of `vpn_id` and saves the file when it
+    /// reports a modification. Emptied records are dropped.
+    async fn update<T>(
+        &self,
+        vpn_id: &str,
+        change: impl FnOnce(&mut CredentialRecord) -> (bool, T),
+    ) -> Result<T, CredentialStoreError> {
+        let _guard = self.lock.lock().await;
+        let mut file = self.read_file().await?;
+        let record = file.profiles.entry(vpn_id.to_string()).or_default();
+        let (modified, result) = change(record);
+        if record.is_empty() {
+            file.profiles.remove(vpn_id);
+        }
+        if modified {
+            self.write_file(&file).await?;
+        }
+        Ok(result)
+    }
+
+    async fn write_file(&self, file: &CredentialFile) -> Result<(), CredentialStoreError> {
+        let corrupt = || CredentialStoreError::Corrupt(self.path.clone());
+        let key = self.read_or_create_key().await?;
+        let plaintext = serde_json::to_vec(file).map_err(|_| corrupt())?;
+        let nonce = Aes256Gcm::generate_nonce(OsRng);
+        let ciphertext = Aes256Gcm::new(&key)
+            .encrypt(&nonce, Payload { msg: &plaintext, aad: MAGIC })
+            .map_err(|_| corrupt())?;
+
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&nonce);
+        bytes.extend_from_slice(&ciphertext);
+
+        write_private_async(&self.path, &bytes).await.map_err(Self::io_error(&self.path))
+    }
+}
+
+#[async_trait]
+impl CredentialStore for EncryptedCredentialStore {
+    async fn load(&self, vpn_id: &str) -> Result<Option<Credentials>, Box<dyn Error + Send + Sync>> {
+        let _guard = self.lock.lock().await;
+        let file = self.read_file().await?;
+        Ok(file.profiles.get(vpn_id).and_then(|record| {
+            Some(Credentials::new(record.username.as_deref()?, record.password.as_deref()?))
+        }))
+    }
+
+    async fn save(&self, vpn_id: &str, credentials: &Credentials) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.update(vpn_id, |record| {
+            record.username = Some(credentials.username().to_string());
+            record.password = Some(credentials.password().to_string());
+            (true, ())
+        })
+        .await?;
+        Ok(())
+    }
+
+    async fn remove(&self, vpn_id: &str) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let removed = self
+            .update(vpn_id, |record| {
+                let removed = record.username.take().is_some() | record.password.take().is_some();
+                (removed, removed)
+            })
+            .await?;
+        Ok(removed)
+    }
+
+    async fn load_secret(&self, vpn_id: &str, name: &str) -> Result<Option<String>, Box<dyn Error + Send + Sync>> {
+        let _guard = self.lock.lock().await;
+        let file = self.read_file().await?;
+        Ok(file.profiles.get(vpn_id).and_then(|record| record.secrets.get(name).cloned()))
+    }
+
+    async fn save_secret(&self, vpn_id: &str, name: &str, value: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.update(vpn_id, |record| {
+            record.secrets.insert(name.to_string(), value.to_string());
+            (true, ())
+        })
+        .await?;
+        Ok(())
+    }
+
+    async fn remove_secret(&self, vpn_id: &str, name: &str) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let removed = self
+            .update(vpn_id, |record| {
+                let removed = record.secrets.remove(name).is_some();
+                (removed, removed)
+            })
+            .await?;
+        Ok(removed)
+    }
+
+    async fn forget(&self, vpn_id: &str) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let _guard = self.lock.lock().await;
+        let mut file = self.read_file().await?;
+        if file.profiles.remove(vpn_id).is_none() {
+            return Ok(false);
+        }
+        self.write_file(&file).await?;
+        Ok(true)
+    }
+
+    async fn rename(&self, vpn_id: &str, new_id: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let _guard = self.lock.lock().await;
+        let mut file = self.read_file().await?;
+        if let Some(record) = file.profiles.remove(vpn_id) {
+            file.profiles.insert(new_id.to_string(), record);
+            self.write_file(&file).await?;
+        }
+        Ok(())
+    }
+}
+
+async fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    if fs::metadata(dir).await.is_ok() {
+        return Ok(());
+    }
+    fs::create_dir_all(dir).await?;
+    fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700)).await
+}
@@ -0,0 +1,43 @@
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+
+/// Writes `content` to `path`, readable by the owner only. It goes through a
+/// temporary file next to `path` and a rename, so readers never see half a
+/// file and a crash leaves the previous one in place.
+pub fn write_private(path: &Path, content: &[u8]) -> io::Result<()> {
+    let temp_path = temp_path_for(path);
+    match std::fs::remove_file(&temp_path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    let written = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&temp_path)
+        .and_then(|mut file| {
+            file.write_all(content)?;
+            file.sync_all()
+        })
+        .and_then(|()| std::fs::rename(&temp_path, path));
+    if written.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    written
+}
+
+/// [`write_private`] off the async runtime's threads.
+pub async fn write_private_async(path: &Path, content: &[u8]) -> io::Result<()> {
+    let (path, content) = (path.to_path_buf(), content.to_vec());
+    tokio::task::spawn_blocking(move || write_private(&path, &content))
+        .await
+        .map_err(io::Error::other)?
+}
+
+/// `.<name>.<pid>.tmp` beside `path`: hidden, and apart per process.
+fn temp_path_for(path: &Path) -> PathBuf {
+    let name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    path.with_file_name(format!(".{}.{}.tmp", name, std::process::id()))
+}
@@ -0,0 +1,252 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// Size at which `<name>.log` is rotated to `<name>.log.1`.
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+
+/// Rotated files kept besides the current one.
+const ROTATED_FILES: usize = 3;
+
+/// Lines kept in memory per profile.
+const RECENT_LINES: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+    /// Written by this program about the connection, not by OpenVPN.
+    Note,
+}
+
+/// One line OpenVPN printed, stamped when it was read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogLine {
+    pub time: SystemTime,
+    pub stream: LogStream,
+    pub text: String,
+}
+
+impl LogLine {
+    pub fn new(stream: LogStream, text: impl Into<String>) -> Self {
+        Self { time: SystemTime::now(), stream, text: text.into() }
+    }
+
+    /// Reads back a line as written to the log file:
+    /// `2024-05-01T10:00:00Z [stderr] text`, with a marker for every stream
+    /// but stdout.
+    pub fn parse(line: &str) -> Option<Self> {
+        let (time, rest) = line.split_once(' ')?;
+        let time = humantime::parse_rfc3339(time).ok()?;
+        let (stream, text) = if let Some(text) = rest.strip_prefix("[stderr] ") {
+            (LogStream::Stderr, text)
+        } else if let Some(text) = rest.strip_prefix("[ui-openvpn] ") {
+            (LogStream::Note, text)
+        } else {
+            (LogStream::Stdout, rest)
+        };
+        Some(Self { time, stream, text: text.to_string() })
+    }
+}
+
+impl std::fmt::Display for LogLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ", humantime::format_rfc3339_seconds(self.time))?;
+        match self.stream {
+            LogStream::Stdout => {}
+            LogStream::Stderr => write!(f, "[stderr] ")?,
+            LogStream::Note => write!(f, "[ui-openvpn] ")?,
+        }
+        write!(f, "{}", self.text)
+    }
+}
+
+/// What the OpenVPN processes we start print, kept per profile: a file
+/// rotated by size, and the last lines in memory for quick error reports.
+#[derive(Debug)]
+pub struct ConnectionLog {
+    dir: PathBuf,
+    max_bytes: u64,
+    recent: Mutex<HashMap<String, VecDeque<LogLine>>>,
+}
+
+impl ConnectionLog {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir, max_bytes: MAX_LOG_BYTES, recent: Mutex::new(HashMap::new()) }
+    }
+
+    /// `$XDG_STATE_HOME/ui-openvpn/logs`, or `~/.local/state/ui-openvpn/logs`.
+    pub fn for_user() -> Self {
+        let state_dir = match std::env::var("XDG_STATE_HOME") {
+            Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => {
+                let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+                PathBuf::from(home).join(".local/state")
+            }
+        };
+        Self::new(state_dir.join("ui-openvpn").join("logs"))
+    }
+
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Current file of `name`; older output is in `<file>.1` to `<file>.3`.
+    pub fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.log", name))
+    }
+
+    fn rotated_path(&self, name: &str, index: usize) -> PathBuf {
+        self.dir.join(format!("{}.log.{}", name, index))
+    }
+
+    /// Records `line`. The file is best effort: a full disk must not stop
+    /// the tunnel, so write errors are returned for the caller to ignore.
+    pub fn append(&self, name: &str, line: LogLine) -> io::Result<()> {
+        let written = format!("{}\n", line);
+        {
+            let mut recent = self.recent.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            let lines = recent.entry(name.to_string()).or_default();
+            if lines.len() == RECENT_LINES {
+                lines.pop_front();
+            }
+            lines.push_back(line);
+        }
+
+        self.prepare_dir()?;
+        let path = self.path(name);
+        let size = fs::metadata(&path).map(|metadata| metadata.len()).unwrap_or(0);
+        if size > 0 && size + written.len() as u64 > self.max_bytes {
+            self.rotate(name)?;
+        }
+        OpenOptions::new().create(true).append(true).open(&path)?.write_all(written.as_bytes())
+    }
+
+    /// Logs hold server addresses and user names, so only the user reads them.
+    fn prepare_dir(&self) -> io::Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        if !self.dir.exists() {
+            fs::create_dir_all(&self.dir)?;
+            fs::set_permissions(&self.dir, fs::Permissions::from_mode(0o700))?;
+        }
+        Ok(())
+    }
+
+    fn rotate(&self, name: &str) -> io::Result<()> {
+        for index in (1..ROTATED_FILES).rev() {
+            let from = self.rotated_path(name, index);
+            if from.exists() {
+                fs::rename(&from, self.rotated_path(name, index + 1))?;
+            }
+        }
+        fs::rename(self.path(name), self.rotated_path(name, 1))
+    }
+
+    /// Forgets the lines kept in memory, so [`Self::recent`] only returns
+    /// those of the process being started.
+    pub fn begin(&self, name: &str) {
+        let mut recent = self.recent.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        recent.remove(name);
+    }
+
+    /// Last lines of `name` seen by this process, oldest first.
+    pub fn recent(&self, name: &str, count: usize) -> Vec<LogLine> {
+        let recent = self.recent.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        recent
+            .get(name)
+            .map(|lines| lines.iter().skip(lines.len().saturating_sub(count)).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Everything on disk for `name`, rotated files included, oldest first.
+    /// Lines not written by us are skipped.
+    pub fn read(&self, name: &str, since: Option<SystemTime>) -> io::Result<Vec<LogLine>> {
+        let mut paths: Vec<PathBuf> = (1..=ROTATED_FILES).rev().map(|index| self.rotated_path(name, index)).collect();
+        paths.push(self.path(name));
+
+        let mut lines = Vec::new();
+        for path in paths {
+            let content = match fs::read_to_string(&path) {
+                Ok(content) => content,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+            lines.extend(
+                content
+                    .lines()
+                    .filter_map(LogLine::parse)
+                    .filter(|line| since.is_none_or(|since| line.time >= since)),
+            );
+        }
+        Ok(lines)
+    }
+
+    /// Follows `name` from its current end, like `tail -f`.
+    pub fn tail(&self, name: &str) -> LogTail {
+        use std::os::unix::fs::MetadataExt;
+
+        let path = self.path(name);
+        let metadata = fs::metadata(&path).ok();
+        LogTail {
+            offset: metadata.as_ref().map_or(0, |metadata| metadata.len()),
+            inode: metadata.map(|metadata| metadata.ino()),
+            path,
+            partial: String::new(),
+        }
+    }
+}
+
+impl Default for ConnectionLog {
+    fn default() -> Self {
+        Self::for_user()
+    }
+}
+
+/// Reads what was appended to a log file since the last call. Works across
+/// processes, so a client can follow a tunnel the daemon owns.
+#[derive(Debug)]
+pub struct LogTail {
+    path: PathBuf,
+    offset: u64,
+    inode: Option<u64>,
+    partial: String,
+}
+
+impl LogTail {
+    pub fn read_new(&mut self) -> io::Result<Vec<LogLine>> {
+        use std::os::unix::fs::MetadataExt;
+
+        let mut file = match fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        // After a rotation the name points to a new file; start over on it.
+        let metadata = file.metadata()?;
+        if self.inode != Some(metadata.ino()) || metadata.len() < self.offset {
+            self.inode = Some(metadata.ino());
+            self.offset = 0;
+            self.partial.clear();
+        }
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut bytes = Vec::new();
+        self.offset += file.read_to_end(&mut bytes)? as u64;
+        self.partial.push_str(&String::from_utf8_lossy(&bytes));
+
+        // Keep an unfinished last line for the next call.
+        let complete = match self.partial.rfind('\n') {
+            Some(end) => self.partial.drain(..=end).collect::<String>(),
+            None => return Ok(Vec::new()),
+        };
+        Ok(complete.lines().filter_map(LogLine::parse).collect())
+    }
+}
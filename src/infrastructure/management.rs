@@ -0,0 +1,354 @@
+use std::path::Path;
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::unix::OwnedWriteHalf;
+use tokio::net::UnixStream;
+use tokio::sync::{broadcast, mpsc, Mutex};
+
+#[derive(Error, Debug)]
+pub enum ManagementError {
+    #[error("management socket error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("management command failed: {0}")]
+    CommandFailed(String),
+    #[error("management interface closed the connection")]
+    Disconnected,
+    #[error("{0} cannot contain line breaks")]
+    LineBreak(&'static str),
+}
+
+/// `>STATE:` notification, see OpenVPN's management-notes.txt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateEvent {
+    pub timestamp: u64,
+    /// OpenVPN state name such as `CONNECTING`, `WAIT`, `AUTH`, `CONNECTED`.
+    pub name: String,
+    pub description: String,
+    pub local_ip: Option<String>,
+    pub remote_ip: Option<String>,
+    pub remote_port: Option<u16>,
+    pub local_ipv6: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogEvent {
+    pub timestamp: u64,
+    /// Any of `I`nfo, `F`atal, `N`on-fatal, `W`arning, `D`ebug.
+    pub flags: String,
+    pub message: String,
+}
+
+impl LogEvent {
+    /// Parses `timestamp,flags,message`, as found after `>LOG:` and in the
+    /// reply to `log all`.
+    pub fn parse(payload: &str) -> Option<Self> {
+        let mut fields = payload.splitn(3, ',');
+        Some(Self {
+            timestamp: fields.next()?.parse().ok()?,
+            flags: fields.next()?.to_string(),
+            message: fields.next().unwrap_or("").to_string(),
+        })
+    }
+
+    /// Device name from `TUN/TAP device tun0 opened`.
+    pub fn opened_device(&self) -> Option<&str> {
+        let device = self.message.strip_prefix("TUN/TAP device ")?.strip_suffix(" opened")?;
+        (!device.is_empty()).then_some(device)
+    }
+
+    /// Options the server pushed, from
+    /// `PUSH: Received control message: 'PUSH_REPLY,opt,opt,...'`.
+    pub fn push_reply_options(&self) -> Option<Vec<&str>> {
+        let reply = self.message.strip_prefix("PUSH: Received control message: '")?;
+        let reply = reply.strip_prefix("PUSH_REPLY")?.trim_end_matches('\'');
+        Some(reply.split(',').map(str::trim).filter(|option| !option.is_empty()).collect())
+    }
+}
+
+/// The profile's `static-challenge`, announced as `SC:<flags>,<text>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaticChallenge {
+    pub text: String,
+    pub echo: bool,
+    /// The response is appended to the password instead of sent as SCRV1.
+    pub concat: bool,
+}
+
+impl StaticChallenge {
+    fn parse(payload: &str) -> Option<Self> {
+        let (_, challenge) = payload.split_once(" SC:")?;
+        let (flags, text) = challenge.split_once(',')?;
+        let flags: u32 = flags.parse().ok()?;
+        Some(Self {
+            text: text.to_string(),
+            echo: flags & 1 != 0,
+            concat: flags & 2 != 0,
+        })
+    }
+
+    /// The `password` to send for `password` and the challenge `response`.
+    pub fn password(&self, password: &str, response: &str) -> String {
+        use base64::Engine;
+
+        if self.concat {
+            return format!("{}{}", password, response);
+        }
+        let encode = |value: &str| base64::engine::general_purpose::STANDARD.encode(value);
+        format!("SCRV1:{}:{}", encode(password), encode(response))
+    }
+}
+
+/// A server's `CRV1` challenge, sent as the reason for a failed verification.
+/// The next username/password query answers it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DynamicChallenge {
+    pub text: String,
+    pub echo: bool,
+    pub response_required: bool,
+    pub state_id: String,
+    pub username: String,
+}
+
+impl DynamicChallenge {
+    /// Parses `CRV1:<flags>:<state id>:<base64 username>:<text>`.
+    fn parse(payload: &str) -> Option<Self> {
+        use base64::Engine;
+
+        let start = payload.find("CRV1:")?;
+        let crv1 = payload[start + "CRV1:".len()..].trim_end_matches(['\'', ']']);
+        let mut fields = crv1.splitn(4, ':');
+        let flags: Vec<&str> = fields.next()?.split(',').collect();
+        let state_id = fields.next()?.to_string();
+        let username = base64::engine::general_purpose::STANDARD.decode(fields.next()?).ok()?;
+        Some(Self {
+            text: fields.next().unwrap_or("").to_string(),
+            echo: flags.contains(&"E"),
+            response_required: flags.contains(&"R"),
+            state_id,
+            username: String::from_utf8(username).ok()?,
+        })
+    }
+
+    /// The `password` that carries `response` back to the server.
+    pub fn password(&self, response: &str) -> String {
+        format!("CRV1::{}::{}", self.state_id, response)
+    }
+}
+
+/// What OpenVPN asks for in a `>PASSWORD:` notification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PasswordPrompt {
+    /// `Need 'Auth' username/password`, with ` SC:...` for a static challenge.
+    UsernamePassword {
+        realm: String,
+        static_challenge: Option<StaticChallenge>,
+    },
+    /// `Need 'Private Key' password` and other password-only realms.
+    Password { realm: String },
+    /// `Verification Failed: 'Auth'`, with `['CRV1:...']` when the server
+    /// wants a challenge answered rather than rejecting the password.
+    VerificationFailed {
+        realm: String,
+        challenge: Option<DynamicChallenge>,
+    },
+    Other(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ManagementEvent {
+    State(StateEvent),
+    ByteCount { bytes_in: u64, bytes_out: u64 },
+    Log(LogEvent),
+    Hold(String),
+    Password(PasswordPrompt),
+    Info(String),
+    Fatal(String),
+    /// Any other real-time notification, verbatim without the leading `>`.
+    Other(String),
+}
+
+impl ManagementEvent {
+    /// Parses one `>KIND:payload` notification line.
+    pub fn parse(line: &str) -> Option<Self> {
+        let (kind, payload) = line.strip_prefix('>')?.split_once(':')?;
+        let event = match kind {
+            "STATE" => Self::parse_state(payload).map(Self::State),
+            "BYTECOUNT" => {
+                let (bytes_in, bytes_out) = payload.split_once(',')?;
+                Some(Self::ByteCount {
+                    bytes_in: bytes_in.trim().parse().ok()?,
+                    bytes_out: bytes_out.trim().parse().ok()?,
+                })
+            }
+            "LOG" => LogEvent::parse(payload).map(Self::Log),
+            "HOLD" => Some(Self::Hold(payload.to_string())),
+            "PASSWORD" => Some(Self::Password(Self::parse_password(payload))),
+            "INFO" => Some(Self::Info(payload.to_string())),
+            "FATAL" => Some(Self::Fatal(payload.to_string())),
+            _ => None,
+        };
+        Some(event.unwrap_or_else(|| Self::Other(line[1..].to_string())))
+    }
+
+    fn parse_state(payload: &str) -> Option<StateEvent> {
+        let fields: Vec<&str> = payload.split(',').collect();
+        let field = |index: usize| fields.get(index).filter(|value| !value.is_empty()).map(|value| value.to_string());
+        Some(StateEvent {
+            timestamp: fields.first()?.parse().ok()?,
+            name: fields.get(1)?.to_string(),
+            description: field(2).unwrap_or_default(),
+            local_ip: field(3),
+            remote_ip: field(4),
+            remote_port: field(5).and_then(|port| port.parse().ok()),
+            local_ipv6: field(8),
+        })
+    }
+
+    fn parse_password(payload: &str) -> PasswordPrompt {
+        let realm = || payload.split('\'').nth(1).unwrap_or("").to_string();
+        if payload.starts_with("Verification Failed") {
+            PasswordPrompt::VerificationFailed {
+                realm: realm(),
+                challenge: DynamicChallenge::parse(payload),
+            }
+        } else if payload.starts_with("Need ") && payload.contains("username/password") {
+            PasswordPrompt::UsernamePassword {
+                realm: realm(),
+                static_challenge: StaticChallenge::parse(payload),
+            }
+        } else if payload.starts_with("Need ") && payload.contains("password") {
+            PasswordPrompt::Password { realm: realm() }
+        } else {
+            PasswordPrompt::Other(payload.to_string())
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CommandChannel {
+    writer: OwnedWriteHalf,
+    responses: mpsc::UnboundedReceiver<Result<String, String>>,
+}
+
+/// Client for OpenVPN's `--management <path> unix` interface. Commands are
+/// serialised; real-time notifications are fanned out to every subscriber.
+#[derive(Debug)]
+pub struct ManagementClient {
+    channel: Mutex<CommandChannel>,
+    events: broadcast::Sender<ManagementEvent>,
+}
+
+impl ManagementClient {
+    pub async fn connect(socket_path: &Path) -> Result<Arc<Self>, ManagementError> {
+        let stream = UnixStream::connect(socket_path).await?;
+        let (reader, writer) = stream.into_split();
+        let (response_tx, responses) = mpsc::unbounded_channel();
+        let (events, _) = broadcast::channel(256);
+
+        let event_tx = events.clone();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(reader).lines();
+            let mut multiline = Vec::new();
+
+            while let Ok(Some(line)) = lines.next_line().await {
+                if line.starts_with('>') {
+                    if let Some(event) = ManagementEvent::parse(&line) {
+                        // No subscribers is fine; events are best effort.
+                        let _ = event_tx.send(event);
+                    }
+                } else if let Some(message) = line.strip_prefix("SUCCESS:") {
+                    let _ = response_tx.send(Ok(message.trim().to_string()));
+                } else if let Some(message) = line.strip_prefix("ERROR:") {
+                    let _ = response_tx.send(Err(message.trim().to_string()));
+                } else if line == "END" {
+                    let _ = response_tx.send(Ok(multiline.join("\n")));
+                    multiline.clear();
+                } else {
+                    multiline.push(line);
+                }
+            }
+        });
+
+        Ok(Arc::new(Self {
+            channel: Mutex::new(CommandChannel { writer, responses }),
+            events,
+        }))
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<ManagementEvent> {
+        self.events.subscribe()
+    }
+
+    /// Sends one command and waits for its `SUCCESS:`/`ERROR:` line, or the
+    /// body of a multi-line reply terminated by `END`.
+    pub async fn command(&self, command: &str) -> Result<String, ManagementError> {
+        let mut channel = self.channel.lock().await;
+        channel.writer.write_all(format!("{}\n", command).as_bytes()).await?;
+        channel.writer.flush().await?;
+
+        match channel.responses.recv().await {
+            Some(Ok(message)) => Ok(message),
+            Some(Err(message)) => Err(ManagementError::CommandFailed(message)),
+            None => Err(ManagementError::Disconnected),
+        }
+    }
+
+    /// Current state as reported by the `state` command, for callers that
+    /// subscribed after the last `>STATE:` notification went by.
+    pub async fn current_state(&self) -> Result<Option<StateEvent>, ManagementError> {
+        let history = self.command("state").await?;
+        Ok(history.lines().last().and_then(ManagementEvent::parse_state))
+    }
+
+    /// Log lines OpenVPN kept from before this client attached.
+    pub async fn log_history(&self) -> Result<Vec<LogEvent>, ManagementError> {
+        let history = self.command("log all").await?;
+        Ok(history.lines().filter_map(LogEvent::parse).collect())
+    }
+
+    pub async fn enable_state_events(&self) -> Result<(), ManagementError> {
+        self.command("state on").await.map(|_| ())
+    }
+
+    pub async fn enable_log_events(&self) -> Result<(), ManagementError> {
+        self.command("log on").await.map(|_| ())
+    }
+
+    /// Asks for a `>BYTECOUNT:` notification every `interval_secs` (0 disables).
+    pub async fn enable_bytecount(&self, interval_secs: u32) -> Result<(), ManagementError> {
+        self.command(&format!("bytecount {}", interval_secs)).await.map(|_| ())
+    }
+
+    /// Clears the hold flag, so restarts after a ping-restart or SIGUSR1 do
+    /// not wait for a client that may be gone by then. The current hold
+    /// still needs [`Self::release_hold`].
+    pub async fn disable_hold(&self) -> Result<(), ManagementError> {
+        self.command("hold off").await.map(|_| ())
+    }
+
+    pub async fn release_hold(&self) -> Result<(), ManagementError> {
+        self.command("hold release").await.map(|_| ())
+    }
+
+    pub async fn signal(&self, signal: &str) -> Result<(), ManagementError> {
+        self.command(&format!("signal {}", signal)).await.map(|_| ())
+    }
+
+    pub async fn send_username(&self, realm: &str, username: &str) -> Result<(), ManagementError> {
+        self.command(&format!("username \"{}\" {}", realm, Self::quote("username", username)?)).await.map(|_| ())
+    }
+
+    pub async fn send_password(&self, realm: &str, password: &str) -> Result<(), ManagementError> {
+        self.command(&format!("password \"{}\" {}", realm, Self::quote("password", password)?)).await.map(|_| ())
+    }
+
+    /// Commands are line based, so a line break would end the quoted value
+    /// and let the rest through as a command of its own.
+    fn quote(field: &'static str, value: &str) -> Result<String, ManagementError> {
+        if value.contains(['\r', '\n']) {
+            return Err(ManagementError::LineBreak(field));
+        }
+        Ok(format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\"")))
+    }
+}
@@ -0,0 +1,169 @@
+use crate::domain::entities::{ProfileMetadata, ProfileOptions};
+use crate::infrastructure::files::write_private_async;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use tokio::fs;
+
+pub const METADATA_FILE: &str = "profiles.json";
+const FORMAT_VERSION: u32 = 1;
+
+#[derive(Error, Debug)]
+pub enum MetadataError {
+    #[error("cannot access {path}: {source}")]
+    Io { path: PathBuf, source: std::io::Error },
+    #[error("{path} is not a valid profile metadata file: {source}")]
+    Parse { path: PathBuf, source: serde_json::Error },
+    #[error("{path} has an invalid last_used time for '{id}': {value}")]
+    InvalidTimestamp { path: PathBuf, id: String, value: String },
+}
+
+/// On-disk shape of one profile entry. Kept separate from the domain type so
+/// the file format stays stable when the entity changes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ProfileRecord {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    display_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    favorite: bool,
+    /// RFC 3339, UTC.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_used: Option<String>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    options: ProfileOptions,
+}
+
+fn is_default(options: &ProfileOptions) -> bool {
+    options == &ProfileOptions::default()
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct MetadataFile {
+    version: u32,
+    #[serde(default)]
+    profiles: BTreeMap<String, ProfileRecord>,
+}
+
+/// `profiles.json` next to the .ovpn files: display names, tags, favorites
+/// and per-profile options keyed by profile id.
+#[derive(Debug, Clone)]
+pub struct MetadataStore {
+    path: PathBuf,
+}
+
+impl MetadataStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn in_dir(config_dir: &Path) -> Self {
+        Self::new(config_dir.join(METADATA_FILE))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Every stored entry. A missing file is an empty store; a corrupt one
+    /// is an error so it never gets overwritten by the next save.
+    pub async fn load(&self) -> Result<BTreeMap<String, ProfileMetadata>, MetadataError> {
+        let file = self.read_file().await?;
+        file.profiles
+            .into_iter()
+            .map(|(id, record)| {
+                let metadata = self.to_metadata(&id, record)?;
+                Ok((id, metadata))
+            })
+            .collect()
+    }
+
+    pub async fn get(&self, id: &str) -> Result<ProfileMetadata, MetadataError> {
+        let mut file = self.read_file().await?;
+        match file.profiles.remove(id) {
+            Some(record) => self.to_metadata(id, record),
+            None => Ok(ProfileMetadata::default()),
+        }
+    }
+
+    /// Stores `metadata` for `id`; empty metadata removes the entry.
+    pub async fn put(&self, id: &str, metadata: &ProfileMetadata) -> Result<(), MetadataError> {
+        let mut file = self.read_file().await?;
+        if metadata.is_empty() {
+            if file.profiles.remove(id).is_none() {
+                return Ok(());
+            }
+        } else {
+            file.profiles.insert(id.to_string(), Self::to_record(metadata));
+        }
+        self.write_file(&file).await
+    }
+
+    pub async fn remove(&self, id: &str) -> Result<(), MetadataError> {
+        let mut file = self.read_file().await?;
+        if file.profiles.remove(id).is_some() {
+            self.write_file(&file).await?;
+        }
+        Ok(())
+    }
+
+    async fn read_file(&self) -> Result<MetadataFile, MetadataError> {
+        let content = match fs::read_to_string(&self.path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(MetadataFile { version: FORMAT_VERSION, profiles: BTreeMap::new() })
+            }
+            Err(source) => return Err(MetadataError::Io { path: self.path.clone(), source }),
+        };
+        serde_json::from_str(&content).map_err(|source| MetadataError::Parse { path: self.path.clone(), source })
+    }
+
+    /// Written owner-only through [`write_private_async`], so a crash never
+    /// leaves a truncated store behind.
+    async fn write_file(&self, file: &MetadataFile) -> Result<(), MetadataError> {
+        let io_error = |source| MetadataError::Io { path: self.path.clone(), source };
+        let mut json = serde_json::to_string_pretty(file)
+            .map_err(|source| MetadataError::Parse { path: self.path.clone(), source })?;
+        json.push('\n');
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).await.map_err(io_error)?;
+        }
+        write_private_async(&self.path, json.as_bytes()).await.map_err(io_error)
+    }
+
+    fn to_metadata(&self, id: &str, record: ProfileRecord) -> Result<ProfileMetadata, MetadataError> {
+        let last_used = match record.last_used {
+            Some(value) => Some(humantime::parse_rfc3339_weak(&value).map_err(|_| MetadataError::InvalidTimestamp {
+                path: self.path.clone(),
+                id: id.to_string(),
+                value,
+            })?),
+            None => None,
+        };
+
+        let mut metadata = ProfileMetadata::default();
+        metadata.set_display_name(record.display_name);
+        metadata.set_description(record.description);
+        metadata.set_tags(record.tags);
+        metadata.set_favorite(record.favorite);
+        metadata.set_last_used(last_used);
+        metadata.set_options(record.options);
+        Ok(metadata)
+    }
+
+    fn to_record(metadata: &ProfileMetadata) -> ProfileRecord {
+        ProfileRecord {
+            display_name: metadata.display_name().map(str::to_string),
+            description: metadata.description().map(str::to_string),
+            tags: metadata.tags().to_vec(),
+            favorite: metadata.is_favorite(),
+            last_used: metadata.last_used().map(|time| humantime::format_rfc3339_seconds(time).to_string()),
+            options: metadata.options().clone(),
+        }
+    }
+}
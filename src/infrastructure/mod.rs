@@ -1,3 +1,12 @@
 // Infrastructure layer - External dependencies
 pub mod repositories;
-pub mod services;
\ No newline at end of file
+pub mod services;
+pub mod process;
+pub mod management;
+pub mod metadata;
+pub mod traffic;
+pub mod bundle;
+pub mod archive;
+pub mod credentials;
+pub mod logs;
+pub mod files;
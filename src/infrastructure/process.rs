@@ -0,0 +1,181 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A running OpenVPN instance as seen through procfs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenVpnProcess {
+    pub pid: u32,
+    pub start_time: Option<SystemTime>,
+    /// Absolute path of the profile, resolved against `--cd` or the process cwd.
+    pub config_path: Option<PathBuf>,
+    pub uid: Option<u32>,
+    pub user: Option<String>,
+    pub exe: Option<PathBuf>,
+    pub args: Vec<String>,
+}
+
+/// Discovers OpenVPN processes by reading `/proc/<pid>/{cmdline,cwd,exe,stat,status}`.
+#[derive(Debug)]
+pub struct ProcessInspector {
+    proc_root: PathBuf,
+    passwd_path: PathBuf,
+}
+
+impl ProcessInspector {
+    pub fn new(proc_root: PathBuf, passwd_path: PathBuf) -> Self {
+        Self { proc_root, passwd_path }
+    }
+
+    pub fn system() -> Self {
+        Self::new(PathBuf::from("/proc"), PathBuf::from("/etc/passwd"))
+    }
+
+    pub fn openvpn_processes(&self) -> Vec<OpenVpnProcess> {
+        let entries = match fs::read_dir(&self.proc_root) {
+            Ok(entries) => entries,
+            Err(_) => return Vec::new(),
+        };
+
+        let mut processes: Vec<OpenVpnProcess> = entries
+            .flatten()
+            .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+            .filter_map(|pid| self.inspect(pid))
+            .collect();
+        processes.sort_by_key(|process| process.pid);
+        processes
+    }
+
+    pub fn find_by_config(&self, config_path: &Path) -> Option<OpenVpnProcess> {
+        self.openvpn_processes()
+            .into_iter()
+            .find(|process| process.config_path.as_deref() == Some(config_path))
+    }
+
+    /// Returns the process when `pid` is an OpenVPN instance, `None` otherwise
+    /// (including when it exited while we were looking).
+    pub fn inspect(&self, pid: u32) -> Option<OpenVpnProcess> {
+        let dir = self.proc_root.join(pid.to_string());
+        let cmdline = fs::read(dir.join("cmdline")).ok()?;
+        let args: Vec<String> = cmdline
+            .split(|byte| *byte == 0)
+            .filter(|arg| !arg.is_empty())
+            .map(|arg| String::from_utf8_lossy(arg).into_owned())
+            .collect();
+
+        // `exe` is unreadable for other users' processes, so fall back to argv[0].
+        // Wrappers such as `pkexec openvpn ...` are skipped: only the real binary counts.
+        let exe = fs::read_link(dir.join("exe")).ok();
+        let is_openvpn = match &exe {
+            Some(exe) => Self::is_openvpn_binary(exe),
+            None => args.first().is_some_and(|arg0| Self::is_openvpn_binary(Path::new(arg0))),
+        };
+        if !is_openvpn {
+            return None;
+        }
+
+        let cwd = fs::read_link(dir.join("cwd")).ok();
+        let uid = Self::read_uid(&dir);
+
+        Some(OpenVpnProcess {
+            pid,
+            start_time: self.read_start_time(&dir),
+            config_path: Self::config_path(&args, cwd.as_deref()),
+            user: uid.and_then(|uid| self.user_name(uid)),
+            uid,
+            exe,
+            args,
+        })
+    }
+
+    fn is_openvpn_binary(path: &Path) -> bool {
+        path.file_name().is_some_and(|name| name == "openvpn")
+    }
+
+    /// Mirrors OpenVPN's own handling: `--config X`, `--config=X`, or a lone
+    /// positional argument, relative to `--cd` when given, else to the cwd.
+    fn config_path(args: &[String], cwd: Option<&Path>) -> Option<PathBuf> {
+        let mut config = None;
+        let mut cd = None;
+        let mut iter = args.iter().skip(1);
+
+        while let Some(arg) = iter.next() {
+            if let Some(value) = arg.strip_prefix("--config=") {
+                config = Some(value.to_string());
+            } else if arg == "--config" {
+                config = iter.next().cloned();
+            } else if let Some(value) = arg.strip_prefix("--cd=") {
+                cd = Some(value.to_string());
+            } else if arg == "--cd" {
+                cd = iter.next().cloned();
+            }
+        }
+
+        if config.is_none() && args.len() == 2 && !args[1].starts_with("--") {
+            config = Some(args[1].clone());
+        }
+
+        let config = PathBuf::from(config?);
+        if config.is_absolute() {
+            return Some(config);
+        }
+        let base = match (cd, cwd) {
+            (Some(cd), Some(cwd)) => cwd.join(cd),
+            (Some(cd), None) => PathBuf::from(cd),
+            (None, Some(cwd)) => cwd.to_path_buf(),
+            (None, None) => return Some(config),
+        };
+        Some(base.join(config))
+    }
+
+    fn read_uid(dir: &Path) -> Option<u32> {
+        let status = fs::read_to_string(dir.join("status")).ok()?;
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix("Uid:"))
+            .and_then(|ids| ids.split_whitespace().next())
+            .and_then(|uid| uid.parse().ok())
+    }
+
+    fn read_start_time(&self, dir: &Path) -> Option<SystemTime> {
+        let stat = fs::read_to_string(dir.join("stat")).ok()?;
+        // The command name is parenthesised and may contain spaces, so count
+        // fields from the last ')'. `starttime` is field 22; `state` is field 3.
+        let fields: Vec<&str> = stat.get(stat.rfind(')')? + 1..)?.split_whitespace().collect();
+        let start_ticks: u64 = fields.get(22 - 3)?.parse().ok()?;
+
+        let proc_stat = fs::read_to_string(self.proc_root.join("stat")).ok()?;
+        let boot_time: u64 = proc_stat
+            .lines()
+            .find_map(|line| line.strip_prefix("btime "))?
+            .trim()
+            .parse()
+            .ok()?;
+
+        let ticks_per_second = Self::clock_ticks_per_second();
+        let since_boot = Duration::from_millis(start_ticks * 1000 / ticks_per_second);
+        Some(UNIX_EPOCH + Duration::from_secs(boot_time) + since_boot)
+    }
+
+    fn clock_ticks_per_second() -> u64 {
+        // SAFETY: sysconf has no preconditions and only reads a constant.
+        let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+        if ticks > 0 { ticks as u64 } else { 100 }
+    }
+
+    pub fn user_name(&self, uid: u32) -> Option<String> {
+        let passwd = fs::read_to_string(&self.passwd_path).ok()?;
+        passwd.lines().find_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            let entry_uid: u32 = fields.nth(1)?.parse().ok()?;
+            (entry_uid == uid).then(|| name.to_string())
+        })
+    }
+}
+
+impl Default for ProcessInspector {
+    fn default() -> Self {
+        Self::system()
+    }
+}
@@ -1,16 +1,34 @@
-use crate::domain::{entities::Vpn, repositories::VpnRepository};
+use crate::domain::{
+    bundle::BundledFile,
+    entities::{ProfileMetadata, Vpn},
+    ovpn::OvpnConfig,
+    repositories::VpnRepository,
+};
+use crate::infrastructure::files::write_private_async;
+use crate::infrastructure::metadata::MetadataStore;
 use async_trait::async_trait;
 use std::error::Error;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
 use tokio::fs;
 
+#[derive(Error, Debug)]
+pub enum ProfileStoreError {
+    #[error("VPN already exists: {0}")]
+    AlreadyExists(String),
+    #[error("VPN not found: {0}")]
+    NotFound(String),
+}
+
 pub struct FileVpnRepository {
     config_dir: PathBuf,
+    metadata: MetadataStore,
 }
 
 impl FileVpnRepository {
     pub fn new(config_dir: PathBuf) -> Self {
-        Self { config_dir }
+        let metadata = MetadataStore::in_dir(&config_dir);
+        Self { config_dir, metadata }
     }
 
     pub fn from_home_dir() -> Self {
@@ -19,74 +37,195 @@ impl FileVpnRepository {
         Self::new(config_dir)
     }
 
-    fn map_display_name(filename: &str) -> String {
-        match filename {
-            "David_cruz.ovpn" => "Dynamic".to_string(),
-            "julian.ovpn" => "Howden".to_string(),
-            _ => "Unknown".to_string(),
+    pub fn config_dir(&self) -> &Path {
+        &self.config_dir
+    }
+
+    pub fn metadata_store(&self) -> &MetadataStore {
+        &self.metadata
+    }
+
+    fn profile_path(&self, id: &str) -> PathBuf {
+        self.config_dir.join(format!("{}.ovpn", id))
+    }
+
+    /// Certificates and keys copied in on import live in `<id>.d/`.
+    fn files_dir(&self, id: &str) -> PathBuf {
+        self.config_dir.join(format!("{}.d", id))
+    }
+
+    async fn remove_files_dir(&self, id: &str) -> std::io::Result<()> {
+        match fs::remove_dir_all(self.files_dir(id)).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// Repoints references into `from` at the same files under `to`.
+    fn relocate_references(content: &str, from: &Path, to: &Path) -> Option<String> {
+        let mut config = OvpnConfig::parse(content).ok()?;
+        let mut changed = false;
+        for reference in config.file_references() {
+            if let Ok(rest) = Path::new(&reference.path).strip_prefix(from) {
+                let path = to.join(rest);
+                config.rewrite_file_reference(&reference.directive, &reference.path, &path.to_string_lossy());
+                changed = true;
+            }
         }
+        changed.then(|| config.render())
+    }
+
+    /// Profiles can embed private keys, so they are written owner-only.
+    async fn write_profile(&self, path: &Path, content: &str) -> std::io::Result<()> {
+        fs::create_dir_all(&self.config_dir).await?;
+        write_private_async(path, content.as_bytes()).await
     }
 
     fn extract_id_from_filename(filename: &str) -> String {
         filename.trim_end_matches(".ovpn").to_string()
     }
+
+    async fn load_config(path: &Path) -> Option<OvpnConfig> {
+        // Unreadable or malformed profiles are still listed; `validate` reports why.
+        let content = fs::read_to_string(path).await.ok()?;
+        OvpnConfig::parse(&content).ok()
+    }
+
+    /// Profiles without stored metadata are shown under their id.
+    async fn build_vpn(id: String, path: &Path, metadata: ProfileMetadata) -> Vpn {
+        let mut vpn = Vpn::new(id.clone(), id, path.to_string_lossy().to_string()).with_metadata(metadata);
+        vpn.set_config(Self::load_config(path).await);
+        vpn
+    }
 }
 
 #[async_trait]
 impl VpnRepository for FileVpnRepository {
     async fn find_by_id(&self, id: &str) -> Result<Option<Vpn>, Box<dyn Error>> {
-        let config_path = self.config_dir.join(format!("{}.ovpn", id));
+        let config_path = self.profile_path(id);
         
         if !config_path.exists() {
             return Ok(None);
         }
 
-        let filename = format!("{}.ovpn", id);
-        let display_name = Self::map_display_name(&filename);
-        
-        let vpn = Vpn::new(
-            id.to_string(),
-            display_name,
-            config_path.to_string_lossy().to_string(),
-        );
-
-        Ok(Some(vpn))
+        let metadata = self.metadata.get(id).await?;
+        Ok(Some(Self::build_vpn(id.to_string(), &config_path, metadata).await))
     }
 
-    async fn save(&self, _vpn: &Vpn) -> Result<(), Box<dyn Error>> {
-        // For now, we only update in-memory state
-        // In a real implementation, this might update a metadata file
+    /// Persists the profile's metadata. Connection status is runtime state
+    /// and is not written.
+    async fn save(&self, vpn: &Vpn) -> Result<(), Box<dyn Error>> {
+        let stored = self.metadata.get(vpn.id()).await?;
+        if &stored != vpn.metadata() {
+            self.metadata.put(vpn.id(), vpn.metadata()).await?;
+        }
         Ok(())
     }
 
+    /// Every .ovpn file in the config directory merged with its metadata,
+    /// favorites first, then by display name.
     async fn list_all(&self) -> Result<Vec<Vpn>, Box<dyn Error>> {
         if !self.config_dir.exists() {
             return Ok(Vec::new());
         }
 
+        let mut metadata = self.metadata.load().await?;
         let mut vpns = Vec::new();
         let mut entries = fs::read_dir(&self.config_dir).await?;
 
         while let Some(entry) = entries.next_entry().await? {
             let path = entry.path();
             if let Some(extension) = path.extension() {
-                if extension == "ovpn" {
+                if extension == "ovpn" && path.is_file() {
                     if let Some(filename) = path.file_name() {
                         let filename_str = filename.to_string_lossy().to_string();
                         let id = Self::extract_id_from_filename(&filename_str);
-                        let display_name = Self::map_display_name(&filename_str);
-                        
-                        let vpn = Vpn::new(
-                            id,
-                            display_name,
-                            path.to_string_lossy().to_string(),
-                        );
-                        vpns.push(vpn);
+                        let profile_metadata = metadata.remove(&id).unwrap_or_default();
+                        vpns.push(Self::build_vpn(id, &path, profile_metadata).await);
                     }
                 }
             }
         }
 
+        vpns.sort_by(|a, b| {
+            b.metadata()
+                .is_favorite()
+                .cmp(&a.metadata().is_favorite())
+                .then_with(|| a.display_name().to_lowercase().cmp(&b.display_name().to_lowercase()))
+                .then_with(|| a.id().cmp(b.id()))
+        });
         Ok(vpns)
     }
-}
\ No newline at end of file
+
+    async fn create(&self, id: &str, content: &str) -> Result<Vpn, Box<dyn Error>> {
+        let path = self.profile_path(id);
+        if path.exists() {
+            return Err(ProfileStoreError::AlreadyExists(id.to_string()).into());
+        }
+        self.write_profile(&path, content).await?;
+        let metadata = self.metadata.get(id).await?;
+        Ok(Self::build_vpn(id.to_string(), &path, metadata).await)
+    }
+
+    async fn update_config(&self, id: &str, content: &str) -> Result<Vpn, Box<dyn Error>> {
+        let path = self.profile_path(id);
+        if !path.exists() {
+            return Err(ProfileStoreError::NotFound(id.to_string()).into());
+        }
+        self.write_profile(&path, content).await?;
+        let metadata = self.metadata.get(id).await?;
+        Ok(Self::build_vpn(id.to_string(), &path, metadata).await)
+    }
+
+    async fn rename(&self, id: &str, new_id: &str) -> Result<Vpn, Box<dyn Error>> {
+        let from = self.profile_path(id);
+        let to = self.profile_path(new_id);
+        if !from.exists() {
+            return Err(ProfileStoreError::NotFound(id.to_string()).into());
+        }
+        if to.exists() {
+            return Err(ProfileStoreError::AlreadyExists(new_id.to_string()).into());
+        }
+
+        let metadata = self.metadata.get(id).await?;
+        let (old_files, new_files) = (self.files_dir(id), self.files_dir(new_id));
+        if old_files.exists() {
+            self.remove_files_dir(new_id).await?;
+            fs::rename(&old_files, &new_files).await?;
+            let content = fs::read_to_string(&from).await?;
+            if let Some(content) = Self::relocate_references(&content, &old_files, &new_files) {
+                self.write_profile(&from, &content).await?;
+            }
+        }
+        fs::rename(&from, &to).await?;
+        self.metadata.put(new_id, &metadata).await?;
+        self.metadata.remove(id).await?;
+        Ok(Self::build_vpn(new_id.to_string(), &to, metadata).await)
+    }
+
+    async fn delete(&self, id: &str) -> Result<(), Box<dyn Error>> {
+        match fs::remove_file(self.profile_path(id)).await {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(ProfileStoreError::NotFound(id.to_string()).into())
+            }
+            result => result?,
+        }
+        self.remove_files_dir(id).await?;
+        self.metadata.remove(id).await?;
+        Ok(())
+    }
+
+    async fn store_files(&self, id: &str, files: &[BundledFile]) -> Result<PathBuf, Box<dyn Error>> {
+        let dir = self.files_dir(id);
+        self.remove_files_dir(id).await?;
+        if files.is_empty() {
+            return Ok(dir);
+        }
+
+        fs::DirBuilder::new().recursive(true).mode(0o700).create(&dir).await?;
+        for file in files {
+            write_private_async(&dir.join(&file.name), &file.content).await?;
+        }
+        Ok(dir)
+    }
+}
@@ -1,171 +1,592 @@
-use std::process::Stdio;
+use crate::infrastructure::files::write_private;
+use crate::infrastructure::logs::{ConnectionLog, LogLine, LogStream};
+use crate::infrastructure::management::ManagementClient;
+use crate::domain::credentials::Credentials;
+use crate::domain::entities::TrafficStats;
+use crate::infrastructure::process::{OpenVpnProcess, ProcessInspector};
+use crate::infrastructure::traffic::TrafficMeter;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Stdio};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
 use tokio::process::{Child, Command};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
+/// How long to wait for OpenVPN to open its management socket. This includes
+/// the time the user spends answering the pkexec/sudo prompt.
+const MANAGEMENT_ATTACH_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How often OpenVPN reports `>BYTECOUNT:` for tunnels we started.
+const BYTECOUNT_INTERVAL_SECS: u32 = 2;
+
+/// How long OpenVPN gets to run its down script and restore routes and DNS
+/// after being asked to stop, before the next, harsher step.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Bound for attaching to another instance's management socket on shutdown.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Output lines quoted when OpenVPN dies before its management interface is up.
+const FAILURE_CONTEXT_LINES: usize = 5;
+
+/// How a running OpenVPN was brought down, from gentlest to harshest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownStep {
+    /// `signal SIGTERM` through the management interface.
+    Management,
+    /// SIGTERM sent to the process.
+    Terminate,
+    /// SIGKILL: OpenVPN had no chance to run its down script.
+    Kill,
+}
+
+impl ShutdownStep {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Management => "management",
+            Self::Terminate => "sigterm",
+            Self::Kill => "sigkill",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "management" => Some(Self::Management),
+            "sigterm" => Some(Self::Terminate),
+            "sigkill" => Some(Self::Kill),
+            _ => None,
+        }
+    }
+
+    /// Whether routes and DNS may have been left behind.
+    pub fn is_forced(&self) -> bool {
+        *self == Self::Kill
+    }
+}
+
+impl std::fmt::Display for ShutdownStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Management => write!(f, "stopped through the management interface"),
+            Self::Terminate => write!(f, "stopped with SIGTERM"),
+            Self::Kill => write!(f, "killed with SIGKILL"),
+        }
+    }
+}
+
+/// An OpenVPN process that was stopped, and how.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoppedInstance {
+    pub pid: u32,
+    pub step: ShutdownStep,
+}
+
+/// Answers handed to OpenVPN in files at launch, for when nobody stays
+/// attached to give them over the management interface.
+#[derive(Clone, Default)]
+pub struct LaunchSecrets {
+    /// Written for `--auth-user-pass`.
+    pub credentials: Option<Credentials>,
+    /// Written for `--askpass`.
+    pub key_passphrase: Option<String>,
+}
+
+/// A file only the user can read, removed when dropped.
+#[derive(Debug)]
+struct SecretFile(PathBuf);
+
+impl SecretFile {
+    fn create(path: PathBuf, contents: &str) -> std::io::Result<Self> {
+        write_private(&path, contents.as_bytes())?;
+        Ok(Self(path))
+    }
+}
+
+impl Drop for SecretFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// An OpenVPN started by this service.
+#[derive(Debug)]
+struct OwnedTunnel {
+    /// The pkexec/sudo wrapper around OpenVPN.
+    child: Child,
+    /// Its path on the command line tells the real OpenVPN apart from the wrapper.
+    socket_path: PathBuf,
+    management: Arc<ManagementClient>,
+    /// Kept until OpenVPN has read them, see [`OpenVpnService::discard_secrets`].
+    secrets: Vec<SecretFile>,
+}
+
 #[derive(Debug)]
 pub struct OpenVpnService {
-    process: Arc<Mutex<Option<Child>>>,
-    connected: Arc<Mutex<bool>>,
+    /// Tunnels we started, by profile config path.
+    tunnels: Arc<Mutex<HashMap<String, OwnedTunnel>>>,
+    inspector: ProcessInspector,
+    traffic: TrafficMeter,
+    shutdown_timeout: Duration,
+    log: Arc<ConnectionLog>,
+    resident_client: bool,
+}
+
+/// Private directory for sockets: `$XDG_RUNTIME_DIR/ui-openvpn/`, or
+/// `/tmp/ui-openvpn-<uid>/` without a runtime dir.
+pub fn runtime_dir() -> PathBuf {
+    match std::env::var("XDG_RUNTIME_DIR") {
+        Ok(runtime_dir) if !runtime_dir.is_empty() => PathBuf::from(runtime_dir).join("ui-openvpn"),
+        // SAFETY: getuid cannot fail and has no preconditions.
+        _ => PathBuf::from(format!("/tmp/ui-openvpn-{}", unsafe { libc::getuid() })),
+    }
 }
 
 impl OpenVpnService {
     pub fn new() -> Self {
+        Self::with_inspector(ProcessInspector::system())
+    }
+
+    pub fn with_inspector(inspector: ProcessInspector) -> Self {
         Self {
-            process: Arc::new(Mutex::new(None)),
-            connected: Arc::new(Mutex::new(false)),
+            tunnels: Arc::new(Mutex::new(HashMap::new())),
+            inspector,
+            traffic: TrafficMeter::system(),
+            shutdown_timeout: SHUTDOWN_TIMEOUT,
+            log: Arc::new(ConnectionLog::for_user()),
+            resident_client: false,
         }
     }
 
+    /// Whether this process stays attached to the tunnels it starts (daemon,
+    /// GUI, interactive mode, `connect --keep-alive`), so OpenVPN may ask it
+    /// for passwords at any time. Otherwise nobody would answer once the
+    /// command exits, and passwords go in [`LaunchSecrets`] instead.
+    pub fn with_resident_client(mut self, resident: bool) -> Self {
+        self.resident_client = resident;
+        self
+    }
+
+    pub fn has_resident_client(&self) -> bool {
+        self.resident_client
+    }
+
+    pub fn with_log(mut self, log: ConnectionLog) -> Self {
+        self.log = Arc::new(log);
+        self
+    }
+
+    /// Where the output of the processes we start goes.
+    pub fn log(&self) -> &ConnectionLog {
+        &self.log
+    }
+
+    /// Adds a line of our own to the log of `config_path`'s tunnel, next to
+    /// what OpenVPN printed, e.g. a saved secret that could not be used.
+    pub fn note(&self, config_path: &str, text: impl Into<String>) {
+        let _ = self.log.append(&Self::instance_name(config_path), LogLine::new(LogStream::Note, text));
+    }
+
+    /// How long each shutdown step waits for OpenVPN to exit.
+    pub fn with_shutdown_timeout(mut self, timeout: Duration) -> Self {
+        self.shutdown_timeout = timeout;
+        self
+    }
+
+    pub fn with_traffic_meter(mut self, traffic: TrafficMeter) -> Self {
+        self.traffic = traffic;
+        self
+    }
+
+    /// Kernel byte counters of a tunnel interface.
+    pub fn interface_traffic(&self, interface: &str) -> Option<TrafficStats> {
+        self.traffic.sample(interface)
+    }
+
+    /// Management client of the tunnel this service started for `config_path`.
+    pub async fn management(&self, config_path: &str) -> Option<Arc<ManagementClient>> {
+        let tunnels = self.tunnels.lock().await;
+        tunnels.get(config_path).map(|tunnel| Arc::clone(&tunnel.management))
+    }
+
+    /// Attaches to the management socket of a tunnel that another instance of
+    /// this program started. OpenVPN serves one client at a time, so this
+    /// fails while that instance is still attached.
+    pub async fn probe_management(&self, config_path: &str) -> Option<Arc<ManagementClient>> {
+        let socket_path = self.management_socket_path(config_path);
+        ManagementClient::connect(&socket_path).await.ok()
+    }
+
+    /// Whether any OpenVPN runs on the system, ours or not.
     pub async fn is_connected(&self) -> bool {
-        // Check both internal state and actual system processes
-        let internal_connected = *self.connected.lock().await;
-        let system_connected = self.check_system_openvpn_processes().await;
-        
-        // If system says connected but internal says not, sync the internal state
-        if system_connected && !internal_connected {
-            let mut connected = self.connected.lock().await;
-            *connected = true;
-        }
-        
-        // If system says disconnected but internal says connected, sync the internal state
-        if !system_connected && internal_connected {
-            let mut connected = self.connected.lock().await;
-            *connected = false;
-        }
-        
-        system_connected
+        self.check_system_openvpn_processes().await
     }
 
     async fn check_system_openvpn_processes(&self) -> bool {
-        self.get_connected_vpn_config().await.is_some()
+        !self.running_instances().await.is_empty()
     }
 
-    pub async fn get_connected_vpn_config(&self) -> Option<String> {
-        use std::process::Command;
-        
-        // Check if any OpenVPN processes are running and get their config file
-        if let Ok(output) = Command::new("ps")
-            .args(&["aux"])
-            .output()
-        {
-            let output_str = String::from_utf8_lossy(&output.stdout);
-            // Look for OpenVPN processes (excluding grep itself)
-            for line in output_str.lines() {
-                if line.contains("/usr/bin/openvpn") && 
-                   line.contains("--config") && 
-                   !line.contains("grep") {
-                    // Extract the config file path from the ps output
-                    if let Some(config_start) = line.find("--config") {
-                        let config_part = &line[config_start + 8..]; // Skip "--config"
-                        if let Some(config_path) = config_part.trim().split_whitespace().next() {
-                            return Some(config_path.to_string());
-                        }
-                    }
-                }
-            }
+    /// Every OpenVPN process on the system, whoever started it.
+    pub async fn running_instances(&self) -> Vec<OpenVpnProcess> {
+        self.inspector.openvpn_processes()
+    }
+
+    /// Config files of every running OpenVPN, one per tunnel.
+    pub async fn connected_vpn_configs(&self) -> Vec<String> {
+        self.running_instances()
+            .await
+            .into_iter()
+            .filter_map(|process| process.config_path)
+            .map(|path| path.to_string_lossy().to_string())
+            .collect()
+    }
+
+    pub fn build_openvpn_args(&self, config_path: &str) -> Vec<String> {
+        vec!["--config".to_string(), config_path.to_string()]
+    }
+
+    /// OpenVPN runs as root and creates the socket world-writable, so access is
+    /// restricted to the invoking user. `--management-hold` keeps OpenVPN idle
+    /// until we are attached and no early state change is missed; the hold
+    /// is turned off once attached, so later restarts need nobody. With a
+    /// resident client, passwords are asked over the socket as well, since
+    /// OpenVPN has no terminal, and a rejected password is asked again
+    /// instead of ending the process.
+    pub fn build_management_args(&self, socket_path: &Path) -> Vec<String> {
+        let mut args = vec![
+            "--management".to_string(),
+            socket_path.to_string_lossy().to_string(),
+            "unix".to_string(),
+            "--management-hold".to_string(),
+        ];
+        if self.resident_client {
+            args.extend(["--management-query-passwords", "--auth-retry", "interact"].map(str::to_string));
         }
-        
-        None
+        // SAFETY: getuid cannot fail and has no preconditions.
+        let uid = unsafe { libc::getuid() };
+        if let Some(user) = self.inspector.user_name(uid) {
+            args.push("--management-client-user".to_string());
+            args.push(user);
+        }
+        args
     }
 
-    async fn get_openvpn_pids(&self) -> Option<Vec<u32>> {
-        use std::process::Command;
-        
-        // Get PIDs of OpenVPN processes
-        if let Ok(output) = Command::new("pgrep")
-            .args(&["-f", "openvpn"])
-            .output()
-        {
-            let output_str = String::from_utf8_lossy(&output.stdout);
-            let pids: Vec<u32> = output_str
-                .lines()
-                .filter_map(|line| line.trim().parse().ok())
-                .collect();
-            
-            if !pids.is_empty() {
-                return Some(pids);
+    /// Per-profile socket in [`runtime_dir`], named after [`Self::instance_name`].
+    pub fn management_socket_path(&self, config_path: &str) -> PathBuf {
+        runtime_dir().join(format!("{}.sock", Self::instance_name(config_path)))
+    }
+
+    /// Names a profile's socket and log: its sanitized file stem, so it never
+    /// contains a dot or a slash, and a hash of the canonical config path,
+    /// so `a.b.ovpn` and `a_b.ovpn` or equal names in other directories
+    /// stay apart. FNV-1a keeps the hash the same across builds and runs.
+    pub fn instance_name(config_path: &str) -> String {
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = Path::new(config_path);
+        let stem: String = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| "openvpn".to_string())
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+
+        let canonical = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let hash = canonical
+            .as_os_str()
+            .as_bytes()
+            .iter()
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3));
+        format!("{}-{:08x}", stem, hash as u32)
+    }
+
+    /// Copies one of the child's pipes into the log until it closes. Reading
+    /// it also keeps OpenVPN from blocking on a full pipe.
+    fn pump_output(
+        &self,
+        name: String,
+        stream: LogStream,
+        pipe: Option<impl AsyncRead + Unpin + Send + 'static>,
+    ) -> Option<tokio::task::JoinHandle<()>> {
+        let log = Arc::clone(&self.log);
+        let mut lines = BufReader::new(pipe?).lines();
+        Some(tokio::spawn(async move {
+            while let Ok(Some(line)) = lines.next_line().await {
+                let _ = log.append(&name, LogLine::new(stream, line));
             }
+        }))
+    }
+
+    async fn prepare_socket_dir(socket_path: &Path) -> std::io::Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        if let Some(dir) = socket_path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+            tokio::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700)).await?;
+        }
+        match tokio::fs::remove_file(socket_path).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
         }
-        
-        None
     }
 
-    pub fn build_openvpn_args(&self, config_path: &str) -> Vec<String> {
-        vec!["--config".to_string(), config_path.to_string()]
+    /// Waits for the socket and subscribes to state, log and traffic
+    /// notifications. OpenVPN stays on hold until the caller releases it.
+    async fn attach_management(
+        socket_path: &Path,
+        child: &mut Child,
+    ) -> Result<Arc<ManagementClient>, Box<dyn std::error::Error + Send + Sync>> {
+        let deadline = tokio::time::Instant::now() + MANAGEMENT_ATTACH_TIMEOUT;
+
+        let client = loop {
+            if let Some(status) = child.try_wait()? {
+                return Err(format!("OpenVPN exited before it was ready ({})", status).into());
+            }
+            if socket_path.exists() {
+                if let Ok(client) = ManagementClient::connect(socket_path).await {
+                    break client;
+                }
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Err("Timed out waiting for the OpenVPN management interface".into());
+            }
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        };
+
+        client.enable_state_events().await?;
+        client.enable_log_events().await?;
+        client.enable_bytecount(BYTECOUNT_INTERVAL_SECS).await?;
+        Ok(client)
     }
 
+    /// Starts OpenVPN for `config_path` and attaches to its management
+    /// interface. The process waits on hold until `release_hold` is sent
+    /// through [`Self::management`], so listeners can subscribe first.
+    /// Tunnels of other profiles keep running; an earlier one of the same
+    /// profile is stopped, as both would share the socket.
     pub async fn connect(&self, config_path: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        // Kill existing connection if any
-        self.disconnect().await?;
+        self.connect_with_secrets(config_path, &LaunchSecrets::default()).await
+    }
+
+    /// Same as [`Self::connect`], handing `secrets` to OpenVPN in 0600 files
+    /// next to the socket.
+    pub async fn connect_with_secrets(
+        &self,
+        config_path: &str,
+        secrets: &LaunchSecrets,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.disconnect(config_path).await?;
+
+        let socket_path = self.management_socket_path(config_path);
+        Self::prepare_socket_dir(&socket_path).await?;
+        let name = Self::instance_name(config_path);
+        self.log.begin(&name);
+        let (secret_files, secret_args) = Self::write_secrets(&socket_path, secrets)?;
 
         // Try pkexec first (GUI-friendly), fallback to sudo if not available
         let mut cmd = if Command::new("pkexec").arg("--version").output().await.is_ok() {
             let mut cmd = Command::new("pkexec");
             cmd.arg("openvpn")
-               .args(self.build_openvpn_args(config_path));
+               .args(self.build_openvpn_args(config_path))
+               .args(self.build_management_args(&socket_path))
+               .args(&secret_args);
             cmd
         } else {
             let mut cmd = Command::new("sudo");
             cmd.arg("openvpn")
-               .args(self.build_openvpn_args(config_path));
+               .args(self.build_openvpn_args(config_path))
+               .args(self.build_management_args(&socket_path))
+               .args(&secret_args);
             cmd
         };
         
-        cmd.stdin(Stdio::null())
+        // Lines are stamped when logged; OpenVPN's own stamps would repeat them.
+        cmd.arg("--suppress-timestamps")
+           .stdin(Stdio::null())
            .stdout(Stdio::piped())
            .stderr(Stdio::piped());
 
-        let child = cmd.spawn().map_err(|e| {
+        let mut child = cmd.spawn().map_err(|e| {
             format!("Failed to start OpenVPN process: {}. Make sure OpenVPN is installed and you have proper permissions.", e)
         })?;
-        
-        {
-            let mut process = self.process.lock().await;
-            *process = Some(child);
+
+        let pumps: Vec<_> = [
+            self.pump_output(name.clone(), LogStream::Stdout, child.stdout.take()),
+            self.pump_output(name.clone(), LogStream::Stderr, child.stderr.take()),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        let client = match Self::attach_management(&socket_path, &mut child).await {
+            Ok(client) => client,
+            Err(e) => {
+                // Held at startup, the process would otherwise wait forever.
+                let _ = child.kill().await;
+                // Let the pipes drain so the report has OpenVPN's last words.
+                for pump in pumps {
+                    let _ = tokio::time::timeout(Duration::from_secs(1), pump).await;
+                }
+                let last_lines: Vec<String> = self
+                    .log
+                    .recent(&name, FAILURE_CONTEXT_LINES)
+                    .into_iter()
+                    .map(|line| line.text)
+                    .collect();
+                if last_lines.is_empty() {
+                    return Err(e);
+                }
+                return Err(format!("{}\n{}", e, last_lines.join("\n")).into());
+            }
+        };
+
+        let tunnel = OwnedTunnel { child, socket_path, management: client, secrets: secret_files };
+        self.tunnels.lock().await.insert(config_path.to_string(), tunnel);
+        Ok(())
+    }
+
+    /// Writes each of `secrets` next to the socket and returns the files with
+    /// the options naming them. Options after `--config` override the
+    /// profile's own `auth-user-pass` and `askpass`.
+    fn write_secrets(
+        socket_path: &Path,
+        secrets: &LaunchSecrets,
+    ) -> std::io::Result<(Vec<SecretFile>, Vec<String>)> {
+        let mut files = Vec::new();
+        let mut args = Vec::new();
+        if let Some(credentials) = &secrets.credentials {
+            let contents = format!("{}\n{}\n", credentials.username(), credentials.password());
+            let file = SecretFile::create(socket_path.with_extension("auth"), &contents)?;
+            args.extend(["--auth-user-pass".to_string(), file.0.to_string_lossy().to_string()]);
+            files.push(file);
         }
-        
-        {
-            let mut connected = self.connected.lock().await;
-            *connected = true;
+        if let Some(passphrase) = &secrets.key_passphrase {
+            let file = SecretFile::create(socket_path.with_extension("askpass"), &format!("{}\n", passphrase))?;
+            args.extend(["--askpass".to_string(), file.0.to_string_lossy().to_string()]);
+            files.push(file);
         }
+        Ok((files, args))
+    }
 
-        Ok(())
+    /// Removes the files [`Self::connect_with_secrets`] wrote for
+    /// `config_path`. OpenVPN keeps what it read in memory, including across
+    /// restarts, so this is safe once the tunnel is up or has failed.
+    pub async fn discard_secrets(&self, config_path: &str) {
+        if let Some(tunnel) = self.tunnels.lock().await.get_mut(config_path) {
+            tunnel.secrets.clear();
+        }
     }
 
-    pub async fn disconnect(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let mut process_guard = self.process.lock().await;
-        if let Some(mut child) = process_guard.take() {
-            // Try to kill the process gracefully
-            if let Err(e) = child.kill().await {
-                eprintln!("Failed to kill OpenVPN process: {}", e);
-            }
-            
-            // Wait for the process to exit
-            if let Err(e) = child.wait().await {
-                eprintln!("Failed to wait for OpenVPN process: {}", e);
+    /// How the OpenVPN this service started for `config_path` ended, once it
+    /// has; `None` while it runs or when there is none.
+    pub async fn exit_status(&self, config_path: &str) -> Option<ExitStatus> {
+        let mut tunnels = self.tunnels.lock().await;
+        tunnels.get_mut(config_path)?.child.try_wait().ok().flatten()
+    }
+
+    /// Stops the tunnel of `config_path`, whether this service or another
+    /// instance started it, with the gentlest step that works: `signal
+    /// SIGTERM` over the management interface, SIGTERM to the process, and
+    /// SIGKILL only when it outlived both. Other tunnels are left alone.
+    pub async fn disconnect(&self, config_path: &str) -> Result<Vec<StoppedInstance>, Box<dyn std::error::Error + Send + Sync>> {
+        let tunnel = self.tunnels.lock().await.remove(config_path);
+        let mut shutdown = Shutdown::default();
+        if let Some(tunnel) = tunnel {
+            self.stop_owned(tunnel, &mut shutdown).await;
+        }
+        for process in self.running_instances().await {
+            if process.config_path.as_deref() == Some(Path::new(config_path)) {
+                self.stop_foreign(process, &mut shutdown).await;
             }
         }
-        
-        // Drop the lock before calling force_kill_all
-        drop(process_guard);
+        shutdown.into_result()
+    }
 
-        // Also force kill any system processes that might still be running
-        if self.check_system_openvpn_processes().await {
-            self.force_kill_all().await?;
-        } else {
-            // Update internal state if no processes found
-            let mut connected = self.connected.lock().await;
-            *connected = false;
+    /// Stops every tunnel this service started, then any other OpenVPN still
+    /// running, escalating per process like [`Self::disconnect`].
+    pub async fn disconnect_all(&self) -> Result<Vec<StoppedInstance>, Box<dyn std::error::Error + Send + Sync>> {
+        let tunnels: Vec<OwnedTunnel> = self.tunnels.lock().await.drain().map(|(_, tunnel)| tunnel).collect();
+        let mut shutdown = Shutdown::default();
+        for tunnel in tunnels {
+            self.stop_owned(tunnel, &mut shutdown).await;
+        }
+        for process in self.running_instances().await {
+            self.stop_foreign(process, &mut shutdown).await;
         }
+        shutdown.into_result()
+    }
 
-        Ok(())
+    async fn stop_owned(&self, mut tunnel: OwnedTunnel, shutdown: &mut Shutdown) {
+        let pid = self.instance_with_socket(&tunnel.socket_path).or(tunnel.child.id());
+        if let Some(pid) = pid {
+            shutdown.record(pid, self.stop_instance(pid, Some(tunnel.management)).await);
+        }
+        // Reap the wrapper; it exits along with OpenVPN.
+        let _ = tokio::time::timeout(self.shutdown_timeout, tunnel.child.wait()).await;
+    }
+
+    async fn stop_foreign(&self, process: OpenVpnProcess, shutdown: &mut Shutdown) {
+        let management = match &process.config_path {
+            Some(config_path) => {
+                let config_path = config_path.to_string_lossy();
+                let probe = self.probe_management(&config_path);
+                tokio::time::timeout(PROBE_TIMEOUT, probe).await.ok().flatten()
+            }
+            None => None,
+        };
+        shutdown.record(process.pid, self.stop_instance(process.pid, management).await);
+    }
+
+    /// PID of the OpenVPN listening on `socket_path` for management.
+    fn instance_with_socket(&self, socket_path: &Path) -> Option<u32> {
+        let socket_path = socket_path.to_string_lossy();
+        self.inspector
+            .openvpn_processes()
+            .into_iter()
+            .find(|process| process.args.windows(2).any(|pair| pair[0] == "--management" && pair[1] == socket_path))
+            .map(|process| process.pid)
+    }
+
+    /// Stops OpenVPN process `pid`, escalating only when the previous step
+    /// did not end it within the shutdown timeout.
+    pub async fn stop_instance(
+        &self,
+        pid: u32,
+        management: Option<Arc<ManagementClient>>,
+    ) -> Result<ShutdownStep, String> {
+        if let Some(management) = management {
+            if management.signal("SIGTERM").await.is_ok() && self.wait_for_exit(pid).await {
+                return Ok(ShutdownStep::Management);
+            }
+        }
+        if send_signal(pid, libc::SIGTERM, "TERM").await && self.wait_for_exit(pid).await {
+            return Ok(ShutdownStep::Terminate);
+        }
+        if send_signal(pid, libc::SIGKILL, "KILL").await && self.wait_for_exit(pid).await {
+            return Ok(ShutdownStep::Kill);
+        }
+        Err(format!("OpenVPN (PID {}) is still running; stop it as root", pid))
+    }
+
+    async fn wait_for_exit(&self, pid: u32) -> bool {
+        let deadline = tokio::time::Instant::now() + self.shutdown_timeout;
+        loop {
+            // Zombies no longer look like OpenVPN, so they count as gone.
+            if self.inspector.inspect(pid).is_none() {
+                return true;
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return false;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
     }
 
     pub async fn get_status(&self) -> ConnectionStatus {
         let connected = self.is_connected().await;
-        let process_guard = self.process.lock().await;
-        let has_process = process_guard.is_some();
+        let has_process = !self.tunnels.lock().await.is_empty();
 
         match (connected, has_process) {
             (true, true) => ConnectionStatus::Connected,
@@ -175,92 +596,55 @@ impl OpenVpnService {
         }
     }
 
-    pub async fn force_kill_all(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        use std::process::Command;
-
-        // First try to kill any processes we're tracking
-        {
-            let mut process_guard = self.process.lock().await;
-            if let Some(mut child) = process_guard.take() {
-                let _ = child.kill().await; // Ignore errors
-            }
-        }
-
-        // Force kill all system OpenVPN processes 
-        let kill_commands = [
-            // Try pkexec first (GUI-friendly, doesn't need terminal)
-            vec!["pkexec", "pkill", "-9", "-f", "openvpn"],
-            vec!["pkexec", "killall", "-9", "openvpn"],
-            // Try user-level kills
-            vec!["pkill", "-9", "-f", "openvpn"],
-            vec!["killall", "-9", "openvpn"],
-            vec!["pkill", "-f", "/usr/bin/openvpn"],
-            vec!["killall", "openvpn"],
-            // Try getting the PID and killing it directly
-            vec!["sh", "-c", "pkill -9 -f 'openvpn --config'"],
-            vec!["sh", "-c", "killall -9 openvpn 2>/dev/null || true"],
-        ];
-
-        let mut killed_any = false;
-
-        // First try to get PIDs and kill them directly
-        if let Some(pids) = self.get_openvpn_pids().await {
-            for pid in pids {
-                let pid_str = pid.to_string();
-                
-                // Try to kill each PID directly
-                let kill_pid_commands = [
-                    vec!["pkexec", "kill", "-9", &pid_str],
-                    vec!["kill", "-9", &pid_str],
-                ];
-                
-                for cmd in &kill_pid_commands {
-                    if let Ok(output) = Command::new(cmd[0]).args(&cmd[1..]).output() {
-                        if output.status.success() {
-                            killed_any = true;
-                            break;
-                        }
-                    }
-                }
-            }
-        }
+    /// Same as [`Self::disconnect_all`]; kept for the "kill all" actions of the UIs.
+    pub async fn force_kill_all(&self) -> Result<Vec<StoppedInstance>, Box<dyn std::error::Error + Send + Sync>> {
+        self.disconnect_all().await
+    }
+}
 
-        // If PID killing didn't work, try the generic commands
-        if self.check_system_openvpn_processes().await {
-            for cmd_args in &kill_commands {
-                if let Ok(output) = Command::new(cmd_args[0])
-                    .args(&cmd_args[1..])
-                    .output()
-                {
-                    if output.status.success() {
-                        killed_any = true;
-                    }
-                }
-                
-                // Small delay between attempts
-                tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
-                
-                // Check if any processes are still running
-                if !self.check_system_openvpn_processes().await {
-                    break; // All processes killed
-                }
-            }
-        }
+/// What a disconnect stopped and what it could not.
+#[derive(Default)]
+struct Shutdown {
+    stopped: Vec<StoppedInstance>,
+    failures: Vec<String>,
+}
 
-        // Update internal state
-        {
-            let mut connected = self.connected.lock().await;
-            *connected = false;
+impl Shutdown {
+    fn record(&mut self, pid: u32, outcome: Result<ShutdownStep, String>) {
+        match outcome {
+            Ok(step) => self.stopped.push(StoppedInstance { pid, step }),
+            Err(e) => self.failures.push(e),
         }
+    }
 
-        if killed_any || !self.check_system_openvpn_processes().await {
-            Ok(())
+    fn into_result(self) -> Result<Vec<StoppedInstance>, Box<dyn std::error::Error + Send + Sync>> {
+        if self.failures.is_empty() {
+            Ok(self.stopped)
         } else {
-            Err("Failed to kill OpenVPN processes. Try running with sudo or check if processes exist.".into())
+            Err(self.failures.join("; ").into())
         }
     }
 }
 
+/// Sends `signal` to `pid`, through pkexec when the process belongs to root.
+/// A process that is already gone counts as signalled.
+async fn send_signal(pid: u32, signal: libc::c_int, name: &str) -> bool {
+    let Ok(target) = libc::pid_t::try_from(pid) else { return false };
+    // SAFETY: kill has no memory-safety preconditions.
+    if unsafe { libc::kill(target, signal) } == 0 {
+        return true;
+    }
+    match std::io::Error::last_os_error().raw_os_error() {
+        Some(libc::ESRCH) => true,
+        Some(libc::EPERM) => Command::new("pkexec")
+            .args(["kill", "-s", name, &pid.to_string()])
+            .status()
+            .await
+            .is_ok_and(|status| status.success()),
+        _ => false,
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum ConnectionStatus {
     Disconnected,
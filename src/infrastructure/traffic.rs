@@ -0,0 +1,55 @@
+use crate::domain::entities::TrafficStats;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// Reads interface counters from `/sys/class/net/<iface>/statistics` and
+/// remembers the previous sample of each interface to derive throughput.
+#[derive(Debug)]
+pub struct TrafficMeter {
+    sys_class_net: PathBuf,
+    samples: Mutex<HashMap<String, TrafficStats>>,
+}
+
+impl TrafficMeter {
+    pub fn new(sys_class_net: PathBuf) -> Self {
+        Self {
+            sys_class_net,
+            samples: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn system() -> Self {
+        Self::new(PathBuf::from("/sys/class/net"))
+    }
+
+    /// Current counters of `interface`, or `None` when it does not exist.
+    pub fn sample(&self, interface: &str) -> Option<TrafficStats> {
+        // Interface names never contain '/', so this cannot escape the root.
+        if interface.is_empty() || interface.contains('/') {
+            return None;
+        }
+        let statistics = self.sys_class_net.join(interface).join("statistics");
+        let read = |name: &str| -> Option<u64> { fs::read_to_string(statistics.join(name)).ok()?.trim().parse().ok() };
+        // Seen from the tunnel device, received bytes came from the server.
+        let bytes_in = read("rx_bytes")?;
+        let bytes_out = read("tx_bytes")?;
+
+        let now = SystemTime::now();
+        let mut samples = self.samples.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let stats = match samples.get(interface) {
+            Some(previous) => previous.next(bytes_in, bytes_out, now),
+            None => TrafficStats::new(bytes_in, bytes_out, now),
+        };
+        samples.insert(interface.to_string(), stats.clone());
+        Some(stats)
+    }
+}
+
+impl Default for TrafficMeter {
+    fn default() -> Self {
+        Self::system()
+    }
+}
@@ -1,12 +1,31 @@
 use ui_openvpn_linux::{
-    application::services::VpnApplicationService,
-    infrastructure::{repositories::FileVpnRepository, services::OpenVpnService},
+    application::dtos::{ErrorDto, ValidationResponse, VpnConnectionResponse, VpnListResponse},
+    application::mappers::{ResponseMapper, VpnMapper},
+    application::services::{ProfileExport, VpnApplicationService, VpnControl, VpnServiceError},
+    infrastructure::{
+        archive::ProfileArchive, credentials::EncryptedCredentialStore, files::write_private,
+        repositories::FileVpnRepository,
+        services::{OpenVpnService, StoppedInstance},
+    },
 };
+use ui_openvpn_linux::domain::{bundle::BundleMode, validation::Severity};
+use ui_openvpn_linux::domain::credentials::CredentialPrompt;
+use ui_openvpn_linux::domain::entities::{ConnectionState, ProfileMetadata, Vpn, VpnError, VpnStatus};
+use ui_openvpn_linux::ui::{
+    askpass::AskpassPrompt,
+    daemon::{self, ClientPrompt, DaemonClient, DaemonServer},
+    format,
+    terminal::{self, TerminalPrompt},
+};
+use std::path::{Path, PathBuf};
+use std::io::IsTerminal;
 use std::sync::Arc;
-use clap::{Args, Parser, Subcommand};
+use std::time::SystemTime;
+use serde::Serialize;
+use clap::{Args, Parser, Subcommand, ValueEnum};
 
 #[cfg(feature = "gui")]
-use ui_openvpn_linux::ui::gtk::MainWindow;
+use ui_openvpn_linux::ui::gtk::{GtkCredentialPrompt, MainWindow};
 #[cfg(feature = "gui")]
 use gtk4::prelude::*;
 
@@ -44,6 +63,19 @@ struct Cli {
     /// Enable verbose output
     #[arg(short, long)]
     verbose: bool,
+
+    /// Ask for passwords by running PROGRAM with the prompt as its argument
+    /// (ssh-askpass style) instead of the terminal or the GUI
+    #[arg(long, global = true, value_name = "PROGRAM")]
+    askpass: Option<PathBuf>,
+
+    /// Control socket of the daemon (defaults to $XDG_RUNTIME_DIR/ui-openvpn/daemon.rpc.sock)
+    #[arg(long, global = true, value_name = "PATH")]
+    socket: Option<PathBuf>,
+
+    /// How list, status, info, validate, connect and disconnect print their results
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Table)]
+    output: OutputFormat,
 }
 
 #[derive(Subcommand)]
@@ -55,19 +87,51 @@ enum Commands {
     /// Connect to a specific VPN
     Connect(ConnectArgs),
     
-    /// Disconnect current VPN connection
+    /// Disconnect a VPN, or every active one
     #[command(alias = "dc")]
-    Disconnect,
+    Disconnect(DisconnectArgs),
     
     /// Show current connection status
     #[command(alias = "st")]
-    Status,
+    Status(StatusArgs),
     
     /// Validate VPN configuration files
-    Validate,
+    Validate(ValidateArgs),
     
     /// Show detailed information about a VPN
     Info(InfoArgs),
+
+    /// Add a .ovpn file to the configured VPNs
+    Import(ImportArgs),
+
+    /// Delete a VPN profile and its metadata
+    #[command(alias = "rm")]
+    Remove(RemoveArgs),
+
+    /// Change the ID (file name) of a VPN profile
+    Rename(RenameArgs),
+
+    /// Set or reset the name shown for a VPN
+    SetName(SetNameArgs),
+
+    /// Open a VPN profile in $EDITOR and re-validate it on save
+    Edit(EditArgs),
+
+    /// Write a VPN profile in another format
+    Export(ExportArgs),
+
+    /// Delete the saved username, password and TOTP seed of a VPN profile
+    Forget(ForgetArgs),
+
+    /// Save a TOTP seed so challenges are answered without asking
+    Totp(TotpArgs),
+
+    /// Show what OpenVPN printed for a VPN profile
+    Logs(LogsArgs),
+
+    /// Stay running and own the VPN connection; list, connect, disconnect and
+    /// status then go through it
+    Daemon,
 }
 
 #[derive(Args)]
@@ -75,9 +139,48 @@ struct ConnectArgs {
     /// VPN ID to connect to
     vpn_id: String,
     
-    /// Force connection even if another VPN is active
+    /// Reconnect an active VPN, or stop the ones an exclusive profile conflicts with
     #[arg(short, long)]
     force: bool,
+
+    /// Stay in the foreground and reconnect when the tunnel drops; Ctrl+C disconnects
+    #[arg(short, long)]
+    keep_alive: bool,
+}
+
+#[derive(Args, Default)]
+struct DisconnectArgs {
+    /// VPN ID to disconnect (defaults to every active VPN)
+    vpn_id: Option<String>,
+}
+
+#[derive(Args, Default)]
+struct StatusArgs {
+    /// Keep refreshing until interrupted
+    #[arg(short, long)]
+    watch: bool,
+
+    /// Seconds between refreshes in --watch mode
+    #[arg(short, long, default_value_t = 2, value_name = "SECS")]
+    interval: u64,
+}
+
+#[derive(Args)]
+struct ValidateArgs {
+    /// VPN IDs or .ovpn file paths to check (defaults to all configured VPNs)
+    targets: Vec<String>,
+
+    /// Treat warnings as errors for the exit code
+    #[arg(long)]
+    strict: bool,
+
+    /// Also flag directives deprecated or insecure in OpenVPN 2.6
+    #[arg(long)]
+    lint: bool,
+
+    /// Write an auto-fixed copy of a single profile to PATH ('-' for stdout); implies --lint
+    #[arg(long, value_name = "PATH")]
+    fix_output: Option<PathBuf>,
 }
 
 #[derive(Args)]
@@ -86,37 +189,284 @@ struct InfoArgs {
     vpn_id: String,
 }
 
+#[derive(Args)]
+struct ImportArgs {
+    /// Path of the .ovpn file, NetworkManager .nmconnection keyfile, or .zip/.tar.gz provider bundle
+    file: PathBuf,
+
+    /// Profile ID (defaults to the file name without extension)
+    #[arg(long)]
+    id: Option<String>,
+
+    /// Display name for the imported VPN
+    #[arg(long)]
+    name: Option<String>,
+
+    /// Overwrite an existing profile with the same ID
+    #[arg(long)]
+    replace: bool,
+
+    /// Embed referenced certificates and keys in the profile instead of copying them
+    #[arg(long)]
+    inline: bool,
+
+    /// Archives: only list the profiles it contains
+    #[arg(long)]
+    list: bool,
+
+    /// Archives: import every profile
+    #[arg(long, conflicts_with = "select")]
+    all: bool,
+
+    /// Archives: import profiles whose ID or path matches PATTERN ('*' wildcards, repeatable)
+    #[arg(long, value_name = "PATTERN")]
+    select: Vec<String>,
+
+    /// Archives: prepend PREFIX to every imported ID
+    #[arg(long, value_name = "PREFIX")]
+    prefix: Option<String>,
+
+    /// Import even if validation reports errors
+    #[arg(short, long)]
+    force: bool,
+}
+
+#[derive(Args)]
+struct RemoveArgs {
+    /// VPN ID to remove
+    vpn_id: String,
+
+    /// Do not ask for confirmation
+    #[arg(short, long)]
+    yes: bool,
+}
+
+#[derive(Args)]
+struct RenameArgs {
+    /// Current VPN ID
+    vpn_id: String,
+
+    /// New VPN ID
+    new_id: String,
+}
+
+#[derive(Args)]
+struct SetNameArgs {
+    /// VPN ID to rename
+    vpn_id: String,
+
+    /// New display name; omit to show the ID again
+    name: Option<String>,
+}
+
+#[derive(Args)]
+struct EditArgs {
+    /// VPN ID to edit
+    vpn_id: String,
+}
+
+#[derive(Args)]
+struct ForgetArgs {
+    /// VPN ID whose credentials to delete
+    vpn_id: String,
+}
+
+#[derive(Args)]
+struct TotpArgs {
+    /// VPN ID the seed belongs to
+    vpn_id: String,
+
+    /// Delete the saved seed instead
+    #[arg(long)]
+    remove: bool,
+}
+
+#[derive(Args)]
+struct LogsArgs {
+    /// VPN ID whose output to show
+    vpn_id: String,
+
+    /// Keep printing new lines until interrupted
+    #[arg(short, long)]
+    follow: bool,
+
+    /// Only lines after this: a duration ago (`10m`, `2h`) or an RFC 3339 time
+    #[arg(long, value_name = "WHEN", value_parser = parse_since)]
+    since: Option<SystemTime>,
+}
+
+fn parse_since(value: &str) -> Result<SystemTime, String> {
+    if let Ok(ago) = humantime::parse_duration(value) {
+        return SystemTime::now().checked_sub(ago).ok_or_else(|| "duration too long".to_string());
+    }
+    humantime::parse_rfc3339_weak(value).map_err(|_| "expected a duration such as 10m or a time such as 2024-05-01T10:00:00Z".to_string())
+}
+
+#[derive(Args)]
+struct ExportArgs {
+    /// VPN IDs to export; more than one needs an archive as output
+    #[arg(required_unless_present = "all")]
+    vpn_ids: Vec<String>,
+
+    /// Export every profile (needs an archive as output)
+    #[arg(long, conflicts_with = "vpn_ids")]
+    all: bool,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = ExportFormat::Ovpn)]
+    format: ExportFormat,
+
+    /// File to write: .ovpn, or .zip/.tar/.tar.gz for several profiles (defaults to stdout)
+    #[arg(short = 'o', long, value_name = "PATH")]
+    file: Option<PathBuf>,
+
+    /// Leave out private keys, static keys and saved credentials
+    #[arg(long)]
+    strip_secrets: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ExportFormat {
+    /// Single .ovpn with every certificate and key inlined
+    Ovpn,
+    /// NetworkManager keyfile, for /etc/NetworkManager/system-connections/
+    #[value(name = "nm", alias = "networkmanager")]
+    NetworkManager,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// Text for people
+    Table,
+    /// Pretty-printed JSON, one document per result
+    Json,
+    /// YAML, one `---` document per result
+    Yaml,
+}
+
+impl OutputFormat {
+    fn is_structured(self) -> bool {
+        self != Self::Table
+    }
+}
+
+// Exit codes scripts can tell apart; clap exits with 2 on usage errors.
+const EXIT_FAILURE: i32 = 1;
+const EXIT_NOT_FOUND: i32 = 3;
+const EXIT_CONNECTION_FAILED: i32 = 4;
+const EXIT_AUTH_FAILED: i32 = 5;
+const EXIT_INVALID_PROFILE: i32 = 6;
+const EXIT_DAEMON_UNREACHABLE: i32 = 7;
+const EXIT_ALREADY_CONNECTED: i32 = 8;
+
+fn exit_code(error: &VpnServiceError) -> i32 {
+    match error {
+        VpnServiceError::VpnNotFound(_) => EXIT_NOT_FOUND,
+        VpnServiceError::Diagnosed(VpnError::AuthFailed) => EXIT_AUTH_FAILED,
+        VpnServiceError::Diagnosed(_) | VpnServiceError::ConnectionFailed(_) | VpnServiceError::OpenVpnError(_) => {
+            EXIT_CONNECTION_FAILED
+        }
+        VpnServiceError::InvalidProfile(_) => EXIT_INVALID_PROFILE,
+        VpnServiceError::DaemonUnreachable(_) => EXIT_DAEMON_UNREACHABLE,
+        _ => EXIT_FAILURE,
+    }
+}
+
+/// Prints `value` for scripts; callers handle [`OutputFormat::Table`] themselves.
+fn emit<T: Serialize>(output: OutputFormat, value: &T) {
+    let text = match output {
+        OutputFormat::Json => serde_json::to_string_pretty(value).map(|json| format!("{}\n", json)).map_err(|e| e.to_string()),
+        OutputFormat::Yaml => serde_yaml::to_string(value).map(|yaml| format!("---\n{}", yaml)).map_err(|e| e.to_string()),
+        OutputFormat::Table => return,
+    };
+    match text {
+        Ok(text) => print!("{}", text),
+        Err(e) => {
+            eprintln!("❌ Cannot serialize the result: {}", e);
+            std::process::exit(EXIT_FAILURE);
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
     
-    // Print beautiful ASCII logo
-    print_logo();
+    // Print beautiful ASCII logo, only for people: not into pipes, structured output or profiles written to stdout
+    let exporting_to_stdout = matches!(&cli.command, Some(Commands::Export(args)) if args.file.is_none())
+        || matches!(&cli.command, Some(Commands::Validate(args)) if args.fix_output.as_deref().is_some_and(|path| path.as_os_str() == "-"));
+    if !exporting_to_stdout && !cli.output.is_structured() && std::io::stdout().is_terminal() {
+        print_logo();
+    }
     
     // Initialize services with Dependency Injection
     let vpn_repository = Arc::new(FileVpnRepository::from_home_dir());
-    let openvpn_service = Arc::new(OpenVpnService::new());
-    let vpn_service = Arc::new(VpnApplicationService::new(
+    let credential_store = Arc::new(EncryptedCredentialStore::for_user(vpn_repository.config_dir()));
+    // Only processes that outlive the connect can answer OpenVPN's later questions.
+    let resident = cli.gui
+        || matches!(&cli.command, None | Some(Commands::Daemon))
+        || matches!(&cli.command, Some(Commands::Connect(args)) if args.keep_alive);
+    let openvpn_service = Arc::new(OpenVpnService::new().with_resident_client(resident));
+    let vpn_service = VpnApplicationService::new(
         vpn_repository,
         openvpn_service,
-    ));
+    )
+    .with_credential_store(credential_store);
+
+    // Passwords are asked where the user is: a dialog in the GUI, the terminal otherwise,
+    // unless an askpass helper was named.
+    let askpass = cli.askpass.clone().map(|program| Arc::new(AskpassPrompt::new(program)) as Arc<dyn CredentialPrompt>);
+    let mut prompt = askpass.clone();
+    #[cfg(feature = "gui")]
+    if cli.gui && prompt.is_none() {
+        prompt = Some(Arc::new(GtkCredentialPrompt));
+    }
+    if !cli.gui && prompt.is_none() {
+        prompt = TerminalPrompt::if_interactive().map(|terminal| Arc::new(terminal) as Arc<dyn CredentialPrompt>);
+    }
+
+    let socket = cli.socket.clone().unwrap_or_else(daemon::default_socket_path);
+    if let Some(Commands::Daemon) = cli.command {
+        // Prompts go to the client that asked to connect; only reconnects fall back to askpass.
+        let client_prompt = Arc::new(ClientPrompt::new(askpass));
+        let vpn_service = Arc::new(vpn_service.with_credential_prompt(client_prompt.clone()));
+        return run_daemon(vpn_service, client_prompt, &socket).await;
+    }
+
+    // Connections belong to the daemon when one is running, to this process otherwise.
+    let (vpn_service, control): (Arc<VpnApplicationService>, Arc<dyn VpnControl>) =
+        match DaemonClient::if_running(socket).await {
+            Some(client) => {
+                let client = match prompt {
+                    Some(prompt) => client.with_credential_prompt(prompt),
+                    None => client,
+                };
+                (Arc::new(vpn_service), Arc::new(client))
+            }
+            None => {
+                let vpn_service = Arc::new(match prompt {
+                    Some(prompt) => vpn_service.with_credential_prompt(prompt),
+                    None => vpn_service,
+                });
+                (Arc::clone(&vpn_service), vpn_service)
+            }
+        };
 
     // Handle GUI mode
     #[cfg(feature = "gui")]
     if cli.gui {
-        return run_gui_mode(vpn_service).await;
+        return run_gui_mode(vpn_service, control).await;
     }
     
     #[cfg(not(feature = "gui"))]
     if cli.gui {
-        eprintln!("❌ GUI mode not available. Compile with --features gui");
-        std::process::exit(1);
+        return run_gui_mode(vpn_service, control).await;
     }
 
     // Handle CLI commands
     match cli.command {
-        Some(command) => execute_command(command, vpn_service, cli.verbose).await,
-        None => run_interactive_mode(vpn_service).await,
+        Some(command) => execute_command(command, vpn_service, control, cli.verbose, cli.output).await,
+        None => run_interactive_mode(vpn_service, control).await,
     }
 }
 
@@ -143,17 +493,196 @@ fn print_logo() {
     "#, env!("CARGO_PKG_VERSION"));
 }
 
+fn print_profile_metadata(metadata: &ProfileMetadata, indent: &str) {
+    if let Some(description) = metadata.description() {
+        println!("{}Description: {}", indent, description);
+    }
+    if !metadata.tags().is_empty() {
+        println!("{}Tags: {}", indent, metadata.tags().join(", "));
+    }
+    if let Some(last_used) = metadata.last_used() {
+        println!("{}Last Used: {}", indent, humantime::format_rfc3339_seconds(last_used));
+    }
+}
+
+fn print_tunnel_details(status: &VpnStatus, indent: &str) {
+    if !status.ip_address().is_empty() {
+        println!("{}IP: {}", indent, status.ip_address());
+    }
+    if let Some(ipv6) = status.ipv6_address() {
+        println!("{}IPv6: {}", indent, ipv6);
+    }
+    if let Some(interface) = status.interface() {
+        println!("{}Interface: {}", indent, interface);
+    }
+    if let Some(gateway) = status.gateway() {
+        println!("{}Gateway: {}", indent, gateway);
+    }
+    match (status.remote_address(), status.remote_port()) {
+        (Some(address), Some(port)) => println!("{}Server: {}:{}", indent, address, port),
+        (Some(address), None) => println!("{}Server: {}", indent, address),
+        _ => {}
+    }
+}
+
+async fn print_status(control: &dyn VpnControl, verbose: bool) -> Result<(), VpnServiceError> {
+    println!("📊 VPN Connection Status:");
+    println!("─────────────────────────");
+
+    let vpns = control.list_vpns().await?;
+    let mut has_active = false;
+    for vpn in vpns {
+        if vpn.is_connected() {
+            println!("🟢 {} - CONNECTED", vpn.display_name());
+            if let Some(duration) = vpn.status().session_duration() {
+                println!("   Uptime: {}", format::format_duration(duration));
+            }
+            if let Some(traffic) = vpn.status().traffic() {
+                println!("   Traffic: {}", format::format_traffic(traffic));
+            }
+            if verbose {
+                println!("   VPN ID: {}", vpn.id());
+                println!("   Config: {}", vpn.config_path());
+                print_tunnel_details(vpn.status(), "   ");
+            }
+            has_active = true;
+        } else if let Some(phase) = format::format_phase(vpn.status()) {
+            if vpn.status().tunnel_state().is_some_and(|phase| phase.is_active()) {
+                println!("🟡 {} - {}", vpn.display_name(), phase.to_uppercase());
+                has_active = true;
+            } else {
+                println!("❌ {} - {}", vpn.display_name(), phase.to_uppercase());
+            }
+        } else if let ConnectionState::Error(reason) = vpn.status().state() {
+            println!("❌ {} - FAILED: {}", vpn.display_name(), reason);
+        } else {
+            println!("🔴 {} - DISCONNECTED", vpn.display_name());
+        }
+    }
+
+    if !has_active {
+        println!("\n💡 No active VPN connections");
+    }
+    Ok(())
+}
+
+/// Supervises the tunnel just connected, reporting each phase change, until
+/// Ctrl+C (which disconnects only this tunnel) or until reconnecting gives up. A tunnel the daemon started is
+/// supervised there, and the local supervisor has nothing to watch. With
+/// structured output every change is printed as the VPN's document.
+async fn keep_alive(vpn_service: &Arc<VpnApplicationService>, control: &dyn VpnControl, vpn_id: &str, output: OutputFormat) {
+    let supervisor = Arc::clone(vpn_service);
+    let supervisor = tokio::spawn(async move { supervisor.supervise().await });
+    if !output.is_structured() {
+        println!("🛡️  Keeping the tunnel up; Ctrl+C disconnects");
+    }
+
+    loop {
+        tokio::select! {
+            _ = control.tunnel_state_changed() => {}
+            _ = tokio::signal::ctrl_c() => break,
+        }
+        let Ok(vpns) = control.list_vpns().await else { continue };
+        let Some(vpn) = vpns.iter().find(|vpn| vpn.id() == vpn_id) else { continue };
+        let status = vpn.status();
+        if output.is_structured() {
+            emit(output, &VpnMapper::to_dto(vpn));
+            // Same end condition as below: a phase worth reporting that is no longer active.
+            let active = status.tunnel_state().is_some_and(|phase| phase.is_active());
+            if format::format_phase(status).is_some() && !active {
+                std::process::exit(EXIT_CONNECTION_FAILED);
+            }
+            continue;
+        }
+        match format::format_phase(status) {
+            Some(phase) if status.tunnel_state().is_some_and(|phase| phase.is_active()) => {
+                println!("🟡 {} - {}", vpn_id, phase.to_uppercase());
+            }
+            Some(phase) => {
+                eprintln!("❌ {} - {}", vpn_id, phase.to_uppercase());
+                std::process::exit(EXIT_CONNECTION_FAILED);
+            }
+            None if status.state() == &ConnectionState::Connected => println!("🟢 {} - CONNECTED", vpn_id),
+            None => {}
+        }
+    }
+
+    supervisor.abort();
+    if output.is_structured() {
+        let outcome = control.disconnect_vpn(vpn_id).await;
+        emit(output, &ResponseMapper::connection_response(&outcome, &format!("Disconnected from {}", vpn_id)));
+        if let Err(e) = outcome {
+            std::process::exit(exit_code(&e));
+        }
+        return;
+    }
+    println!("🔌 Disconnecting {}...", vpn_id);
+    match control.disconnect_vpn(vpn_id).await {
+        Ok(stopped) => print_stopped(&stopped),
+        Err(e) => {
+            eprintln!("❌ Failed to disconnect: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Without `--force`, connecting is refused when `vpn_id` is already up or
+/// would stop an active tunnel because one of the two is exclusive.
+fn refuse_conflicts(vpns: &[Vpn], vpn_id: &str, output: OutputFormat) {
+    let active = |vpn: &&Vpn| vpn.is_connected() || vpn.is_connecting();
+    let Some(target) = vpns.iter().find(|vpn| vpn.id() == vpn_id) else { return };
+    let message = if active(&target) {
+        format!("{} is already connected. Use --force to reconnect.", vpn_id)
+    } else if let Some(other) = vpns.iter().filter(active).find(|other| target.conflicts_with(other)) {
+        let exclusive = if target.metadata().options().exclusive { target } else { other };
+        format!("{} is connected and {} is exclusive. Use --force to disconnect it.", other.id(), exclusive.id())
+    } else {
+        return;
+    };
+
+    if output.is_structured() {
+        emit(output, &VpnConnectionResponse {
+            success: false,
+            message,
+            vpn: Some(VpnMapper::to_dto(target)),
+            stopped: Vec::new(),
+            failure: None,
+            error: Some(ErrorDto { kind: "already_connected".to_string(), detail: vpn_id.to_string() }),
+        });
+    } else {
+        eprintln!("⚠️  {}", message);
+    }
+    std::process::exit(EXIT_ALREADY_CONNECTED);
+}
+
+fn print_stopped(stopped: &[StoppedInstance]) {
+    for instance in stopped {
+        let icon = if instance.step.is_forced() { "⚠️" } else { "  " };
+        println!("{} {}", icon, format::format_stopped(instance));
+    }
+}
+
 async fn execute_command(
     command: Commands,
     vpn_service: Arc<VpnApplicationService>,
+    control: Arc<dyn VpnControl>,
     verbose: bool,
+    output: OutputFormat,
 ) -> Result<(), Box<dyn std::error::Error>> {
     match command {
+        Commands::List if output.is_structured() => match control.list_vpns().await {
+            Ok(vpns) => emit(output, &VpnListResponse { vpns: VpnMapper::to_dto_list(&vpns) }),
+            Err(e) => {
+                eprintln!("❌ Error listing VPNs: {}", e);
+                std::process::exit(exit_code(&e));
+            }
+        },
+
         Commands::List => {
             println!("📋 Available VPN configurations:");
             println!("────────────────────────────────");
             
-            match vpn_service.list_vpns().await {
+            match control.list_vpns().await {
                 Ok(vpns) => {
                     if vpns.is_empty() {
                         println!("❌ No VPN configurations found in ~/.connectvpn.conf/");
@@ -163,120 +692,271 @@ async fn execute_command(
                             let status_icon = if vpn.is_connected() { "🟢" } else { "🔴" };
                             let status_text = if vpn.is_connected() { "CONNECTED" } else { "DISCONNECTED" };
                             
-                            println!("  {} {} ({})", status_icon, vpn.display_name(), vpn.id());
+                            let favorite = if vpn.metadata().is_favorite() { " ⭐" } else { "" };
+                            println!("  {} {} ({}){}", status_icon, vpn.display_name(), vpn.id(), favorite);
                             if verbose {
                                 println!("     Status: {}", status_text);
                                 println!("     Config: {}", vpn.config_path());
+                                print_profile_metadata(vpn.metadata(), "     ");
+                                print_tunnel_details(vpn.status(), "     ");
                             }
                         }
                     }
                 },
                 Err(e) => {
                     eprintln!("❌ Error listing VPNs: {}", e);
-                    std::process::exit(1);
+                    std::process::exit(exit_code(&e));
                 }
             }
         },
         
         Commands::Connect(args) => {
             if !args.force {
-                // Check if another VPN is connected
-                if let Ok(vpns) = vpn_service.list_vpns().await {
-                    if vpns.iter().any(|v| v.is_connected()) {
-                        eprintln!("⚠️  Another VPN is already connected. Use --force to override.");
-                        std::process::exit(1);
-                    }
+                if let Ok(vpns) = control.list_vpns().await {
+                    refuse_conflicts(&vpns, &args.vpn_id, output);
                 }
             }
             
-            println!("🔌 Connecting to VPN: {}", args.vpn_id);
-            match vpn_service.connect_vpn(&args.vpn_id).await {
-                Ok(()) => {
-                    println!("✅ Successfully connected to {}!", args.vpn_id);
-                    if verbose {
-                        println!("   Connection established with OpenVPN");
+            if !output.is_structured() {
+                println!("🔌 Connecting to VPN: {}", args.vpn_id);
+            }
+            let outcome = control.connect_vpn(&args.vpn_id).await.map(|()| Vec::new());
+            if output.is_structured() {
+                let mut response = ResponseMapper::connection_response(&outcome, &format!("Connected to {}", args.vpn_id));
+                if outcome.is_ok() {
+                    if let Ok(vpns) = control.list_vpns().await {
+                        response.vpn = vpns.iter().find(|vpn| vpn.id() == args.vpn_id).map(VpnMapper::to_dto);
+                    }
+                }
+                emit(output, &response);
+            }
+            match outcome {
+                Ok(_) => {
+                    if !output.is_structured() {
+                        println!("✅ Successfully connected to {}!", args.vpn_id);
+                        if verbose {
+                            println!("   Connection established with OpenVPN");
+                        }
+                    }
+                    if args.keep_alive {
+                        keep_alive(&vpn_service, control.as_ref(), &args.vpn_id, output).await;
                     }
                 },
                 Err(e) => {
-                    eprintln!("❌ Failed to connect: {}", e);
-                    std::process::exit(1);
+                    if !output.is_structured() {
+                        eprintln!("❌ Failed to connect: {}", e);
+                        if let Some(hint) = e.hint() {
+                            eprintln!("💡 {}", hint);
+                        }
+                    }
+                    std::process::exit(exit_code(&e));
                 }
             }
         },
         
-        Commands::Disconnect => {
-            println!("🔌 Disconnecting VPN...");
-            match vpn_service.disconnect_current().await {
-                Ok(()) => {
-                    println!("✅ Successfully disconnected from VPN");
+        Commands::Disconnect(args) => {
+            let (outcome, done) = match &args.vpn_id {
+                Some(vpn_id) => {
+                    if !output.is_structured() {
+                        println!("🔌 Disconnecting {}...", vpn_id);
+                    }
+                    (control.disconnect_vpn(vpn_id).await, format!("Disconnected from {}", vpn_id))
+                }
+                None => {
+                    if !output.is_structured() {
+                        println!("🔌 Disconnecting every VPN...");
+                    }
+                    (control.disconnect_current().await, "Disconnected".to_string())
+                }
+            };
+            if output.is_structured() {
+                emit(output, &ResponseMapper::connection_response(&outcome, &done));
+                if let Err(e) = outcome {
+                    std::process::exit(exit_code(&e));
+                }
+                return Ok(());
+            }
+            match outcome {
+                Ok(stopped) => {
+                    print_stopped(&stopped);
+                    println!("✅ {}", done);
                 },
                 Err(e) => {
                     eprintln!("❌ Failed to disconnect: {}", e);
-                    std::process::exit(1);
+                    std::process::exit(exit_code(&e));
                 }
             }
         },
         
-        Commands::Status => {
-            println!("📊 VPN Connection Status:");
-            println!("─────────────────────────");
-            
-            match vpn_service.get_connection_status().await {
-                Ok(vpns) => {
-                    let mut has_active = false;
-                    for vpn in vpns {
-                        if vpn.is_connected() {
-                            println!("🟢 {} - CONNECTED", vpn.display_name());
-                            if verbose {
-                                println!("   VPN ID: {}", vpn.id());
-                                println!("   Config: {}", vpn.config_path());
-                                if !vpn.status().ip_address().is_empty() {
-                                    println!("   IP: {}", vpn.status().ip_address());
-                                }
-                            }
-                            has_active = true;
-                        } else {
-                            println!("🔴 {} - DISCONNECTED", vpn.display_name());
-                        }
+        Commands::Status(args) if output.is_structured() => {
+            let interval = tokio::time::Duration::from_secs(args.interval.max(1));
+            loop {
+                match control.list_vpns().await {
+                    Ok(vpns) => emit(output, &VpnListResponse { vpns: VpnMapper::to_dto_list(&vpns) }),
+                    Err(e) if !args.watch => {
+                        eprintln!("❌ Error checking status: {}", e);
+                        std::process::exit(exit_code(&e));
                     }
-                    
-                    if !has_active {
-                        println!("\n💡 No active VPN connections");
-                    }
-                },
-                Err(e) => {
+                    Err(e) => eprintln!("❌ Error checking status: {}", e),
+                }
+                if !args.watch {
+                    break;
+                }
+                tokio::select! {
+                    _ = tokio::time::sleep(interval) => {},
+                    _ = tokio::signal::ctrl_c() => break,
+                }
+            }
+        },
+
+        Commands::Status(args) => {
+            if !args.watch {
+                if let Err(e) = print_status(control.as_ref(), verbose).await {
                     eprintln!("❌ Error checking status: {}", e);
-                    std::process::exit(1);
+                    std::process::exit(exit_code(&e));
+                }
+                return Ok(());
+            }
+
+            let interval = tokio::time::Duration::from_secs(args.interval.max(1));
+            loop {
+                // Clear the screen and move the cursor home before redrawing.
+                print!("\x1b[2J\x1b[H");
+                if let Err(e) = print_status(control.as_ref(), verbose).await {
+                    eprintln!("❌ Error checking status: {}", e);
+                }
+                println!("\n⏱️  Refreshing every {}s, Ctrl+C to stop", interval.as_secs());
+
+                tokio::select! {
+                    _ = tokio::time::sleep(interval) => {},
+                    _ = tokio::signal::ctrl_c() => break,
                 }
             }
         },
         
-        Commands::Validate => {
-            println!("🔍 Validating VPN configurations...");
-            match vpn_service.list_vpns().await {
-                Ok(vpns) => {
-                    let mut valid_count = 0;
-                    for vpn in vpns {
-                        if std::path::Path::new(vpn.config_path()).exists() {
-                            println!("✅ {} - Configuration valid", vpn.display_name());
-                            valid_count += 1;
-                        } else {
-                            println!("❌ {} - Configuration file not found", vpn.display_name());
+        Commands::Validate(args) => {
+            // With the fixed profile on stdout, the report goes to stderr.
+            let fix_to_stdout = args.fix_output.as_deref().is_some_and(|path| path.as_os_str() == "-");
+            if output.is_structured() && fix_to_stdout {
+                eprintln!("❌ --fix-output - would mix the fixed profile into the {} output; give a file", if output == OutputFormat::Json { "JSON" } else { "YAML" });
+                std::process::exit(EXIT_FAILURE);
+            }
+            let say = |line: String| if fix_to_stdout { eprintln!("{}", line) } else { println!("{}", line) };
+            if !output.is_structured() {
+                say("🔍 Validating VPN configurations...".to_string());
+            }
+
+            let mut targets = Vec::new();
+            if args.targets.is_empty() {
+                match vpn_service.list_vpns().await {
+                    Ok(vpns) => {
+                        for vpn in vpns {
+                            targets.push((vpn.display_name().to_string(), PathBuf::from(vpn.config_path())));
                         }
+                    },
+                    Err(e) => {
+                        eprintln!("❌ Error validating configurations: {}", e);
+                        std::process::exit(exit_code(&e));
                     }
-                    println!("\n📈 Summary: {} valid configurations", valid_count);
-                },
-                Err(e) => {
-                    eprintln!("❌ Error validating configurations: {}", e);
+                }
+            } else {
+                for target in &args.targets {
+                    if Path::new(target).is_file() {
+                        targets.push((target.clone(), PathBuf::from(target)));
+                        continue;
+                    }
+                    match vpn_service.config_path_of(target).await {
+                        Ok(config_path) => targets.push((target.clone(), PathBuf::from(config_path))),
+                        Err(e) => {
+                            eprintln!("❌ {}", e);
+                            std::process::exit(exit_code(&e));
+                        }
+                    }
+                }
+            }
+
+            let lint = args.lint || args.fix_output.is_some();
+            let mut reports = Vec::new();
+            for (name, config_path) in &targets {
+                let mut report = vpn_service.validate_file(config_path).await;
+                if lint {
+                    report.merge(vpn_service.lint_file(config_path).await.findings);
+                }
+                reports.push((name.clone(), report));
+            }
+
+            let mut valid_count = 0;
+            let mut failed = false;
+            for (name, report) in &reports {
+                let errors = report.count(Severity::Error);
+                let warnings = report.count(Severity::Warning);
+                // Under --strict a warning fails the profile like an error.
+                let valid = errors == 0 && !(args.strict && warnings > 0);
+                failed |= !valid;
+                valid_count += usize::from(valid);
+                if output.is_structured() {
+                    continue;
+                }
+                if errors == 0 && warnings == 0 {
+                    say(format!("✅ {} - Configuration valid", name));
+                    continue;
+                }
+
+                if errors > 0 {
+                    say(format!("❌ {} - {} error(s), {} warning(s)", name, errors, warnings));
+                } else {
+                    say(format!("⚠️  {} - {} warning(s)", name, warnings));
+                }
+                for finding in &report.findings {
+                    say(format!("   {}", report.format_finding(finding)));
+                    if let Some(hint) = &finding.hint {
+                        say(format!("      💡 {}", hint));
+                    }
+                }
+            }
+
+            if output.is_structured() {
+                emit(output, &ValidationResponse {
+                    valid: valid_count,
+                    total: reports.len(),
+                    reports: reports.iter().map(|(name, report)| ResponseMapper::validation_report(name, report)).collect(),
+                });
+            } else {
+                say(format!("\n📈 Summary: {} of {} configurations valid", valid_count, reports.len()));
+            }
+
+            if let Some(fix_output) = &args.fix_output {
+                let [(_, config_path)] = targets.as_slice() else {
+                    eprintln!("❌ --fix-output needs exactly one profile, got {}", targets.len());
                     std::process::exit(1);
+                };
+                match vpn_service.fix_file(config_path).await {
+                    Ok(fixed) if fix_output.as_os_str() == "-" => print!("{}", fixed),
+                    Ok(fixed) => {
+                        std::fs::write(fix_output, fixed)?;
+                        if !output.is_structured() {
+                            println!("🔧 Fixed profile written to {}", fix_output.display());
+                        }
+                    },
+                    Err(e) => {
+                        eprintln!("❌ Cannot fix profile: {}", e);
+                        std::process::exit(exit_code(&e));
+                    }
                 }
             }
+
+            if failed {
+                std::process::exit(EXIT_INVALID_PROFILE);
+            }
         },
         
         Commands::Info(args) => {
-            match vpn_service.list_vpns().await {
+            match control.list_vpns().await {
                 Ok(vpns) => {
-                    if let Some(vpn) = vpns.iter().find(|v| v.id() == args.vpn_id) {
+                    let found = vpns.iter().find(|v| v.id() == args.vpn_id);
+                    if let (Some(vpn), true) = (found, output.is_structured()) {
+                        emit(output, &VpnMapper::to_dto(vpn));
+                    } else if let Some(vpn) = found {
                         println!("📋 VPN Information: {}", vpn.display_name());
                         println!("─────────────────────────────────");
                         println!("ID: {}", vpn.id());
@@ -284,8 +964,13 @@ async fn execute_command(
                         println!("Config Path: {}", vpn.config_path());
                         println!("Status: {}", if vpn.is_connected() { "🟢 CONNECTED" } else { "🔴 DISCONNECTED" });
                         
-                        if !vpn.status().ip_address().is_empty() {
-                            println!("IP Address: {}", vpn.status().ip_address());
+                        print_tunnel_details(vpn.status(), "");
+                        print_profile_metadata(vpn.metadata(), "");
+                        if let Some(timeout) = vpn.metadata().options().connect_timeout_secs {
+                            println!("Connect Timeout: {}s", timeout);
+                        }
+                        if vpn.metadata().options().exclusive {
+                            println!("Exclusive: yes (other VPNs are disconnected when it connects)");
                         }
                         
                         // Show file info if available
@@ -297,26 +982,538 @@ async fn execute_command(
                         }
                     } else {
                         eprintln!("❌ VPN '{}' not found", args.vpn_id);
-                        std::process::exit(1);
+                        std::process::exit(EXIT_NOT_FOUND);
                     }
                 },
                 Err(e) => {
                     eprintln!("❌ Error getting VPN info: {}", e);
+                    std::process::exit(exit_code(&e));
+                }
+            }
+        },
+
+        Commands::Import(args) if ProfileArchive::is_archive(&args.file) => {
+            if let Err(e) = import_archive(&vpn_service, args).await {
+                eprintln!("❌ {}", e);
+                std::process::exit(1);
+            }
+        },
+
+        Commands::Import(args) if args.file.extension().is_some_and(|ext| ext == "nmconnection") => {
+            let mode = if args.inline { BundleMode::Inline } else { BundleMode::Copy };
+            let nm = match vpn_service.import_nm_connection(&args.file, args.id.as_deref(), mode, args.replace).await {
+                Ok(nm) => nm,
+                Err(e) => {
+                    eprintln!("❌ Error importing NetworkManager connection: {}", e);
                     std::process::exit(1);
                 }
+            };
+            let vpn = match args.name {
+                Some(name) => vpn_service.set_display_name(nm.imported.vpn.id(), Some(name)).await?,
+                None => nm.imported.vpn.clone(),
+            };
+            println!("✅ Imported '{}' as {}", vpn.display_name(), vpn.id());
+            if let Some(username) = &nm.username {
+                println!("👤 Username was '{}'; OpenVPN will ask for it when connecting", username);
+            }
+            for reference in &nm.imported.unresolved {
+                println!("⚠️  Could not read '{} {}'; it still points at the original path",
+                    reference.directive, reference.path);
+            }
+            if !nm.skipped.is_empty() {
+                println!("⚠️  Not converted: {}", nm.skipped.join(", "));
+            }
+        },
+
+        Commands::Import(args) => {
+            let report = vpn_service.validate_file(&args.file).await;
+            let errors = report.count(Severity::Error);
+            for finding in &report.findings {
+                println!("   {}", report.format_finding(finding));
+            }
+            if errors > 0 && !args.force {
+                eprintln!("❌ {} has {} error(s); fix them or pass --force", args.file.display(), errors);
+                std::process::exit(1);
+            }
+
+            let id = args.id.as_deref();
+            let mode = if args.inline { BundleMode::Inline } else { BundleMode::Copy };
+            let imported = match vpn_service.import_profile(&args.file, id, mode, args.replace).await {
+                Ok(imported) => imported,
+                Err(e @ VpnServiceError::AlreadyExists(_)) => {
+                    eprintln!("❌ {} (use --replace to overwrite)", e);
+                    std::process::exit(1);
+                },
+                Err(e) => {
+                    eprintln!("❌ Error importing profile: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            let vpn = match args.name {
+                Some(name) => vpn_service.set_display_name(imported.vpn.id(), Some(name)).await?,
+                None => imported.vpn,
+            };
+            println!("✅ Imported '{}' as {}", vpn.display_name(), vpn.id());
+            if !imported.bundled.is_empty() {
+                let how = if args.inline { "Inlined" } else { "Copied" };
+                println!("📎 {} {}", how, imported.bundled.join(", "));
+            }
+            for reference in &imported.unresolved {
+                println!("⚠️  Could not bundle '{} {}' (line {}); it still points at the original path",
+                    reference.directive, reference.path, reference.line);
+            }
+        },
+
+        Commands::Remove(args) => {
+            if !args.yes && !confirm(&format!("Remove VPN '{}'?", args.vpn_id))? {
+                println!("Aborted");
+                return Ok(());
+            }
+            match vpn_service.remove_profile(&args.vpn_id).await {
+                Ok(vpn) => println!("🗑️  Removed {}", vpn.display_name()),
+                Err(e) => {
+                    eprintln!("❌ Error removing profile: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        },
+
+        Commands::Rename(args) => {
+            match vpn_service.rename_profile(&args.vpn_id, &args.new_id).await {
+                Ok(vpn) => println!("✅ Renamed {} to {}", args.vpn_id, vpn.id()),
+                Err(e) => {
+                    eprintln!("❌ Error renaming profile: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        },
+
+        Commands::SetName(args) => {
+            match vpn_service.set_display_name(&args.vpn_id, args.name).await {
+                Ok(vpn) => println!("✅ {} is now shown as '{}'", vpn.id(), vpn.display_name()),
+                Err(e) => {
+                    eprintln!("❌ Error setting name: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        },
+
+        Commands::Export(args) => {
+            if let Err(e) = export_profiles(&vpn_service, args).await {
+                eprintln!("❌ Error exporting profile: {}", e);
+                std::process::exit(1);
+            }
+        },
+
+        Commands::Forget(args) => {
+            match vpn_service.forget_credentials(&args.vpn_id).await {
+                Ok(true) => println!("🗑️  Saved secrets for {} deleted", args.vpn_id),
+                Ok(false) => println!("ℹ️  Nothing saved for {}", args.vpn_id),
+                Err(e) => {
+                    eprintln!("❌ Error deleting credentials: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        },
+
+        Commands::Totp(args) => {
+            if args.remove {
+                match vpn_service.remove_totp_seed(&args.vpn_id).await {
+                    Ok(true) => println!("🗑️  TOTP seed for {} deleted", args.vpn_id),
+                    Ok(false) => println!("ℹ️  No TOTP seed saved for {}", args.vpn_id),
+                    Err(e) => {
+                        eprintln!("❌ Error deleting TOTP seed: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+                return Ok(());
+            }
+            let seed = match terminal::read_hidden("TOTP seed or otpauth:// URI: ") {
+                Ok(seed) if !seed.trim().is_empty() => seed,
+                Ok(_) => {
+                    eprintln!("❌ No seed given");
+                    std::process::exit(1);
+                }
+                Err(e) => {
+                    eprintln!("❌ Error reading seed: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            match vpn_service.set_totp_seed(&args.vpn_id, &seed).await {
+                Ok(code) => {
+                    println!("✅ TOTP seed saved for {}", args.vpn_id);
+                    println!("   Current code: {} (it should match your authenticator app)", code);
+                }
+                Err(e) => {
+                    eprintln!("❌ Error saving TOTP seed: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        },
+
+        Commands::Logs(args) => {
+            if let Err(e) = show_logs(&vpn_service, args).await {
+                eprintln!("❌ Error reading logs: {}", e);
+                std::process::exit(1);
+            }
+        },
+
+        Commands::Edit(args) => {
+            if let Err(e) = edit_profile(&vpn_service, &args.vpn_id).await {
+                eprintln!("❌ Error editing profile: {}", e);
+                std::process::exit(1);
             }
         },
+
+        // Dispatched in main before any client is set up.
+        Commands::Daemon => unreachable!("the daemon runs from main"),
     }
     
     Ok(())
 }
 
+async fn import_archive(vpn_service: &VpnApplicationService, args: ImportArgs) -> Result<(), Box<dyn std::error::Error>> {
+    if args.id.is_some() || args.name.is_some() {
+        return Err("--id and --name only apply to single profiles; use --prefix for archives".into());
+    }
+
+    let mut profiles = vpn_service.archive_profiles(&args.file).await?;
+    if profiles.is_empty() {
+        return Err(format!("no OpenVPN profiles found in {}", args.file.display()).into());
+    }
+    if let Some(prefix) = &args.prefix {
+        for profile in &mut profiles {
+            profile.id = format!("{}{}", prefix, profile.id);
+        }
+    }
+
+    println!("📦 {} profile(s) in {}", profiles.len(), args.file.display());
+    for (index, profile) in profiles.iter().enumerate() {
+        let marker = if profile.errors.is_empty() { "" } else { " ❌" };
+        println!("  {:>3}. {:<30} {} [{}]{}", index + 1, profile.id, profile.display_name, profile.entry, marker);
+    }
+    if args.list {
+        return Ok(());
+    }
+
+    let selected: Vec<usize> = if args.all {
+        (0..profiles.len()).collect()
+    } else if !args.select.is_empty() {
+        let selected: Vec<usize> = (0..profiles.len())
+            .filter(|&i| args.select.iter().any(|p| matches_pattern(p, &profiles[i].id) || matches_pattern(p, &profiles[i].entry)))
+            .collect();
+        if selected.is_empty() {
+            return Err(format!("no profile matches {}", args.select.join(", ")).into());
+        }
+        selected
+    } else if std::io::IsTerminal::is_terminal(&std::io::stdin()) {
+        print!("Import which profiles? (e.g. 1,3-5 or 'all') ");
+        std::io::Write::flush(&mut std::io::stdout())?;
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        parse_selection(answer.trim(), profiles.len()).ok_or("invalid selection")?
+    } else {
+        return Err("choose profiles with --all or --select".into());
+    };
+
+    let mode = if args.inline { BundleMode::Inline } else { BundleMode::Copy };
+    let mut failures = 0;
+    for index in selected {
+        let profile = &profiles[index];
+        if let Some(error) = profile.errors.first().filter(|_| !args.force) {
+            println!("⏭️  {} skipped: {} (use --force to import anyway)", profile.id, error.message);
+            failures += 1;
+            continue;
+        }
+        match vpn_service.import_archive_profile(profile, mode, args.replace).await {
+            Ok(imported) => {
+                println!("✅ {} → {}", imported.vpn.id(), imported.vpn.display_name());
+                for reference in &imported.unresolved {
+                    println!("   ⚠️  '{} {}' not found in the archive", reference.directive, reference.path);
+                }
+            },
+            Err(e) => {
+                println!("❌ {}: {}", profile.id, e);
+                failures += 1;
+            }
+        }
+    }
+
+    if failures > 0 {
+        return Err(format!("{} profile(s) were not imported", failures).into());
+    }
+    Ok(())
+}
+
+/// Progress goes to stderr: stdout may be the exported profile itself.
+async fn export_profiles(vpn_service: &VpnApplicationService, args: ExportArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let vpn_ids = if args.all {
+        vpn_service.list_vpns().await?.iter().map(|vpn| vpn.id().to_string()).collect()
+    } else {
+        args.vpn_ids
+    };
+    let archive = args.file.as_deref().filter(|path| ProfileArchive::is_archive(path));
+
+    if let Some(path) = archive {
+        if args.format != ExportFormat::Ovpn {
+            return Err("archives can only hold .ovpn profiles".into());
+        }
+        if vpn_ids.is_empty() {
+            return Err("there are no profiles to export".into());
+        }
+        let exports = vpn_service.export_archive(&vpn_ids, path, args.strip_secrets).await?;
+        for export in &exports {
+            eprintln!("📦 {}.ovpn", export.vpn_id);
+            print_export_warnings(export, "   ");
+        }
+        eprintln!("✅ {} profile(s) written to {}", exports.len(), path.display());
+        return Ok(());
+    }
+
+    let [vpn_id] = vpn_ids.as_slice() else {
+        return Err("several profiles need an archive output, e.g. -o profiles.zip".into());
+    };
+    match args.format {
+        ExportFormat::Ovpn => {
+            let export = vpn_service.export_profile(vpn_id, args.strip_secrets).await?;
+            match &args.file {
+                Some(path) => {
+                    write_private(path, export.content.as_bytes())?;
+                    eprintln!("✅ {} written to {}", vpn_id, path.display());
+                },
+                None => print!("{}", export.content),
+            }
+            print_export_warnings(&export, "");
+        },
+        ExportFormat::NetworkManager => {
+            if args.strip_secrets {
+                return Err("--strip-secrets only applies to .ovpn exports; keyfiles never carry passwords".into());
+            }
+            let files_dir = args.file.as_deref().map(|path| path.parent().unwrap_or(Path::new(".")));
+            let export = vpn_service.export_nm_connection(vpn_id, files_dir).await?;
+            match &args.file {
+                Some(path) => {
+                    write_private(path, export.keyfile.as_bytes())?;
+                    eprintln!("✅ Keyfile written to {}", path.display());
+                    eprintln!("   Install it with: sudo install -m 600 {} /etc/NetworkManager/system-connections/ && sudo nmcli connection reload", path.display());
+                },
+                None => print!("{}", export.keyfile),
+            }
+            for path in &export.written {
+                eprintln!("📎 Wrote {}", path.display());
+            }
+            if !export.skipped.is_empty() {
+                eprintln!("⚠️  Not converted: {}", export.skipped.join(", "));
+            }
+        },
+    }
+    Ok(())
+}
+
+fn print_export_warnings(export: &ProfileExport, indent: &str) {
+    if !export.stripped.is_empty() {
+        eprintln!("{}🔒 Left out: {}", indent, export.stripped.join(", "));
+    }
+    for reference in &export.unresolved {
+        eprintln!("{}⚠️  '{} {}' could not be read and still points at this machine", indent, reference.directive, reference.path);
+    }
+}
+
+/// Glob-style match where `*` stands for any run of characters.
+fn matches_pattern(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = text.strip_prefix(first) else { return false };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else { return rest.is_empty() };
+    for part in middle {
+        match rest.find(part) {
+            Some(position) => rest = &rest[position + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+/// Parses `1,3-5` (1-based) or `all` into indexes below `count`.
+fn parse_selection(input: &str, count: usize) -> Option<Vec<usize>> {
+    if input == "all" {
+        return Some((0..count).collect());
+    }
+    let mut selected = Vec::new();
+    for part in input.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+        let (start, end) = match part.split_once('-') {
+            Some((start, end)) => (start.trim().parse::<usize>().ok()?, end.trim().parse::<usize>().ok()?),
+            None => {
+                let number = part.parse::<usize>().ok()?;
+                (number, number)
+            }
+        };
+        if start == 0 || end > count || start > end {
+            return None;
+        }
+        selected.extend(start - 1..end);
+    }
+    selected.sort_unstable();
+    selected.dedup();
+    (!selected.is_empty()).then_some(selected)
+}
+
+fn confirm(question: &str) -> std::io::Result<bool> {
+    print!("{} [y/N] ", question);
+    std::io::Write::flush(&mut std::io::stdout())?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+async fn show_logs(vpn_service: &VpnApplicationService, args: LogsArgs) -> Result<(), VpnServiceError> {
+    // Opened first so nothing written meanwhile is lost.
+    let tail = match args.follow {
+        true => Some(vpn_service.follow_logs(&args.vpn_id).await?),
+        false => None,
+    };
+    let lines = vpn_service.logs(&args.vpn_id, args.since).await?;
+    for line in &lines {
+        println!("{}", line);
+    }
+    let Some(mut tail) = tail else {
+        if lines.is_empty() {
+            println!("ℹ️  No OpenVPN output recorded for {}", args.vpn_id);
+        }
+        return Ok(());
+    };
+
+    loop {
+        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+        let lines = tail.read_new().map_err(|e| VpnServiceError::RepositoryError(e.to_string()))?;
+        for line in lines {
+            println!("{}", line);
+        }
+    }
+}
+
+/// Edits a copy of the profile so a half-written or invalid file never
+/// replaces the one OpenVPN reads.
+async fn edit_profile(vpn_service: &VpnApplicationService, vpn_id: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let config_path = vpn_service.config_path_of(vpn_id).await?;
+    let original = std::fs::read_to_string(&config_path)?;
+
+    let draft = std::env::temp_dir().join(format!("ui-openvpn-{}-{}.ovpn", vpn_id, std::process::id()));
+    write_private(&draft, original.as_bytes())?;
+
+    let result = edit_draft(vpn_service, vpn_id, &draft, &original).await;
+    let _ = std::fs::remove_file(&draft);
+    result
+}
+
+async fn edit_draft(
+    vpn_service: &VpnApplicationService,
+    vpn_id: &str,
+    draft: &Path,
+    original: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+
+    loop {
+        // Run through the shell so EDITOR="code --wait" works.
+        let status = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(format!("{} \"$1\"", editor))
+            .arg("sh")
+            .arg(draft)
+            .status()?;
+        if !status.success() {
+            return Err(format!("editor '{}' exited with {}", editor, status).into());
+        }
+
+        let content = std::fs::read_to_string(draft)?;
+        if content == original {
+            println!("No changes");
+            return Ok(());
+        }
+
+        let report = vpn_service.validate_profile_source(vpn_id, &content).await?;
+        let errors = report.count(Severity::Error);
+        if errors > 0 {
+            println!("❌ The edited profile has {} error(s):", errors);
+            for finding in &report.findings {
+                println!("   {}", report.format_finding(finding));
+            }
+            print!("(e)dit again, (s)ave anyway or (a)bort? [e] ");
+            std::io::Write::flush(&mut std::io::stdout())?;
+            let mut answer = String::new();
+            std::io::stdin().read_line(&mut answer)?;
+            match answer.trim() {
+                "s" | "S" => {},
+                "a" | "A" => {
+                    println!("Aborted, {} left unchanged", vpn_id);
+                    return Ok(());
+                },
+                _ => continue,
+            }
+        }
+
+        vpn_service.update_profile(vpn_id, &content).await?;
+        println!("✅ Saved {}", vpn_id);
+        let vpns = vpn_service.list_vpns().await?;
+        if vpns.iter().any(|vpn| vpn.id() == vpn_id && vpn.is_connected()) {
+            println!("ℹ️  Reconnect to apply the changes");
+        }
+        return Ok(());
+    }
+}
+
+/// Serves clients until SIGTERM or Ctrl+C, then takes the tunnel down with it.
+async fn run_daemon(
+    vpn_service: Arc<VpnApplicationService>,
+    prompt: Arc<ClientPrompt>,
+    socket: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let server = match DaemonServer::bind(socket, Arc::clone(&vpn_service), prompt).await {
+        Ok(server) => server,
+        Err(e) => {
+            eprintln!("❌ Cannot start the daemon: {}", e);
+            std::process::exit(1);
+        }
+    };
+    println!("🛰️  Daemon listening on {}", server.path().display());
+
+    let supervisor = Arc::clone(&vpn_service);
+    tokio::spawn(async move { supervisor.supervise().await });
+
+    let mut terminate = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())?;
+    tokio::select! {
+        result = server.serve() => result?,
+        _ = terminate.recv() => {}
+        _ = tokio::signal::ctrl_c() => {}
+    }
+
+    println!("🛑 Stopping the daemon");
+    drop(server);
+    match vpn_service.disconnect_current().await {
+        Ok(stopped) => print_stopped(&stopped),
+        Err(e) => {
+            eprintln!("❌ Failed to disconnect: {}", e);
+            std::process::exit(1);
+        }
+    }
+    Ok(())
+}
+
 async fn run_interactive_mode(
     vpn_service: Arc<VpnApplicationService>,
+    control: Arc<dyn VpnControl>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     println!("🎮 Interactive Mode");
     println!("Type 'help' for available commands, 'quit' to exit");
     println!("─────────────────────────────────────────────────");
+
+    // Dropped tunnels are brought back while the session lasts.
+    let supervisor = Arc::clone(&vpn_service);
+    tokio::spawn(async move { supervisor.supervise().await });
     
     loop {
         print!("vpn> ");
@@ -331,30 +1528,35 @@ async fn run_interactive_mode(
                 match command {
                     "help" | "h" => {
                         println!("Available commands:");
-                        println!("  list, ls     - List available VPNs");
-                        println!("  connect <id> - Connect to VPN");
-                        println!("  disconnect   - Disconnect current VPN");
-                        println!("  status       - Show connection status");
-                        println!("  quit, exit   - Exit interactive mode");
+                        println!("  list, ls        - List available VPNs");
+                        println!("  connect <id>    - Connect to VPN");
+                        println!("  disconnect [id] - Disconnect a VPN, or all of them");
+                        println!("  status          - Show connection status");
+                        println!("  quit, exit      - Exit interactive mode");
                     },
                     "quit" | "exit" | "q" => {
                         println!("👋 Goodbye!");
                         break;
                     },
                     "list" | "ls" => {
-                        execute_command(Commands::List, Arc::clone(&vpn_service), false).await?;
+                        execute_command(Commands::List, Arc::clone(&vpn_service), Arc::clone(&control), false, OutputFormat::Table).await?;
                     },
                     "status" | "st" => {
-                        execute_command(Commands::Status, Arc::clone(&vpn_service), false).await?;
+                        execute_command(Commands::Status(StatusArgs::default()), Arc::clone(&vpn_service), Arc::clone(&control), false, OutputFormat::Table).await?;
                     },
                     "disconnect" | "dc" => {
-                        execute_command(Commands::Disconnect, Arc::clone(&vpn_service), false).await?;
+                        execute_command(Commands::Disconnect(DisconnectArgs::default()), Arc::clone(&vpn_service), Arc::clone(&control), false, OutputFormat::Table).await?;
+                    },
+                    cmd if cmd.starts_with("disconnect ") => {
+                        let vpn_id = cmd.strip_prefix("disconnect ").unwrap_or("").trim().to_string();
+                        let args = DisconnectArgs { vpn_id: Some(vpn_id) };
+                        execute_command(Commands::Disconnect(args), Arc::clone(&vpn_service), Arc::clone(&control), false, OutputFormat::Table).await?;
                     },
                     cmd if cmd.starts_with("connect ") => {
                         let vpn_id = cmd.strip_prefix("connect ").unwrap_or("").trim();
                         if !vpn_id.is_empty() {
-                            let args = ConnectArgs { vpn_id: vpn_id.to_string(), force: false };
-                            execute_command(Commands::Connect(args), Arc::clone(&vpn_service), false).await?;
+                            let args = ConnectArgs { vpn_id: vpn_id.to_string(), force: false, keep_alive: false };
+                            execute_command(Commands::Connect(args), Arc::clone(&vpn_service), Arc::clone(&control), false, OutputFormat::Table).await?;
                         } else {
                             println!("❌ Usage: connect <vpn_id>");
                         }
@@ -381,6 +1583,7 @@ async fn run_interactive_mode(
 #[cfg(feature = "gui")]
 async fn run_gui_mode(
     vpn_service: Arc<VpnApplicationService>,
+    control: Arc<dyn VpnControl>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     println!("🎨 Launching GUI mode...");
     
@@ -400,8 +1603,12 @@ async fn run_gui_mode(
         }
     });
 
+    // Dropped tunnels are brought back while the window is open.
+    let supervisor = Arc::clone(&vpn_service);
+    tokio::spawn(async move { supervisor.supervise().await });
+
     app.connect_activate(move |app| {
-        let window = MainWindow::new(app, Arc::clone(&vpn_service));
+        let window = MainWindow::new(app, Arc::clone(&vpn_service), Arc::clone(&control));
         window.show();
     });
 
@@ -412,7 +1619,8 @@ async fn run_gui_mode(
 #[cfg(not(feature = "gui"))]
 async fn run_gui_mode(
     _vpn_service: Arc<VpnApplicationService>,
+    _control: Arc<dyn VpnControl>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     eprintln!("❌ GUI mode not available. Compile with --features gui");
     std::process::exit(1);
-}
\ No newline at end of file
+}
@@ -0,0 +1,91 @@
+// Prompts through an external helper such as ssh-askpass
+use crate::domain::credentials::{CredentialKind, CredentialPrompt, CredentialReply, CredentialRequest, Credentials};
+use async_trait::async_trait;
+use std::io;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// Runs `program <prompt>` for every answer needed and reads it from the
+/// helper's stdout, the `SSH_ASKPASS` convention. A non-zero exit cancels.
+#[derive(Debug, Clone)]
+pub struct AskpassPrompt {
+    program: PathBuf,
+}
+
+impl AskpassPrompt {
+    pub fn new(program: PathBuf) -> Self {
+        Self { program }
+    }
+
+    fn read(&self, prompt: &str) -> io::Result<Option<String>> {
+        let output = Command::new(&self.program)
+            .arg(prompt)
+            .stdin(Stdio::null())
+            .stderr(Stdio::inherit())
+            .output()?;
+        if !output.status.success() {
+            return Ok(None);
+        }
+        let answer = String::from_utf8_lossy(&output.stdout);
+        Ok(Some(answer.trim_end_matches(['\r', '\n']).to_string()))
+    }
+
+    fn ask_blocking(&self, request: &CredentialRequest) -> io::Result<Option<CredentialReply>> {
+        let retry = if request.retry { " (rejected, try again)" } else { "" };
+        let name = &request.vpn_name;
+
+        let (username, password, challenge) = match &request.kind {
+            CredentialKind::UsernamePassword { challenge } => {
+                let username = match &request.username {
+                    Some(username) if !request.retry => username.clone(),
+                    _ => match self.read(&format!("Username for {}{}:", name, retry))? {
+                        Some(username) => username,
+                        None => return Ok(None),
+                    },
+                };
+                let Some(password) = self.read(&format!("Password for {}@{}{}:", username, name, retry))? else {
+                    return Ok(None);
+                };
+                (username, password, challenge.as_ref())
+            }
+            CredentialKind::Password { realm } => {
+                let Some(password) = self.read(&format!("'{}' password for {}{}:", realm, name, retry))? else {
+                    return Ok(None);
+                };
+                (String::new(), password, None)
+            }
+            CredentialKind::ChallengeResponse { challenge } => (String::new(), String::new(), Some(challenge)),
+        };
+
+        let response = match challenge {
+            Some(challenge) => match self.read(&format!("{} ({})", challenge.text, name))? {
+                Some(response) => Some(response),
+                None => return Ok(None),
+            },
+            None => None,
+        };
+
+        // The helper has nowhere to ask "remember?", so nothing is kept.
+        Ok(Some(CredentialReply {
+            credentials: Credentials::new(username, password),
+            response,
+            remember: false,
+        }))
+    }
+}
+
+#[async_trait]
+impl CredentialPrompt for AskpassPrompt {
+    async fn ask(&self, request: &CredentialRequest) -> Option<CredentialReply> {
+        let prompt = self.clone();
+        let request = request.clone();
+        match tokio::task::spawn_blocking(move || prompt.ask_blocking(&request)).await {
+            Ok(Ok(reply)) => reply,
+            Ok(Err(e)) => {
+                eprintln!("Could not run {}: {}", self.program.display(), e);
+                None
+            }
+            Err(_) => None,
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use crate::application::services::VpnApplicationService;
 use crate::application::mappers::VpnMapper;
+use crate::ui::format;
 use std::io::{self, Write};
 use std::sync::Arc;
 
@@ -41,7 +42,7 @@ impl CliInterface {
         println!("=====================================");
         println!("1) Listar VPNs disponibles");
         println!("2) Conectar VPN");
-        println!("3) Desconectar VPN actual");
+        println!("3) Desconectar todas las VPNs");
         println!("4) Ver estado de conexión");
         println!("5) Salir");
         println!();
@@ -107,6 +108,9 @@ impl CliInterface {
                     },
                     Err(e) => {
                         println!("❌ Error al conectar: {}", e);
+                        if let Some(hint) = e.hint() {
+                            println!("💡 {}", hint);
+                        }
                     }
                 }
             } else {
@@ -120,10 +124,13 @@ impl CliInterface {
     }
 
     async fn disconnect_current(&self) -> Result<(), Box<dyn std::error::Error>> {
-        println!("🔌 Desconectando VPN actual...");
+        println!("🔌 Desconectando todas las VPNs...");
         
         match self.service.disconnect_current().await {
-            Ok(()) => {
+            Ok(stopped) => {
+                for instance in &stopped {
+                    println!("   {}", format::format_stopped(instance));
+                }
                 println!("✅ Desconectado exitosamente.");
             },
             Err(e) => {
@@ -144,7 +151,17 @@ impl CliInterface {
             let dto = VpnMapper::to_dto(&vpn);
             let status_text = match dto.status.state {
                 crate::application::dtos::ConnectionStateDto::Connected => {
-                    format!("🟢 Conectado - IP: {}", dto.status.ip_address)
+                    let mut text = format!("🟢 Conectado - IP: {}", dto.status.ip_address);
+                    if let Some(interface) = &dto.status.interface {
+                        text.push_str(&format!(" ({})", interface));
+                    }
+                    if let Some(gateway) = &dto.status.gateway {
+                        text.push_str(&format!(" - Puerta de enlace: {}", gateway));
+                    }
+                    if let (Some(address), Some(port)) = (&dto.status.remote_address, dto.status.remote_port) {
+                        text.push_str(&format!(" - Servidor: {}:{}", address, port));
+                    }
+                    text
                 },
                 crate::application::dtos::ConnectionStateDto::Connecting => "🟡 Conectando...".to_string(),
                 crate::application::dtos::ConnectionStateDto::Disconnecting => "🟡 Desconectando...".to_string(),
@@ -1,6 +1,8 @@
 #[cfg(feature = "ui")]
 mod gtk_components {
     use gtk4::prelude::*;
+    use crate::domain::entities::VpnStatus;
+    use crate::ui::format;
     use gtk4::{Application, ApplicationWindow, Box, Button, Label, ListBox, ListBoxRow, Orientation};
 
     pub struct VpnListWidget {
@@ -73,6 +75,7 @@ mod gtk_components {
         container: Box,
         status_label: Label,
         ip_label: Label,
+        details_label: Label,
     }
 
     impl StatusWidget {
@@ -90,13 +93,19 @@ mod gtk_components {
             ip_label.set_halign(gtk4::Align::Start);
             ip_label.add_css_class("dim-label");
 
+            let details_label = Label::new(None);
+            details_label.set_halign(gtk4::Align::Start);
+            details_label.add_css_class("dim-label");
+
             container.append(&status_label);
             container.append(&ip_label);
+            container.append(&details_label);
 
             Self {
                 container,
                 status_label,
                 ip_label,
+                details_label,
             }
         }
 
@@ -104,7 +113,7 @@ mod gtk_components {
             &self.container
         }
 
-        pub fn update_status(&self, is_connected: bool, vpn_name: Option<&str>, ip: Option<&str>) {
+        pub fn update_status(&self, is_connected: bool, vpn_name: Option<&str>, status: Option<&VpnStatus>) {
             let status_text = if is_connected {
                 if let Some(name) = vpn_name {
                     format!("🟢 Conectado a {}", name)
@@ -115,14 +124,39 @@ mod gtk_components {
                 "🔴 Desconectado".to_string()
             };
 
-            let ip_text = if let Some(ip_addr) = ip {
-                format!("IP: {}", ip_addr)
-            } else {
-                "IP: No disponible".to_string()
+            let ip_text = match status.map(VpnStatus::ip_address).filter(|ip| !ip.is_empty()) {
+                Some(ip_addr) => match status.and_then(VpnStatus::ipv6_address) {
+                    Some(ipv6) => format!("IP: {} / {}", ip_addr, ipv6),
+                    None => format!("IP: {}", ip_addr),
+                },
+                None => "IP: No disponible".to_string(),
             };
 
+            let mut details = Vec::new();
+            if let Some(status) = status {
+                if let Some(interface) = status.interface() {
+                    details.push(format!("Interfaz: {}", interface));
+                }
+                if let Some(gateway) = status.gateway() {
+                    details.push(format!("Puerta de enlace: {}", gateway));
+                }
+                if let Some(address) = status.remote_address() {
+                    match status.remote_port() {
+                        Some(port) => details.push(format!("Servidor: {}:{}", address, port)),
+                        None => details.push(format!("Servidor: {}", address)),
+                    }
+                }
+                if let Some(duration) = status.session_duration() {
+                    details.push(format!("Tiempo conectado: {}", format::format_duration(duration)));
+                }
+                if let Some(traffic) = status.traffic() {
+                    details.push(format!("Tráfico: {}", format::format_traffic(traffic)));
+                }
+            }
+
             self.status_label.set_text(&status_text);
             self.ip_label.set_text(&ip_text);
+            self.details_label.set_text(&details.join("\n"));
         }
     }
 }
\ No newline at end of file
@@ -83,7 +83,7 @@ mod gtk_controllers {
                 self.status_widget.update_status(
                     true,
                     Some(vpn.display_name()),
-                    Some(vpn.status().ip_address()),
+                    Some(vpn.status()),
                 );
             } else {
                 self.status_widget.update_status(false, None, None);
@@ -0,0 +1,492 @@
+// Long-running daemon owning the tunnel, and the client other commands use to reach it
+use crate::application::dtos::{
+    ConnectVpnRequest, CredentialReplyDto, CredentialRequestDto, DisconnectVpnRequest, ErrorDto, VpnConnectionResponse,
+    VpnListResponse,
+};
+use crate::application::mappers::{
+    CredentialMapper, ErrorMapper, FailureMapper, ResponseMapper, ShutdownMapper, VpnMapper,
+};
+use crate::application::services::{VpnApplicationService, VpnControl, VpnServiceError};
+use crate::domain::credentials::{CredentialPrompt, CredentialReply, CredentialRequest};
+use crate::domain::entities::Vpn;
+use crate::infrastructure::services::{runtime_dir, StoppedInstance};
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{mpsc, oneshot};
+
+/// Method the daemon calls back on a client that must answer a password prompt.
+const ASK_CREDENTIALS: &str = "ask_credentials";
+
+// JSON-RPC 2.0 error codes.
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// The request was understood but the service failed.
+const SERVICE_ERROR: i64 = -32000;
+
+/// Pause before a client asks again after the daemon went away.
+const RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// `daemon.rpc.sock` in the private socket directory. The dot keeps it apart
+/// from the per-profile management sockets next to it.
+pub fn default_socket_path() -> PathBuf {
+    runtime_dir().join("daemon.rpc.sock")
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RpcRequest {
+    jsonrpc: String,
+    id: Value,
+    method: String,
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    params: Value,
+}
+
+impl RpcRequest {
+    fn new(id: u64, method: &str, params: Value) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id: id.into(),
+            method: method.to_string(),
+            params,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+    /// For service errors, what kind it was, so clients rebuild the same error.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    data: Option<ErrorDto>,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self { code, message: message.into(), data: None }
+    }
+
+    fn service(error: VpnServiceError) -> Self {
+        Self { code: SERVICE_ERROR, message: error.to_string(), data: ErrorMapper::to_dto(&error) }
+    }
+
+    /// The error the daemon's service returned, or the message as is.
+    fn into_service_error(self) -> VpnServiceError {
+        self.data
+            .as_ref()
+            .and_then(ErrorMapper::from_dto)
+            .unwrap_or(VpnServiceError::Daemon(self.message))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RpcResponse {
+    jsonrpc: String,
+    id: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<RpcError>,
+}
+
+impl RpcResponse {
+    fn result(id: Value, result: Value) -> Self {
+        Self { jsonrpc: "2.0".to_string(), id, result: Some(result), error: None }
+    }
+
+    fn error(id: Value, error: RpcError) -> Self {
+        Self { jsonrpc: "2.0".to_string(), id, result: None, error: Some(error) }
+    }
+}
+
+/// Both ends may send requests: the daemon calls back with password prompts
+/// while a `connect` is running.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RpcMessage {
+    Request(RpcRequest),
+    Response(RpcResponse),
+}
+
+/// One socket carrying newline-delimited JSON-RPC messages.
+struct Connection {
+    lines: Lines<BufReader<OwnedReadHalf>>,
+    writer: OwnedWriteHalf,
+}
+
+impl Connection {
+    fn new(stream: UnixStream) -> Self {
+        let (reader, writer) = stream.into_split();
+        Self { lines: BufReader::new(reader).lines(), writer }
+    }
+
+    async fn send(&mut self, message: &impl Serialize) -> io::Result<()> {
+        let mut line = serde_json::to_string(message)?;
+        line.push('\n');
+        self.writer.write_all(line.as_bytes()).await
+    }
+
+    /// `None` once the peer hung up.
+    async fn receive(&mut self) -> io::Result<Option<String>> {
+        while let Some(line) = self.lines.next_line().await? {
+            if !line.trim().is_empty() {
+                return Ok(Some(line));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Passes a password prompt to the client; `None` when it cancelled or
+    /// went away.
+    async fn ask(&mut self, id: u64, request: &CredentialRequest) -> Option<CredentialReply> {
+        let params = serde_json::to_value(CredentialMapper::request_to_dto(request)).ok()?;
+        self.send(&RpcRequest::new(id, ASK_CREDENTIALS, params)).await.ok()?;
+        loop {
+            let line = self.receive().await.ok()??;
+            let Ok(response) = serde_json::from_str::<RpcResponse>(&line) else { continue };
+            if response.id != id {
+                continue;
+            }
+            let reply: Option<CredentialReplyDto> = serde_json::from_value(response.result?).ok()?;
+            return reply.as_ref().map(CredentialMapper::reply_from_dto);
+        }
+    }
+}
+
+type Question = (CredentialRequest, oneshot::Sender<Option<CredentialReply>>);
+
+/// Hands password prompts to the client whose `connect` is running. Tunnels
+/// the supervisor brings back have nobody to ask, so those prompts go to
+/// `fallback`, if any.
+pub struct ClientPrompt {
+    client: Mutex<Option<mpsc::Sender<Question>>>,
+    fallback: Option<Arc<dyn CredentialPrompt>>,
+}
+
+impl ClientPrompt {
+    pub fn new(fallback: Option<Arc<dyn CredentialPrompt>>) -> Self {
+        Self { client: Mutex::new(None), fallback }
+    }
+
+    fn attach(&self, client: mpsc::Sender<Question>) -> AttachedClient<'_> {
+        *self.client.lock().unwrap() = Some(client);
+        AttachedClient(self)
+    }
+}
+
+/// Detaches the client when its `connect` ends, however it ends.
+struct AttachedClient<'a>(&'a ClientPrompt);
+
+impl Drop for AttachedClient<'_> {
+    fn drop(&mut self) {
+        *self.0.client.lock().unwrap() = None;
+    }
+}
+
+#[async_trait]
+impl CredentialPrompt for ClientPrompt {
+    async fn ask(&self, request: &CredentialRequest) -> Option<CredentialReply> {
+        let client = self.client.lock().unwrap().clone();
+        match (client, &self.fallback) {
+            (Some(client), _) => {
+                let (reply_tx, reply_rx) = oneshot::channel();
+                client.send((request.clone(), reply_tx)).await.ok()?;
+                reply_rx.await.ok().flatten()
+            }
+            (None, Some(fallback)) => fallback.ask(request).await,
+            (None, None) => None,
+        }
+    }
+}
+
+/// Serves the connection commands of one [`VpnApplicationService`] over a
+/// Unix socket, one JSON-RPC 2.0 message per line. The socket is removed
+/// when the server is dropped.
+pub struct DaemonServer {
+    listener: UnixListener,
+    path: PathBuf,
+    handler: Arc<Handler>,
+}
+
+struct Handler {
+    service: Arc<VpnApplicationService>,
+    prompt: Arc<ClientPrompt>,
+    /// Prompts can only be routed to one client at a time.
+    connecting: tokio::sync::Mutex<()>,
+}
+
+impl DaemonServer {
+    /// Listens on `path`, owner-only. A socket left behind by a daemon that
+    /// died is replaced; one that still answers is an error.
+    pub async fn bind(
+        path: &Path,
+        service: Arc<VpnApplicationService>,
+        prompt: Arc<ClientPrompt>,
+    ) -> io::Result<Self> {
+        use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+        if UnixStream::connect(path).await.is_ok() {
+            return Err(io::Error::new(
+                io::ErrorKind::AddrInUse,
+                format!("a daemon is already listening on {}", path.display()),
+            ));
+        }
+        if let Some(dir) = path.parent().filter(|dir| !dir.exists()) {
+            std::fs::DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+        }
+        match std::fs::remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+        let listener = UnixListener::bind(path)?;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+
+        Ok(Self {
+            listener,
+            path: path.to_path_buf(),
+            handler: Arc::new(Handler {
+                service,
+                prompt,
+                connecting: tokio::sync::Mutex::new(()),
+            }),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Accepts clients until the listener fails; each one gets its own task.
+    pub async fn serve(&self) -> io::Result<()> {
+        loop {
+            let (stream, _) = self.listener.accept().await?;
+            let handler = Arc::clone(&self.handler);
+            tokio::spawn(async move {
+                if let Err(e) = handler.serve_client(stream).await {
+                    eprintln!("⚠️  Client connection failed: {}", e);
+                }
+            });
+        }
+    }
+}
+
+impl Drop for DaemonServer {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+impl Handler {
+    async fn serve_client(&self, stream: UnixStream) -> io::Result<()> {
+        let mut connection = Connection::new(stream);
+        while let Some(line) = connection.receive().await? {
+            let response = match serde_json::from_str::<RpcRequest>(&line) {
+                Ok(request) => match self.dispatch(&request, &mut connection).await {
+                    Ok(result) => RpcResponse::result(request.id, result),
+                    Err(error) => RpcResponse::error(request.id, error),
+                },
+                Err(e) => RpcResponse::error(Value::Null, RpcError::new(PARSE_ERROR, e.to_string())),
+            };
+            connection.send(&response).await?;
+        }
+        Ok(())
+    }
+
+    async fn dispatch(&self, request: &RpcRequest, connection: &mut Connection) -> Result<Value, RpcError> {
+        match request.method.as_str() {
+            "list" => {
+                let vpns = self.service.list_vpns().await.map_err(RpcError::service)?;
+                to_result(VpnListResponse { vpns: VpnMapper::to_dto_list(&vpns) })
+            }
+            "connect" => {
+                let params: ConnectVpnRequest = params_of(request)?;
+                let outcome = self.connect(&params.vpn_id, connection).await.map(|()| Vec::new());
+                self.command_response(&params.vpn_id, outcome, "Connected to").await
+            }
+            "disconnect" => {
+                let params: DisconnectVpnRequest = params_of(request)?;
+                let outcome = self.service.disconnect_vpn(&params.vpn_id).await;
+                self.command_response(&params.vpn_id, outcome, "Disconnected from").await
+            }
+            "disconnect_current" => {
+                let outcome = self.service.disconnect_current().await;
+                to_result(ResponseMapper::connection_response(&outcome, "Disconnected"))
+            }
+            "force_kill_all" => {
+                let outcome = self.service.force_kill_all_vpns().await;
+                to_result(ResponseMapper::connection_response(&outcome, "All OpenVPN processes stopped"))
+            }
+            "wait_for_change" => {
+                self.service.tunnel_state_changed().await;
+                Ok(Value::Null)
+            }
+            method => Err(RpcError::new(METHOD_NOT_FOUND, format!("unknown method '{}'", method))),
+        }
+    }
+
+    /// Connects while routing password prompts back to the requesting client.
+    async fn connect(&self, vpn_id: &str, connection: &mut Connection) -> Result<(), VpnServiceError> {
+        let _turn = self.connecting.lock().await;
+        let (questions_tx, mut questions) = mpsc::channel::<Question>(1);
+        let _attached = self.prompt.attach(questions_tx);
+
+        let connect = self.service.connect_vpn(vpn_id);
+        tokio::pin!(connect);
+        let mut next_id = 0;
+        loop {
+            tokio::select! {
+                outcome = &mut connect => return outcome,
+                Some((request, reply)) = questions.recv() => {
+                    next_id += 1;
+                    let _ = reply.send(connection.ask(next_id, &request).await);
+                }
+            }
+        }
+    }
+
+    async fn command_response(
+        &self,
+        vpn_id: &str,
+        outcome: Result<Vec<StoppedInstance>, VpnServiceError>,
+        done: &str,
+    ) -> Result<Value, RpcError> {
+        let mut response = ResponseMapper::connection_response(&outcome, &format!("{} {}", done, vpn_id));
+        if response.success {
+            if let Ok(vpns) = self.service.list_vpns().await {
+                response.vpn = vpns.iter().find(|vpn| vpn.id() == vpn_id).map(VpnMapper::to_dto);
+            }
+        }
+        to_result(response)
+    }
+}
+
+fn params_of<T: DeserializeOwned>(request: &RpcRequest) -> Result<T, RpcError> {
+    serde_json::from_value(request.params.clone())
+        .map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+}
+
+fn to_result(value: impl Serialize) -> Result<Value, RpcError> {
+    serde_json::to_value(value).map_err(|e| RpcError::new(SERVICE_ERROR, e.to_string()))
+}
+
+/// Runs connection commands in the daemon. Every call opens its own
+/// connection, so a pending `wait_for_change` never holds up the others.
+pub struct DaemonClient {
+    socket: PathBuf,
+    prompt: Option<Arc<dyn CredentialPrompt>>,
+}
+
+impl DaemonClient {
+    pub fn new(socket: PathBuf) -> Self {
+        Self { socket, prompt: None }
+    }
+
+    /// `None` when nothing answers on `socket`.
+    pub async fn if_running(socket: PathBuf) -> Option<Self> {
+        UnixStream::connect(&socket).await.ok()?;
+        Some(Self::new(socket))
+    }
+
+    /// Answers the daemon's password prompts for connects made through this
+    /// client. Without one they are cancelled.
+    pub fn with_credential_prompt(mut self, prompt: Arc<dyn CredentialPrompt>) -> Self {
+        self.prompt = Some(prompt);
+        self
+    }
+
+    pub fn socket(&self) -> &Path {
+        &self.socket
+    }
+
+    async fn call<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T, VpnServiceError> {
+        let unreachable = |e: io::Error| VpnServiceError::DaemonUnreachable(e.to_string());
+        let invalid = |e: serde_json::Error| VpnServiceError::Daemon(format!("invalid reply from the daemon: {}", e));
+
+        let stream = UnixStream::connect(&self.socket).await.map_err(unreachable)?;
+        let mut connection = Connection::new(stream);
+        connection.send(&RpcRequest::new(1, method, params)).await.map_err(unreachable)?;
+        loop {
+            let line = connection
+                .receive()
+                .await
+                .map_err(unreachable)?
+                .ok_or_else(|| VpnServiceError::DaemonUnreachable("the daemon closed the connection".to_string()))?;
+            match serde_json::from_str::<RpcMessage>(&line).map_err(invalid)? {
+                RpcMessage::Request(request) if request.method == ASK_CREDENTIALS => {
+                    let reply = self.answer(request.params).await;
+                    let result = serde_json::to_value(reply).map_err(invalid)?;
+                    connection.send(&RpcResponse::result(request.id, result)).await.map_err(unreachable)?;
+                }
+                RpcMessage::Request(request) => {
+                    let error = RpcError::new(METHOD_NOT_FOUND, format!("unknown method '{}'", request.method));
+                    connection.send(&RpcResponse::error(request.id, error)).await.map_err(unreachable)?;
+                }
+                RpcMessage::Response(RpcResponse { error: Some(error), .. }) => {
+                    return Err(error.into_service_error());
+                }
+                RpcMessage::Response(response) => {
+                    return serde_json::from_value(response.result.unwrap_or(Value::Null)).map_err(invalid);
+                }
+            }
+        }
+    }
+
+    async fn answer(&self, params: Value) -> Option<CredentialReplyDto> {
+        let request: CredentialRequestDto = serde_json::from_value(params).ok()?;
+        let reply = self.prompt.as_ref()?.ask(&CredentialMapper::request_from_dto(&request)).await?;
+        Some(CredentialMapper::reply_to_dto(&reply))
+    }
+
+    async fn command(&self, method: &str, params: Value) -> Result<Vec<StoppedInstance>, VpnServiceError> {
+        let response: VpnConnectionResponse = self.call(method, params).await?;
+        if response.success {
+            Ok(ShutdownMapper::from_dto_list(&response.stopped))
+        } else {
+            let diagnosed = response.failure.as_ref().and_then(FailureMapper::from_dto).map(VpnServiceError::Diagnosed);
+            let error = diagnosed.or_else(|| response.error.as_ref().and_then(ErrorMapper::from_dto));
+            Err(error.unwrap_or(VpnServiceError::Daemon(response.message)))
+        }
+    }
+}
+
+#[async_trait]
+impl VpnControl for DaemonClient {
+    async fn list_vpns(&self) -> Result<Vec<Vpn>, VpnServiceError> {
+        let response: VpnListResponse = self.call("list", Value::Null).await?;
+        Ok(response.vpns.iter().map(VpnMapper::from_dto).collect())
+    }
+
+    async fn connect_vpn(&self, vpn_id: &str) -> Result<(), VpnServiceError> {
+        let params = serde_json::json!(ConnectVpnRequest { vpn_id: vpn_id.to_string() });
+        self.command("connect", params).await.map(|_| ())
+    }
+
+    async fn disconnect_vpn(&self, vpn_id: &str) -> Result<Vec<StoppedInstance>, VpnServiceError> {
+        let params = serde_json::json!(DisconnectVpnRequest { vpn_id: vpn_id.to_string() });
+        self.command("disconnect", params).await
+    }
+
+    async fn disconnect_current(&self) -> Result<Vec<StoppedInstance>, VpnServiceError> {
+        self.command("disconnect_current", Value::Null).await
+    }
+
+    async fn force_kill_all_vpns(&self) -> Result<Vec<StoppedInstance>, VpnServiceError> {
+        self.command("force_kill_all", Value::Null).await
+    }
+
+    async fn tunnel_state_changed(&self) {
+        if self.call::<Value>("wait_for_change", Value::Null).await.is_err() {
+            tokio::time::sleep(RETRY_INTERVAL).await;
+        }
+    }
+}
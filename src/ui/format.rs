@@ -0,0 +1,80 @@
+// Human-readable formatting shared by the CLI and the GUI
+use crate::domain::entities::{TrafficStats, VpnStatus};
+use crate::domain::state_machine::TunnelState;
+use crate::infrastructure::services::StoppedInstance;
+use std::time::Duration;
+
+/// Binary units, as `ip -h` and most file managers print them.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+pub fn format_rate(bytes_per_second: u64) -> String {
+    format!("{}/s", format_bytes(bytes_per_second))
+}
+
+/// `1h 02m 03s`, dropping leading zero units.
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (days, hours, minutes, seconds) = (seconds / 86_400, seconds / 3_600 % 24, seconds / 60 % 60, seconds % 60);
+    match (days, hours, minutes) {
+        (0, 0, 0) => format!("{}s", seconds),
+        (0, 0, _) => format!("{}m {:02}s", minutes, seconds),
+        (0, _, _) => format!("{}h {:02}m {:02}s", hours, minutes, seconds),
+        _ => format!("{}d {:02}h {:02}m", days, hours, minutes),
+    }
+}
+
+/// `↓ 1.2 MiB (34.0 KiB/s)  ↑ 200.0 KiB (1.0 KiB/s)`; rates are omitted
+/// until a second sample exists.
+pub fn format_traffic(traffic: &TrafficStats) -> String {
+    let side = |arrow: &str, bytes: u64, rate: Option<u64>| match rate {
+        Some(rate) => format!("{} {} ({})", arrow, format_bytes(bytes), format_rate(rate)),
+        None => format!("{} {}", arrow, format_bytes(bytes)),
+    };
+    format!(
+        "{}  {}",
+        side("↓", traffic.bytes_in(), traffic.rate_in()),
+        side("↑", traffic.bytes_out(), traffic.rate_out())
+    )
+}
+
+/// What a tunnel that is neither up nor simply down is doing: a handshake
+/// phase, `reconnecting (attempt 2)` or `gave up after 10 attempts: ...`.
+pub fn format_phase(status: &VpnStatus) -> Option<String> {
+    let phase = status.tunnel_state()?;
+    let text = match (phase, status.reconnect_attempt()) {
+        (TunnelState::GaveUp(reason), Some(attempts)) => {
+            format!("gave up after {} attempt{}: {}", attempts, if attempts == 1 { "" } else { "s" }, reason)
+        }
+        (TunnelState::GaveUp(_), None) => phase.to_string(),
+        (TunnelState::Connected, _) => return None,
+        (TunnelState::Reconnecting, Some(attempt)) => format!("reconnecting (attempt {})", attempt),
+        (phase, Some(attempt)) if phase.is_active() => format!("reconnecting (attempt {}, {})", attempt, phase),
+        (phase, None) if phase.is_active() => phase.to_string(),
+        _ => return None,
+    };
+    Some(text)
+}
+
+/// `openvpn 1234 stopped with SIGTERM`. A SIGKILL skips OpenVPN's own
+/// cleanup, so that case says what may have been left behind.
+pub fn format_stopped(instance: &StoppedInstance) -> String {
+    let line = format!("openvpn {} {}", instance.pid, instance.step);
+    if instance.step.is_forced() {
+        format!("{}; its down script did not run, so routes and DNS may need a manual cleanup", line)
+    } else {
+        line
+    }
+}
@@ -1,19 +1,163 @@
 #[cfg(feature = "gui")]
 mod gtk_implementation {
-    use crate::application::services::VpnApplicationService;
+    use crate::application::services::{ArchiveProfile, VpnApplicationService, VpnControl};
+    use crate::domain::{
+        bundle::BundleMode,
+        credentials::{CredentialKind, CredentialPrompt, CredentialReply, CredentialRequest, Credentials, KEY_REALM},
+        entities::VpnStatus,
+        validation::Severity,
+    };
+    use crate::infrastructure::archive::ProfileArchive;
+    use crate::infrastructure::logs::{LogLine, LogTail};
+    use crate::ui::format;
     use gtk4::prelude::*;
-    use gtk4::{glib, Application, ApplicationWindow, Box as GtkBox, Button, Label, ListBox, Orientation, ScrolledWindow, FileChooserDialog, FileChooserAction, ResponseType};
+    use gtk4::{glib, Application, ApplicationWindow, Box as GtkBox, Button, CheckButton, Label, ListBox, Orientation, ScrolledWindow, FileChooserDialog, FileChooserAction, ResponseType};
+    use async_trait::async_trait;
+    use std::cell::RefCell;
+    use std::path::PathBuf;
+    use std::rc::Rc;
     use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::sync::oneshot;
+
+    /// Lines loaded into the log panel when a profile is picked.
+    const LOG_PANEL_LINES: usize = 1000;
+
+    /// Asks for passwords with a modal dialog over the active window.
+    #[derive(Debug, Default)]
+    pub struct GtkCredentialPrompt;
+
+    #[async_trait]
+    impl CredentialPrompt for GtkCredentialPrompt {
+        async fn ask(&self, request: &CredentialRequest) -> Option<CredentialReply> {
+            let (reply_tx, reply_rx) = oneshot::channel();
+            let request = request.clone();
+            // Widgets live on the main thread; only the answer travels back.
+            glib::MainContext::default().invoke(move || Self::show_dialog(request, reply_tx));
+            reply_rx.await.ok().flatten()
+        }
+    }
+
+    impl GtkCredentialPrompt {
+        fn show_dialog(request: CredentialRequest, reply_tx: oneshot::Sender<Option<CredentialReply>>) {
+            let parent = gtk4::gio::Application::default()
+                .and_then(|app| app.downcast::<Application>().ok())
+                .and_then(|app| app.active_window());
+            let dialog = gtk4::Window::builder()
+                .title(format!("Connect to {}", request.vpn_name))
+                .modal(true)
+                .default_width(360)
+                .build();
+            dialog.set_transient_for(parent.as_ref());
+
+            let content = GtkBox::new(Orientation::Vertical, 8);
+            content.set_margin_top(12);
+            content.set_margin_bottom(12);
+            content.set_margin_start(12);
+            content.set_margin_end(12);
+
+            if request.retry {
+                let message = match &request.kind {
+                    CredentialKind::Password { realm } => format!("❌ Wrong '{}' password", realm),
+                    _ => "❌ The server rejected these credentials".to_string(),
+                };
+                content.append(&Label::new(Some(&message)));
+            }
+            let username_entry = gtk4::Entry::new();
+            let password_entry = gtk4::PasswordEntry::new();
+            password_entry.set_show_peek_icon(true);
+            password_entry.set_placeholder_text(Some("Password"));
+            let remember_check = CheckButton::with_label("Remember for next time");
+            let challenge = match &request.kind {
+                CredentialKind::UsernamePassword { challenge } => {
+                    username_entry.set_placeholder_text(Some("Username"));
+                    username_entry.set_text(request.username.as_deref().unwrap_or(""));
+                    content.append(&username_entry);
+                    content.append(&password_entry);
+                    challenge.as_ref()
+                }
+                CredentialKind::Password { realm } => {
+                    content.append(&Label::new(Some(&format!("Password for '{}'", realm))));
+                    content.append(&password_entry);
+                    // Only the key passphrase is stored; other realms are asked every time.
+                    remember_check.set_visible(realm == KEY_REALM);
+                    None
+                }
+                CredentialKind::ChallengeResponse { challenge } => {
+                    remember_check.set_visible(false);
+                    Some(challenge)
+                }
+            };
+            // One-time codes are typed fresh on every connection and never saved.
+            let response_entry = challenge.map(|challenge| {
+                let entry = gtk4::Entry::new();
+                entry.set_visibility(challenge.echo);
+                entry.set_input_purpose(gtk4::InputPurpose::Digits);
+                content.append(&Label::new(Some(&challenge.text)));
+                content.append(&entry);
+                entry
+            });
+            content.append(&remember_check);
+
+            let buttons = GtkBox::new(Orientation::Horizontal, 12);
+            buttons.set_halign(gtk4::Align::End);
+            let cancel_btn = Button::with_label("Cancel");
+            let connect_btn = Button::with_label("Connect");
+            connect_btn.add_css_class("suggested-action");
+            buttons.append(&cancel_btn);
+            buttons.append(&connect_btn);
+            content.append(&buttons);
+            dialog.set_child(Some(&content));
+
+            // Whichever way the dialog goes away answers exactly once.
+            let reply_tx = Rc::new(RefCell::new(Some(reply_tx)));
+            let answer = move |reply: Option<CredentialReply>| {
+                if let Some(reply_tx) = reply_tx.borrow_mut().take() {
+                    let _ = reply_tx.send(reply);
+                }
+            };
+
+            let answer_connect = answer.clone();
+            let dialog_connect = dialog.clone();
+            let response_value = response_entry.clone();
+            let password_activate = password_entry.clone();
+            let submit = move || {
+                answer_connect(Some(CredentialReply {
+                    credentials: Credentials::new(username_entry.text().to_string(), password_entry.text().to_string()),
+                    response: response_value.as_ref().map(|entry| entry.text().to_string()),
+                    remember: remember_check.is_active(),
+                }));
+                dialog_connect.close();
+            };
+            let submit_enter = submit.clone();
+            match &response_entry {
+                Some(entry) => entry.connect_activate(move |_| submit_enter()),
+                None => password_activate.connect_activate(move |_| submit_enter()),
+            };
+            connect_btn.connect_clicked(move |_| submit());
+
+            let dialog_cancel = dialog.clone();
+            cancel_btn.connect_clicked(move |_| dialog_cancel.close());
+            dialog.connect_close_request(move |_| {
+                answer(None);
+                glib::Propagation::Proceed
+            });
+
+            dialog.present();
+        }
+    }
 
     pub struct MainWindow {
         window: ApplicationWindow,
         vpn_service: Arc<VpnApplicationService>,
+        /// Connects and lists through the daemon when one is running.
+        control: Arc<dyn VpnControl>,
         vpn_list: ListBox,
         status_label: Label,
     }
 
     impl MainWindow {
-        pub fn new(app: &Application, vpn_service: Arc<VpnApplicationService>) -> Self {
+        pub fn new(app: &Application, vpn_service: Arc<VpnApplicationService>, control: Arc<dyn VpnControl>) -> Self {
             // Create main window
             let window = ApplicationWindow::builder()
                 .application(app)
@@ -40,6 +184,7 @@ mod gtk_implementation {
             // Status label
             let status_label = Label::new(Some("🔴 No VPN connections"));
             status_label.set_halign(gtk4::Align::Start);
+            status_label.set_wrap(true);
             status_label.add_css_class("title-3");
             main_box.append(&status_label);
 
@@ -83,11 +228,14 @@ mod gtk_implementation {
             button_box.append(&disconnect_btn);
             main_box.append(&button_box);
 
+            main_box.append(&Self::log_panel(Arc::clone(&vpn_service), Arc::clone(&control)));
+
             window.set_child(Some(&main_box));
 
             let main_window = Self {
                 window,
                 vpn_service,
+                control,
                 vpn_list,
                 status_label,
             };
@@ -98,13 +246,13 @@ mod gtk_implementation {
         }
 
         fn setup_events(&self, load_file_btn: Button, refresh_btn: Button, disconnect_btn: Button) {
-            let vpn_service_clone = Arc::clone(&self.vpn_service);
+            let control_clone = Arc::clone(&self.control);
             let vpn_list_clone = self.vpn_list.clone();
             let status_label_clone = self.status_label.clone();
 
             // Refresh button event
             refresh_btn.connect_clicked(move |_| {
-                let service = Arc::clone(&vpn_service_clone);
+                let service = Arc::clone(&control_clone);
                 let list = vpn_list_clone.clone();
                 let status = status_label_clone.clone();
                 
@@ -113,12 +261,10 @@ mod gtk_implementation {
                 });
             });
 
-            let vpn_service_clone2 = Arc::clone(&self.vpn_service);
-            let status_label_clone2 = self.status_label.clone();
-
             // Load file button event
             let window_clone = self.window.clone();
             let vpn_service_clone_load = Arc::clone(&self.vpn_service);
+            let control_clone_load = Arc::clone(&self.control);
             let vpn_list_clone_load = self.vpn_list.clone();
             let status_label_clone_load = self.status_label.clone();
 
@@ -130,27 +276,40 @@ mod gtk_implementation {
                     &[("Cancel", ResponseType::Cancel), ("Open", ResponseType::Accept)],
                 );
 
-                // Set file filter for .ovpn files
+                // Set file filter for .ovpn files and provider bundles
                 let filter = gtk4::FileFilter::new();
                 filter.set_name(Some("OpenVPN Configuration Files"));
                 filter.add_pattern("*.ovpn");
+                for pattern in ["*.nmconnection", "*.zip", "*.tar", "*.tar.gz", "*.tgz"] {
+                    filter.add_pattern(pattern);
+                }
                 dialog.add_filter(&filter);
 
                 let service_load = Arc::clone(&vpn_service_clone_load);
+                let control_load = Arc::clone(&control_clone_load);
                 let list_load = vpn_list_clone_load.clone();
                 let status_load = status_label_clone_load.clone();
+                let parent_load = window_clone.clone();
 
                 dialog.connect_response(move |dialog, response| {
                     if response == ResponseType::Accept {
                         if let Some(file) = dialog.file() {
                             if let Some(path) = file.path() {
                                 let service = Arc::clone(&service_load);
+                                let control = Arc::clone(&control_load);
                                 let list = list_load.clone();
                                 let status = status_load.clone();
                                 let path_str = path.to_string_lossy().to_string();
+                                let parent = parent_load.clone();
 
                                 glib::spawn_future_local(async move {
-                                    Self::load_vpn_file_async(service, list, status, path_str).await;
+                                    if ProfileArchive::is_archive(&path) {
+                                        Self::load_archive_async(parent, service, control, list, status, path).await;
+                                    } else if path.extension().is_some_and(|ext| ext == "nmconnection") {
+                                        Self::load_nm_connection_async(service, control, list, status, path).await;
+                                    } else {
+                                        Self::load_vpn_file_async(service, control, list, status, path_str).await;
+                                    }
                                 });
                             }
                         }
@@ -162,12 +321,12 @@ mod gtk_implementation {
             });
 
             // Disconnect button event - Force kill all VPN processes
-            let vpn_service_clone2 = Arc::clone(&self.vpn_service);
+            let control_clone2 = Arc::clone(&self.control);
             let status_label_clone2 = self.status_label.clone();
             let vpn_list_clone2 = self.vpn_list.clone();
 
             disconnect_btn.connect_clicked(move |_| {
-                let service = Arc::clone(&vpn_service_clone2);
+                let service = Arc::clone(&control_clone2);
                 let status = status_label_clone2.clone();
                 let list = vpn_list_clone2.clone();
                 
@@ -175,8 +334,11 @@ mod gtk_implementation {
                     status.set_text("🔄 Killing all VPN processes...");
                     
                     match service.force_kill_all_vpns().await {
-                        Ok(()) => {
-                            status.set_text("✅ All VPN processes killed successfully");
+                        Ok(stopped) => {
+                            match stopped.iter().find(|instance| instance.step.is_forced()) {
+                                Some(forced) => status.set_text(&format!("⚠️ {}", format::format_stopped(forced))),
+                                None => status.set_text("✅ All VPN processes stopped"),
+                            }
                             // Refresh the list
                             Self::refresh_vpn_list_async(service, list, status.clone()).await;
                         },
@@ -188,7 +350,7 @@ mod gtk_implementation {
             });
 
             // Initial load
-            let service = Arc::clone(&self.vpn_service);
+            let service = Arc::clone(&self.control);
             let list = self.vpn_list.clone();
             let status = self.status_label.clone();
             
@@ -196,8 +358,19 @@ mod gtk_implementation {
                 Self::refresh_vpn_list_async(service.clone(), list.clone(), status.clone()).await;
             });
 
+            // Tunnel phase changes (drops, reconnects) show up right away.
+            let service_watch = Arc::clone(&self.control);
+            let list_watch = self.vpn_list.clone();
+            let status_watch = self.status_label.clone();
+            glib::spawn_future_local(async move {
+                loop {
+                    service_watch.tunnel_state_changed().await;
+                    Self::refresh_vpn_list_async(Arc::clone(&service_watch), list_watch.clone(), status_watch.clone()).await;
+                }
+            });
+
             // Auto-refresh timer (every 5 seconds)
-            let service_timer = Arc::clone(&self.vpn_service);
+            let service_timer = Arc::clone(&self.control);
             let list_timer = self.vpn_list.clone();
             let status_timer = self.status_label.clone();
             
@@ -215,7 +388,7 @@ mod gtk_implementation {
         }
 
         async fn refresh_vpn_list_async(
-            service: Arc<VpnApplicationService>,
+            service: Arc<dyn VpnControl>,
             list: ListBox,
             status_label: Label,
         ) {
@@ -243,9 +416,17 @@ mod gtk_implementation {
                             row_box.set_margin_end(12);
 
                             // Status icon
+                            let phase = format::format_phase(vpn.status());
+                            let active = vpn.status().tunnel_state().is_some_and(|phase| phase.is_active());
+                            // Stopping a reconnecting tunnel must be possible before it is back.
+                            let reconnecting = active && vpn.status().reconnect_attempt().is_some();
                             let status_icon = if vpn.is_connected() {
                                 connected_count += 1;
                                 "🟢"
+                            } else if active {
+                                "🟡"
+                            } else if phase.is_some() {
+                                "⚠️"
                             } else {
                                 "🔴"
                             };
@@ -254,7 +435,12 @@ mod gtk_implementation {
                             
                             // VPN info
                             let info_box = GtkBox::new(Orientation::Vertical, 4);
-                            let name_label = Label::new(Some(&vpn.display_name()));
+                            let name = if vpn.metadata().is_favorite() {
+                                format!("⭐ {}", vpn.display_name())
+                            } else {
+                                vpn.display_name().to_string()
+                            };
+                            let name_label = Label::new(Some(&name));
                             name_label.set_halign(gtk4::Align::Start);
                             name_label.add_css_class("title-4");
                             
@@ -264,15 +450,33 @@ mod gtk_implementation {
                             
                             info_box.append(&name_label);
                             info_box.append(&id_label);
+                            if let Some(description) = vpn.metadata().description() {
+                                let description_label = Label::new(Some(description));
+                                description_label.set_halign(gtk4::Align::Start);
+                                info_box.append(&description_label);
+                            }
+                            let details = Self::tunnel_details(vpn.status());
+                            if !details.is_empty() {
+                                let details_label = Label::new(Some(&details));
+                                details_label.set_halign(gtk4::Align::Start);
+                                details_label.add_css_class("dim-label");
+                                info_box.append(&details_label);
+                            }
+                            if let Some(phase) = phase {
+                                let phase_label = Label::new(Some(&format!("Phase: {}", phase)));
+                                phase_label.set_halign(gtk4::Align::Start);
+                                phase_label.add_css_class("dim-label");
+                                info_box.append(&phase_label);
+                            }
                             info_box.set_hexpand(true);
 
                             // Connect button
-                            let connect_btn = if vpn.is_connected() {
+                            let connect_btn = if vpn.is_connected() || reconnecting {
                                 let btn = Button::with_label("🔌 Disconnect");
                                 btn.add_css_class("destructive-action");
                                 btn
                             } else {
-                                let btn = Button::with_label("🚀 Connect (Auto-Kill Others)");
+                                let btn = Button::with_label("🚀 Connect");
                                 btn.add_css_class("suggested-action");
                                 btn
                             };
@@ -281,7 +485,7 @@ mod gtk_implementation {
                             let vpn_id = vpn.id().to_string();
                             let service_clone = Arc::clone(&service);
                             let status_clone = status_label.clone();
-                            let is_connected = vpn.is_connected();
+                            let is_connected = vpn.is_connected() || reconnecting;
 
                             let list_clone_for_refresh = list.clone();
                             let service_clone_for_refresh = Arc::clone(&service);
@@ -296,9 +500,12 @@ mod gtk_implementation {
                                 glib::spawn_future_local(async move {
                                     if is_connected {
                                         status.set_text(&format!("🔄 Disconnecting {}...", vpn_id));
-                                        match service.force_kill_all_vpns().await {
-                                            Ok(()) => {
-                                                status.set_text(&format!("✅ Disconnected from {}", vpn_id));
+                                        match service.disconnect_vpn(&vpn_id).await {
+                                            Ok(stopped) => {
+                                                match stopped.iter().find(|instance| instance.step.is_forced()) {
+                                                    Some(forced) => status.set_text(&format!("⚠️ {}", format::format_stopped(forced))),
+                                                    None => status.set_text(&format!("✅ Disconnected from {}", vpn_id)),
+                                                }
                                                 // Actualizar la GUI automáticamente
                                                 Self::refresh_vpn_list_async(service_refresh, list_refresh, status.clone()).await;
                                             },
@@ -308,16 +515,19 @@ mod gtk_implementation {
                                         }
                                     } else {
                                         // Mostrar estado de conectando
-                                        status.set_text(&format!("🔄 Connecting to {}... (Auto-disconnecting others)", vpn_id));
+                                        status.set_text(&format!("🔄 Connecting to {}...", vpn_id));
                                         
                                         match service.connect_vpn(&vpn_id).await {
                                             Ok(()) => {
-                                                status.set_text(&format!("✅ Connected to {} (others disconnected)", vpn_id));
+                                                status.set_text(&format!("✅ Connected to {}", vpn_id));
                                                 // Actualizar la GUI automáticamente
                                                 Self::refresh_vpn_list_async(service_refresh, list_refresh, status.clone()).await;
                                             },
                                             Err(e) => {
-                                                status.set_text(&format!("❌ Connect failed: {}", e));
+                                                match e.hint() {
+                                                    Some(hint) => status.set_text(&format!("❌ Connect failed: {}\n💡 {}", e, hint)),
+                                                    None => status.set_text(&format!("❌ Connect failed: {}", e)),
+                                                }
                                             }
                                         }
                                     }
@@ -351,18 +561,34 @@ mod gtk_implementation {
 
         async fn load_vpn_file_async(
             service: Arc<VpnApplicationService>,
+            control: Arc<dyn VpnControl>,
             list: ListBox,
             status_label: Label,
             file_path: String,
         ) {
             status_label.set_text(&format!("📁 Loading VPN file: {}...", file_path));
-            
-            // Copy file to config directory
-            match Self::copy_vpn_file_to_config(&file_path).await {
-                Ok(new_path) => {
-                    status_label.set_text(&format!("✅ VPN file loaded: {}", new_path));
+
+            let path = std::path::Path::new(&file_path);
+            let report = service.validate_file(path).await;
+            if let Some(error) = report.findings.iter().find(|f| f.severity == Severity::Error) {
+                status_label.set_text(&format!("❌ Invalid profile: {}", report.format_finding(error)));
+                return;
+            }
+
+            match service.import_profile(path, None, BundleMode::Copy, false).await {
+                Ok(imported) if !imported.unresolved.is_empty() => {
+                    let missing: Vec<&str> = imported.unresolved.iter().map(|r| r.path.as_str()).collect();
+                    status_label.set_text(&format!(
+                        "⚠️ {} loaded, but these files were not found: {}",
+                        imported.vpn.display_name(),
+                        missing.join(", ")
+                    ));
+                    Self::refresh_vpn_list_async(control, list, status_label.clone()).await;
+                },
+                Ok(imported) => {
+                    status_label.set_text(&format!("✅ VPN file loaded: {}", imported.vpn.display_name()));
                     // Refresh the list to show the new VPN
-                    Self::refresh_vpn_list_async(service, list, status_label.clone()).await;
+                    Self::refresh_vpn_list_async(control, list, status_label.clone()).await;
                 },
                 Err(e) => {
                     status_label.set_text(&format!("❌ Failed to load file: {}", e));
@@ -370,27 +596,275 @@ mod gtk_implementation {
             }
         }
 
-        async fn copy_vpn_file_to_config(file_path: &str) -> Result<String, Box<dyn std::error::Error>> {
-            use std::path::Path;
-            use tokio::fs;
+        async fn load_nm_connection_async(
+            service: Arc<VpnApplicationService>,
+            control: Arc<dyn VpnControl>,
+            list: ListBox,
+            status_label: Label,
+            path: std::path::PathBuf,
+        ) {
+            status_label.set_text(&format!("📁 Converting NetworkManager connection: {}...", path.display()));
+
+            match service.import_nm_connection(&path, None, BundleMode::Copy, false).await {
+                Ok(nm) => {
+                    let mut message = format!("✅ Imported {}", nm.imported.vpn.display_name());
+                    if !nm.skipped.is_empty() {
+                        message.push_str(&format!(" (not converted: {})", nm.skipped.join(", ")));
+                    }
+                    status_label.set_text(&message);
+                    Self::refresh_vpn_list_async(control, list, status_label.clone()).await;
+                },
+                Err(e) => {
+                    status_label.set_text(&format!("❌ Failed to import connection: {}", e));
+                }
+            }
+        }
 
-            let source_path = Path::new(file_path);
-            let file_name = source_path
-                .file_name()
-                .ok_or("Invalid file name")?;
-            
-            let home_dir = std::env::var("HOME").unwrap_or_else(|_| "/home".to_string());
-            let config_dir = format!("{}/.connectvpn.conf", home_dir);
-            
-            // Create config directory if it doesn't exist
-            fs::create_dir_all(&config_dir).await?;
-            
-            let dest_path = format!("{}/{}", config_dir, file_name.to_string_lossy());
-            
-            // Copy the file
-            fs::copy(source_path, &dest_path).await?;
-            
-            Ok(dest_path)
+        async fn load_archive_async(
+            parent: ApplicationWindow,
+            service: Arc<VpnApplicationService>,
+            control: Arc<dyn VpnControl>,
+            list: ListBox,
+            status_label: Label,
+            path: PathBuf,
+        ) {
+            status_label.set_text(&format!("📦 Reading {}...", path.display()));
+            match service.archive_profiles(&path).await {
+                Ok(profiles) if profiles.is_empty() => {
+                    status_label.set_text(&format!("❌ No OpenVPN profiles in {}", path.display()));
+                },
+                Ok(profiles) => {
+                    status_label.set_text(&format!("📦 {} profiles found, choose which to import", profiles.len()));
+                    Self::show_archive_dialog(&parent, service, control, list, status_label, profiles);
+                },
+                Err(e) => {
+                    status_label.set_text(&format!("❌ Failed to read archive: {}", e));
+                }
+            }
+        }
+
+        /// Lets the user tick the archive profiles to import; invalid ones
+        /// are listed but cannot be selected.
+        fn show_archive_dialog(
+            parent: &ApplicationWindow,
+            service: Arc<VpnApplicationService>,
+            control: Arc<dyn VpnControl>,
+            list: ListBox,
+            status_label: Label,
+            profiles: Vec<ArchiveProfile>,
+        ) {
+            let dialog = gtk4::Window::builder()
+                .title("Import VPN Profiles")
+                .transient_for(parent)
+                .modal(true)
+                .default_width(520)
+                .default_height(420)
+                .build();
+
+            let content = GtkBox::new(Orientation::Vertical, 12);
+            content.set_margin_top(12);
+            content.set_margin_bottom(12);
+            content.set_margin_start(12);
+            content.set_margin_end(12);
+
+            let checks_box = GtkBox::new(Orientation::Vertical, 4);
+            let mut checks = Vec::new();
+            for profile in &profiles {
+                let label = match profile.errors.first() {
+                    Some(error) => format!("❌ {} ({}): {}", profile.display_name, profile.entry, error.message),
+                    None => format!("{} ({})", profile.display_name, profile.entry),
+                };
+                let check = CheckButton::with_label(&label);
+                check.set_sensitive(profile.errors.is_empty());
+                checks_box.append(&check);
+                checks.push(check);
+            }
+            let scrolled = ScrolledWindow::new();
+            scrolled.set_vexpand(true);
+            scrolled.set_child(Some(&checks_box));
+            content.append(&scrolled);
+
+            let buttons = GtkBox::new(Orientation::Horizontal, 12);
+            buttons.set_halign(gtk4::Align::End);
+            let select_all_btn = Button::with_label("Select All");
+            let cancel_btn = Button::with_label("Cancel");
+            let import_btn = Button::with_label("Import");
+            import_btn.add_css_class("suggested-action");
+            buttons.append(&select_all_btn);
+            buttons.append(&cancel_btn);
+            buttons.append(&import_btn);
+            content.append(&buttons);
+            dialog.set_child(Some(&content));
+
+            let checks_all = checks.clone();
+            select_all_btn.connect_clicked(move |_| {
+                for check in checks_all.iter().filter(|check| check.is_sensitive()) {
+                    check.set_active(true);
+                }
+            });
+
+            let dialog_cancel = dialog.clone();
+            let status_cancel = status_label.clone();
+            cancel_btn.connect_clicked(move |_| {
+                status_cancel.set_text("Import cancelled");
+                dialog_cancel.close();
+            });
+
+            let dialog_import = dialog.clone();
+            import_btn.connect_clicked(move |_| {
+                let selected: Vec<ArchiveProfile> = profiles
+                    .iter()
+                    .zip(&checks)
+                    .filter(|(_, check)| check.is_active())
+                    .map(|(profile, _)| profile.clone())
+                    .collect();
+                dialog_import.close();
+
+                let service = Arc::clone(&service);
+                let control = Arc::clone(&control);
+                let list = list.clone();
+                let status = status_label.clone();
+                glib::spawn_future_local(async move {
+                    let mut imported = 0;
+                    let mut failed = Vec::new();
+                    for profile in &selected {
+                        match service.import_archive_profile(profile, BundleMode::Copy, false).await {
+                            Ok(_) => imported += 1,
+                            Err(e) => failed.push(format!("{}: {}", profile.id, e)),
+                        }
+                    }
+                    Self::refresh_vpn_list_async(control, list, status.clone()).await;
+                    if failed.is_empty() {
+                        status.set_text(&format!("✅ Imported {} profile(s)", imported));
+                    } else {
+                        status.set_text(&format!("⚠️ Imported {}, failed: {}", imported, failed.join("; ")));
+                    }
+                });
+            });
+
+            dialog.present();
+        }
+
+        /// Collapsed panel following the OpenVPN output of a chosen profile.
+        /// Profiles are listed when it opens; the log file is read directly,
+        /// so it also works while the daemon owns the tunnel.
+        fn log_panel(service: Arc<VpnApplicationService>, control: Arc<dyn VpnControl>) -> gtk4::Expander {
+            let expander = gtk4::Expander::new(Some("📜 OpenVPN Log"));
+
+            let content = GtkBox::new(Orientation::Vertical, 6);
+            let profiles = gtk4::StringList::new(&[]);
+            let picker = gtk4::DropDown::new(Some(profiles.clone()), None::<gtk4::Expression>);
+            picker.set_halign(gtk4::Align::Start);
+            content.append(&picker);
+
+            let text_view = gtk4::TextView::new();
+            text_view.set_editable(false);
+            text_view.set_cursor_visible(false);
+            text_view.set_monospace(true);
+            let scrolled = ScrolledWindow::new();
+            scrolled.set_min_content_height(160);
+            scrolled.set_vexpand(true);
+            scrolled.set_child(Some(&text_view));
+            content.append(&scrolled);
+            expander.set_child(Some(&content));
+
+            let tail: Rc<RefCell<Option<LogTail>>> = Rc::new(RefCell::new(None));
+
+            expander.connect_expanded_notify(move |expander| {
+                if !expander.is_expanded() {
+                    return;
+                }
+                let control = Arc::clone(&control);
+                let profiles = profiles.clone();
+                glib::spawn_future_local(async move {
+                    if let Ok(vpns) = control.list_vpns().await {
+                        let ids: Vec<&str> = vpns.iter().map(|vpn| vpn.id()).collect();
+                        profiles.splice(0, profiles.n_items(), &ids);
+                    }
+                });
+            });
+
+            let tail_select = Rc::clone(&tail);
+            let view_select = text_view.clone();
+            picker.connect_selected_notify(move |picker| {
+                let Some(vpn_id) = picker
+                    .selected_item()
+                    .and_downcast::<gtk4::StringObject>()
+                    .map(|item| item.string().to_string())
+                else {
+                    return;
+                };
+                let service = Arc::clone(&service);
+                let tail = Rc::clone(&tail_select);
+                let view = view_select.clone();
+                glib::spawn_future_local(async move {
+                    *tail.borrow_mut() = service.follow_logs(&vpn_id).await.ok();
+                    let text = match service.logs(&vpn_id, None).await {
+                        Ok(lines) if lines.is_empty() => format!("No OpenVPN output recorded for {}\n", vpn_id),
+                        Ok(lines) => Self::log_text(&lines[lines.len().saturating_sub(LOG_PANEL_LINES)..]),
+                        Err(e) => format!("❌ {}\n", e),
+                    };
+                    view.buffer().set_text(&text);
+                    Self::scroll_to_end(&view);
+                });
+            });
+
+            let expander_timer = expander.clone();
+            glib::timeout_add_local(Duration::from_secs(1), move || {
+                if !expander_timer.is_expanded() {
+                    return glib::ControlFlow::Continue;
+                }
+                let lines = match tail.borrow_mut().as_mut().map(|tail| tail.read_new()) {
+                    Some(Ok(lines)) if !lines.is_empty() => lines,
+                    _ => return glib::ControlFlow::Continue,
+                };
+                let buffer = text_view.buffer();
+                buffer.insert(&mut buffer.end_iter(), &Self::log_text(&lines));
+                Self::scroll_to_end(&text_view);
+                glib::ControlFlow::Continue
+            });
+
+            expander
+        }
+
+        fn log_text(lines: &[LogLine]) -> String {
+            lines.iter().map(|line| format!("{}\n", line)).collect()
+        }
+
+        fn scroll_to_end(view: &gtk4::TextView) {
+            let mut end = view.buffer().end_iter();
+            view.scroll_to_iter(&mut end, 0.0, false, 0.0, 0.0);
+        }
+
+        /// One-line summary of the tunnel addresses, uptime and traffic, empty
+        /// when none are known.
+        fn tunnel_details(status: &VpnStatus) -> String {
+            let mut parts = Vec::new();
+            if !status.ip_address().is_empty() {
+                parts.push(format!("IP: {}", status.ip_address()));
+            }
+            if let Some(ipv6) = status.ipv6_address() {
+                parts.push(format!("IPv6: {}", ipv6));
+            }
+            if let Some(interface) = status.interface() {
+                parts.push(format!("Interface: {}", interface));
+            }
+            if let Some(gateway) = status.gateway() {
+                parts.push(format!("Gateway: {}", gateway));
+            }
+            if let Some(address) = status.remote_address() {
+                match status.remote_port() {
+                    Some(port) => parts.push(format!("Server: {}:{}", address, port)),
+                    None => parts.push(format!("Server: {}", address)),
+                }
+            }
+            if let Some(duration) = status.session_duration() {
+                parts.push(format!("Uptime: {}", format::format_duration(duration)));
+            }
+            if let Some(traffic) = status.traffic() {
+                parts.push(format::format_traffic(traffic));
+            }
+            parts.join("  ·  ")
         }
 
         pub fn show(&self) {
@@ -400,14 +874,18 @@ mod gtk_implementation {
 }
 
 #[cfg(feature = "gui")]
-pub use gtk_implementation::MainWindow;
+pub use gtk_implementation::{GtkCredentialPrompt, MainWindow};
 
 #[cfg(not(feature = "gui"))]
 pub struct MainWindow;
 
 #[cfg(not(feature = "gui"))]
 impl MainWindow {
-    pub fn new(_app: &gtk4::Application, _service: std::sync::Arc<crate::application::services::VpnApplicationService>) -> Self {
+    pub fn new(
+        _app: &gtk4::Application,
+        _service: std::sync::Arc<crate::application::services::VpnApplicationService>,
+        _control: std::sync::Arc<dyn crate::application::services::VpnControl>,
+    ) -> Self {
         Self
     }
     
@@ -1,3 +1,7 @@
 // UI layer - GTK4 GUI
+pub mod askpass;
+pub mod daemon;
+pub mod format;
+pub mod terminal;
 #[cfg(feature = "gui")]
-pub mod gtk;
\ No newline at end of file
+pub mod gtk;
@@ -0,0 +1,121 @@
+// Interactive prompts for the CLI
+use crate::domain::credentials::{
+    CredentialKind, CredentialPrompt, CredentialReply, CredentialRequest, Credentials, KEY_REALM,
+};
+use async_trait::async_trait;
+use std::io::{self, BufRead, IsTerminal, Write};
+
+/// Asks for credentials on the controlling terminal. Prompts go to stderr so
+/// they never mix with machine-readable output.
+#[derive(Debug, Default)]
+pub struct TerminalPrompt;
+
+impl TerminalPrompt {
+    /// `None` when stdin is not a terminal and nobody could answer.
+    pub fn if_interactive() -> Option<Self> {
+        io::stdin().is_terminal().then_some(Self)
+    }
+
+    fn ask_blocking(request: &CredentialRequest) -> io::Result<Option<CredentialReply>> {
+        let mut stderr = io::stderr();
+        if request.retry {
+            match &request.kind {
+                CredentialKind::Password { realm } => writeln!(stderr, "❌ Wrong '{}' password", realm)?,
+                _ => writeln!(stderr, "❌ The server rejected the credentials for {}", request.vpn_name)?,
+            }
+        }
+
+        let (username, password, challenge) = match &request.kind {
+            CredentialKind::UsernamePassword { challenge } => {
+                writeln!(stderr, "🔑 {} requires a username and password", request.vpn_name)?;
+                let username = match &request.username {
+                    Some(previous) => {
+                        let answer = read_line(&format!("Username [{}]: ", previous))?;
+                        if answer.is_empty() { previous.clone() } else { answer }
+                    }
+                    None => read_line("Username: ")?,
+                };
+                if username.is_empty() {
+                    return Ok(None);
+                }
+                (username, read_hidden("Password: ")?, challenge.as_ref())
+            }
+            CredentialKind::Password { realm } => {
+                writeln!(stderr, "🔑 {} requires the '{}' password", request.vpn_name, realm)?;
+                (String::new(), read_hidden("Password: ")?, None)
+            }
+            CredentialKind::ChallengeResponse { challenge } => {
+                writeln!(stderr, "🔐 {} requires a second factor", request.vpn_name)?;
+                (String::new(), String::new(), Some(challenge))
+            }
+        };
+
+        let response = match challenge {
+            Some(challenge) => {
+                let prompt = format!("{}: ", challenge.text.trim_end_matches(':'));
+                let answer = if challenge.echo { read_line(&prompt)? } else { read_hidden(&prompt)? };
+                if answer.is_empty() {
+                    return Ok(None);
+                }
+                Some(answer)
+            }
+            None => None,
+        };
+
+        let rememberable = match &request.kind {
+            CredentialKind::UsernamePassword { .. } => true,
+            CredentialKind::Password { realm } => realm == KEY_REALM,
+            CredentialKind::ChallengeResponse { .. } => false,
+        };
+        let remember = rememberable
+            && read_line("Remember for next time? [y/N] ")?.eq_ignore_ascii_case("y");
+        Ok(Some(CredentialReply {
+            credentials: Credentials::new(username, password),
+            response,
+            remember,
+        }))
+    }
+}
+
+#[async_trait]
+impl CredentialPrompt for TerminalPrompt {
+    async fn ask(&self, request: &CredentialRequest) -> Option<CredentialReply> {
+        let request = request.clone();
+        tokio::task::spawn_blocking(move || Self::ask_blocking(&request))
+            .await
+            .ok()
+            .and_then(Result::ok)
+            .flatten()
+    }
+}
+
+/// One line from stdin without its line ending; EOF reads as empty.
+pub fn read_line(prompt: &str) -> io::Result<String> {
+    let mut stderr = io::stderr();
+    write!(stderr, "{}", prompt)?;
+    stderr.flush()?;
+    let mut line = String::new();
+    io::stdin().lock().read_line(&mut line)?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Like [`read_line`] with terminal echo turned off.
+pub fn read_hidden(prompt: &str) -> io::Result<String> {
+    let fd = libc::STDIN_FILENO;
+    // SAFETY: termios is plain data and tcgetattr fully initialises it on success.
+    let mut original: libc::termios = unsafe { std::mem::zeroed() };
+    // SAFETY: fd is stdin and `original` is a valid, writable termios.
+    if unsafe { libc::tcgetattr(fd, &mut original) } != 0 {
+        // Not a terminal, e.g. a password piped in; nothing to hide.
+        return read_line(prompt);
+    }
+    let mut silent = original;
+    silent.c_lflag &= !libc::ECHO;
+    silent.c_lflag |= libc::ECHONL;
+    // SAFETY: `silent` is a copy of the settings tcgetattr returned.
+    unsafe { libc::tcsetattr(fd, libc::TCSANOW, &silent) };
+    let line = read_line(prompt);
+    // SAFETY: restores the settings read above.
+    unsafe { libc::tcsetattr(fd, libc::TCSANOW, &original) };
+    line
+}
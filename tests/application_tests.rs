@@ -1,12 +1,13 @@
 #[cfg(test)]
 mod vpn_application_service_tests {
     use ui_openvpn_linux::{
-        domain::{entities::Vpn, repositories::VpnRepository},
+        domain::{bundle::BundledFile, entities::Vpn, repositories::VpnRepository},
         application::services::VpnApplicationService,
         infrastructure::services::OpenVpnService,
     };
     use async_trait::async_trait;
     use std::collections::HashMap;
+    use std::path::PathBuf;
     use std::sync::{Arc, Mutex};
 
     struct MockVpnRepository {
@@ -43,6 +44,34 @@ mod vpn_application_service_tests {
             let vpns = self.vpns.lock().unwrap();
             Ok(vpns.values().cloned().collect())
         }
+
+        async fn create(&self, id: &str, _content: &str) -> Result<Vpn, Box<dyn std::error::Error>> {
+            let vpn = Vpn::new(id.to_string(), id.to_string(), format!("/{}.ovpn", id));
+            self.add_vpn(vpn.clone());
+            Ok(vpn)
+        }
+
+        async fn update_config(&self, id: &str, _content: &str) -> Result<Vpn, Box<dyn std::error::Error>> {
+            let vpns = self.vpns.lock().unwrap();
+            Ok(vpns.get(id).cloned().ok_or("not found")?)
+        }
+
+        async fn rename(&self, id: &str, new_id: &str) -> Result<Vpn, Box<dyn std::error::Error>> {
+            let mut vpns = self.vpns.lock().unwrap();
+            vpns.remove(id).ok_or("not found")?;
+            let vpn = Vpn::new(new_id.to_string(), new_id.to_string(), format!("/{}.ovpn", new_id));
+            vpns.insert(new_id.to_string(), vpn.clone());
+            Ok(vpn)
+        }
+
+        async fn store_files(&self, id: &str, _files: &[BundledFile]) -> Result<PathBuf, Box<dyn std::error::Error>> {
+            Ok(PathBuf::from(format!("/{}.d", id)))
+        }
+
+        async fn delete(&self, id: &str) -> Result<(), Box<dyn std::error::Error>> {
+            self.vpns.lock().unwrap().remove(id);
+            Ok(())
+        }
     }
 
     #[tokio::test]
@@ -75,6 +104,33 @@ mod vpn_application_service_tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn should_ask_for_passwords_before_launch_without_a_resident_client() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("work.ovpn");
+        let repo = Arc::new(MockVpnRepository::new());
+        repo.add_vpn(Vpn::new("work".to_string(), "Work".to_string(), path.to_string_lossy().to_string()));
+        let service = VpnApplicationService::new(repo, Arc::new(OpenVpnService::new()));
+
+        // Nobody to ask, so OpenVPN is never started.
+        std::fs::write(&path, "client\nremote vpn.example.com 1194\nauth-user-pass\n").unwrap();
+        let error = service.connect_vpn("work").await.unwrap_err();
+        assert!(error.to_string().contains("needs a username and password"), "{}", error);
+
+        std::fs::write(&path, "client\nauth-user-pass\nstatic-challenge \"OTP\" 1\n").unwrap();
+        let error = service.connect_vpn("work").await.unwrap_err();
+        assert!(error.to_string().contains("one-time code"), "{}", error);
+    }
+
+    #[test]
+    fn should_derive_ids_and_names_from_file_names() {
+        use std::path::Path;
+
+        assert_eq!(VpnApplicationService::profile_id_for(Path::new("/tmp/My VPN (2).ovpn")), "My_VPN__2_");
+        assert_eq!(VpnApplicationService::display_name_for("us-new_york-udp"), "US New York UDP");
+        assert_eq!(VpnApplicationService::display_name_for("nordvpn_de123"), "Nordvpn De123");
+    }
+
     #[tokio::test]
     async fn should_disconnect_current_connection() {
         let repo = Arc::new(MockVpnRepository::new());
@@ -84,14 +140,14 @@ mod vpn_application_service_tests {
         let result = service.disconnect_current().await;
         
         // Should succeed even if no connection exists
-        assert!(result.is_ok());
+        assert!(result.unwrap().is_empty());
     }
 }
 
 #[cfg(test)]
 mod vpn_mapper_tests {
     use ui_openvpn_linux::{
-        domain::entities::{Vpn, VpnStatus, ConnectionState},
+        domain::entities::Vpn,
         application::mappers::VpnMapper,
     };
 
@@ -122,7 +178,17 @@ mod vpn_mapper_tests {
                 state: ConnectionStateDto::Disconnected,
                 ip_address: String::new(),
                 connected_since: None,
+                phase: None,
+                ipv6_address: None,
+                remote_address: None,
+                remote_port: None,
+                interface: None,
+                gateway: None,
+                traffic: None,
+                uptime_seconds: None,
+                reconnect_attempt: None,
             },
+            metadata: Default::default(),
         };
 
         let vpn = VpnMapper::from_dto(&dto);
@@ -145,4 +211,181 @@ mod vpn_mapper_tests {
         assert_eq!(dtos[0].id, "vpn1");
         assert_eq!(dtos[1].id, "vpn2");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn should_map_credential_prompts_both_ways() {
+        use ui_openvpn_linux::application::mappers::CredentialMapper;
+        use ui_openvpn_linux::domain::credentials::{Challenge, CredentialKind, CredentialReply, CredentialRequest, Credentials};
+
+        let request = CredentialRequest {
+            vpn_id: "work".to_string(),
+            vpn_name: "Work".to_string(),
+            kind: CredentialKind::UsernamePassword {
+                challenge: Some(Challenge { text: "Enter PIN".to_string(), echo: false }),
+            },
+            username: Some("alice".to_string()),
+            retry: true,
+        };
+        let dto = CredentialMapper::request_to_dto(&request);
+        let json = serde_json::to_value(&dto).unwrap();
+        assert_eq!(json["kind"]["type"], "username_password");
+        assert_eq!(CredentialMapper::request_from_dto(&dto), request);
+
+        let reply = CredentialReply {
+            credentials: Credentials::new("alice", "secret"),
+            response: Some("123456".to_string()),
+            remember: true,
+        };
+        assert_eq!(CredentialMapper::reply_from_dto(&CredentialMapper::reply_to_dto(&reply)), reply);
+    }
+
+    #[test]
+    fn should_carry_diagnosed_failures_with_their_hint() {
+        use ui_openvpn_linux::application::mappers::FailureMapper;
+        use ui_openvpn_linux::domain::entities::VpnError;
+
+        let error = VpnError::HostUnresolved("vpn.example.com:1194".to_string());
+        let dto = FailureMapper::to_dto(&error).unwrap();
+        assert_eq!(dto.kind, "host_unresolved");
+        assert_eq!(dto.hint.as_deref(), error.hint());
+        assert!(matches!(
+            FailureMapper::from_dto(&dto),
+            Some(VpnError::HostUnresolved(host)) if host == "vpn.example.com:1194"
+        ));
+        assert!(FailureMapper::to_dto(&VpnError::EmptyId).is_none());
+    }
+
+    #[test]
+    fn should_build_stable_responses_for_scripts() {
+        use ui_openvpn_linux::application::mappers::ResponseMapper;
+        use ui_openvpn_linux::application::services::VpnServiceError;
+        use ui_openvpn_linux::domain::entities::VpnError;
+        use ui_openvpn_linux::domain::validation::{Finding, Severity, ValidationReport};
+
+        let done = ResponseMapper::connection_response(&Ok(Vec::new()), "Connected to work");
+        assert_eq!(
+            serde_json::to_value(&done).unwrap(),
+            serde_json::json!({ "success": true, "message": "Connected to work", "vpn": null })
+        );
+
+        let failed = ResponseMapper::connection_response(&Err(VpnServiceError::Diagnosed(VpnError::AuthFailed)), "unused");
+        let json = serde_json::to_value(&failed).unwrap();
+        assert_eq!(json["success"], false);
+        assert_eq!(json["failure"]["kind"], "auth_failed");
+        assert!(json["failure"]["hint"].is_string());
+
+        let report = ValidationReport::new(
+            "/tmp/work.ovpn".to_string(),
+            vec![
+                Finding::new(Severity::Warning, "comp-lzo", "compression is deprecated".to_string(), Some(4)),
+                Finding::new(Severity::Error, "missing-remote", "no 'remote'".to_string(), None),
+            ],
+        );
+        let dto = ResponseMapper::validation_report("work", &report);
+        assert_eq!((dto.errors, dto.warnings), (1, 1));
+        assert_eq!(dto.findings[0].severity, "error");
+        assert_eq!(dto.findings[1].line, Some(4));
+    }
+}
+#[cfg(test)]
+mod vpn_status_round_trip_tests {
+    use proptest::prelude::*;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+    use ui_openvpn_linux::application::dtos::VpnStatusDto;
+    use ui_openvpn_linux::application::mappers::VpnMapper;
+    use ui_openvpn_linux::domain::entities::{ConnectionState, TrafficStats, VpnStatus};
+    use ui_openvpn_linux::domain::state_machine::TunnelState;
+
+    const PHASES: [&str; 13] = [
+        "disconnected", "connecting", "resolving", "waiting", "auth", "get_config", "assign_ip",
+        "add_routes", "connected", "reconnecting", "exiting", "failed", "gave_up",
+    ];
+
+    fn any_time() -> impl Strategy<Value = SystemTime> {
+        (0u64..=253_402_300_799, prop_oneof![Just(0u32), 0u32..1_000_000_000])
+            .prop_map(|(seconds, nanos)| UNIX_EPOCH + Duration::new(seconds, nanos))
+    }
+
+    fn any_base() -> impl Strategy<Value = VpnStatus> {
+        let state = prop_oneof![
+            Just(ConnectionState::Disconnected),
+            Just(ConnectionState::Connecting),
+            Just(ConnectionState::Connected),
+            Just(ConnectionState::Disconnecting),
+            ".{0,20}".prop_map(ConnectionState::Error),
+        ];
+        let phase = (proptest::sample::select(&PHASES[..]), ".{0,20}")
+            .prop_map(|(name, reason)| TunnelState::from_name(name, &reason).unwrap());
+        prop_oneof![
+            (state, "[0-9.]{0,15}").prop_map(|(state, ip)| VpnStatus::new(state, ip)),
+            (phase, "[0-9.]{0,15}").prop_map(|(phase, ip)| VpnStatus::from_tunnel_state(phase, ip)),
+        ]
+    }
+
+    fn any_traffic() -> impl Strategy<Value = TrafficStats> {
+        (any::<u64>(), any::<u64>(), any::<Option<u64>>(), any::<Option<u64>>(), any_time()).prop_map(
+            |(bytes_in, bytes_out, rate_in, rate_out, sampled_at)| {
+                TrafficStats::new(bytes_in, bytes_out, sampled_at).with_rates(rate_in, rate_out)
+            },
+        )
+    }
+
+    prop_compose! {
+        fn any_status()(
+            mut status in any_base(),
+            connected_since in proptest::option::of(any_time()),
+            ipv6 in proptest::option::of("[0-9a-f:]{2,39}"),
+            remote in proptest::option::of("[a-z0-9.-]{1,30}"),
+            port in any::<Option<u16>>(),
+            interface in proptest::option::of("tun[0-9]"),
+            gateway in proptest::option::of("[0-9.]{7,15}"),
+            traffic in proptest::option::of(any_traffic()),
+            reconnect_attempt in any::<Option<u32>>(),
+        ) -> VpnStatus {
+            status.set_connected_since(connected_since);
+            status.set_ipv6_address(ipv6);
+            status.set_remote(remote, port);
+            status.set_interface(interface);
+            status.set_gateway(gateway);
+            status.set_traffic(traffic);
+            status.set_reconnect_attempt(reconnect_attempt);
+            status
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn should_round_trip_every_status_field(status in any_status()) {
+            let dto = VpnMapper::status_to_dto(&status);
+            prop_assert_eq!(VpnMapper::status_from_dto(&dto), status);
+        }
+
+        #[test]
+        fn should_round_trip_status_through_json(status in any_status()) {
+            let json = serde_json::to_string(&VpnMapper::status_to_dto(&status)).unwrap();
+            let dto: VpnStatusDto = serde_json::from_str(&json).unwrap();
+            prop_assert_eq!(VpnMapper::status_from_dto(&dto), status);
+        }
+    }
+
+    #[test]
+    fn should_write_rfc3339_and_compute_uptime() {
+        let mut status = VpnStatus::new(ConnectionState::Connected, "10.8.0.2".to_string());
+        status.set_connected_since(Some(SystemTime::now() - Duration::from_secs(90)));
+        let dto = VpnMapper::status_to_dto(&status);
+        let since = dto.connected_since.unwrap();
+        assert!(humantime::parse_rfc3339(&since).is_ok(), "not RFC 3339: {}", since);
+        assert!((90..=91).contains(&dto.uptime_seconds.unwrap()));
+
+        status.set_connected_since(Some(UNIX_EPOCH + Duration::from_secs(1_714_557_600)));
+        assert_eq!(VpnMapper::status_to_dto(&status).connected_since.as_deref(), Some("2024-05-01T10:00:00Z"));
+    }
+
+    #[test]
+    fn should_read_epoch_seconds_written_by_older_versions() {
+        let mut dto = VpnMapper::status_to_dto(&VpnStatus::new(ConnectionState::Connected, String::new()));
+        dto.connected_since = Some("1714557600".to_string());
+        let status = VpnMapper::status_from_dto(&dto);
+        assert_eq!(status.connected_since(), Some(UNIX_EPOCH + Duration::from_secs(1_714_557_600)));
+    }
+}
@@ -41,6 +41,77 @@ mod vpn_entity_tests {
         let result = Vpn::try_new("id".to_string(), "Display".to_string(), "".to_string());
         assert!(result.is_err());
     }
+
+    #[test]
+    fn should_only_conflict_with_exclusive_profiles() {
+        use ui_openvpn_linux::domain::entities::{ProfileMetadata, ProfileOptions};
+
+        let vpn = |id: &str, exclusive: bool| {
+            let mut metadata = ProfileMetadata::default();
+            metadata.set_options(ProfileOptions { exclusive, ..ProfileOptions::default() });
+            Vpn::new(id.to_string(), id.to_string(), format!("/vpn/{}.ovpn", id)).with_metadata(metadata)
+        };
+        let (work, lab, bank) = (vpn("work", false), vpn("lab", false), vpn("bank", true));
+
+        assert!(!work.conflicts_with(&lab));
+        assert!(work.conflicts_with(&bank));
+        assert!(bank.conflicts_with(&lab));
+        assert!(!bank.conflicts_with(&bank));
+    }
+}
+
+#[cfg(test)]
+mod credentials_tests {
+    use ui_openvpn_linux::domain::credentials::Credentials;
+
+    #[test]
+    fn should_not_reveal_passwords_in_debug_output() {
+        let credentials = Credentials::new("alice", "hunter2");
+        let debug = format!("{:?}", credentials);
+
+        assert!(debug.contains("alice"));
+        assert!(!debug.contains("hunter2"));
+        assert_eq!(credentials.password(), "hunter2");
+    }
+}
+
+#[cfg(test)]
+mod totp_tests {
+    use ui_openvpn_linux::domain::totp::{TotpAlgorithm, TotpError, TotpGenerator};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn should_match_rfc_6238_vectors() {
+        let at = |secs| UNIX_EPOCH + Duration::from_secs(secs);
+        let sha1 = TotpGenerator::parse("otpauth://totp/VPN:alice?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&digits=8&issuer=VPN").unwrap();
+        assert_eq!(sha1.code_at(at(59)), "94287082");
+        assert_eq!(sha1.code_at(at(1111111109)), "07081804");
+
+        let sha256 = TotpGenerator::new(b"12345678901234567890123456789012".to_vec(), 8, 30, TotpAlgorithm::Sha256);
+        assert_eq!(sha256.code_at(at(59)), "46119246");
+        let sha512 = TotpGenerator::new(
+            b"1234567890123456789012345678901234567890123456789012345678901234".to_vec(),
+            8,
+            30,
+            TotpAlgorithm::Sha512,
+        );
+        assert_eq!(sha512.code_at(at(59)), "90693936");
+    }
+
+    #[test]
+    fn should_accept_seeds_as_authenticator_apps_show_them() {
+        let spaced = TotpGenerator::parse("gezd gnbv gy3t qojq gezd gnbv gy3t qojq").unwrap();
+        let uri = TotpGenerator::parse("otpauth://totp/x?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ").unwrap();
+        assert_eq!(spaced, uri);
+        assert_eq!(spaced.code_at(UNIX_EPOCH + Duration::from_secs(59)).len(), 6);
+        assert!(!format!("{:?}", spaced).contains("secret"));
+
+        assert_eq!(TotpGenerator::parse("not base32!"), Err(TotpError::InvalidSeed));
+        assert!(matches!(
+            TotpGenerator::parse("otpauth://hotp/x?secret=GEZDGNBV"),
+            Err(TotpError::InvalidUri(_))
+        ));
+    }
 }
 
 #[cfg(test)]
@@ -79,10 +150,12 @@ mod vpn_status_tests {
 #[cfg(test)]
 mod connection_use_case_tests {
     use ui_openvpn_linux::domain::{
-        entities::{Vpn, ConnectionState},
+        bundle::BundledFile,
+        entities::Vpn,
         use_cases::ConnectVpnUseCase,
         repositories::VpnRepository,
     };
+    use std::path::PathBuf;
     use std::sync::Arc;
     use mockall::mock;
 
@@ -94,6 +167,11 @@ mod connection_use_case_tests {
             async fn find_by_id(&self, id: &str) -> Result<Option<Vpn>, Box<dyn std::error::Error>>;
             async fn save(&self, vpn: &Vpn) -> Result<(), Box<dyn std::error::Error>>;
             async fn list_all(&self) -> Result<Vec<Vpn>, Box<dyn std::error::Error>>;
+            async fn create(&self, id: &str, content: &str) -> Result<Vpn, Box<dyn std::error::Error>>;
+            async fn update_config(&self, id: &str, content: &str) -> Result<Vpn, Box<dyn std::error::Error>>;
+            async fn rename(&self, id: &str, new_id: &str) -> Result<Vpn, Box<dyn std::error::Error>>;
+            async fn store_files(&self, id: &str, files: &[BundledFile]) -> Result<PathBuf, Box<dyn std::error::Error>>;
+            async fn delete(&self, id: &str) -> Result<(), Box<dyn std::error::Error>>;
         }
     }
 
@@ -134,4 +212,617 @@ mod connection_use_case_tests {
 
         assert!(result.is_err());
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod ovpn_config_tests {
+    use ui_openvpn_linux::domain::ovpn::{OvpnConfig, OvpnEntry, OvpnParseError};
+
+    const PROFILE: &str = "# Corporate profile\r\nclient\r\ndev tun\r\nremote vpn.example.com 1194 udp\r\n; legacy option\r\nauth-user-pass \"/etc/openvpn/my creds.txt\"\r\n\r\n<ca>\r\n-----BEGIN CERTIFICATE-----\r\nMIIB\r\n-----END CERTIFICATE-----\r\n</ca>\r\nverb 3 # chatty";
+
+    #[test]
+    fn should_parse_directives_with_line_numbers() {
+        let config = OvpnConfig::parse(PROFILE).unwrap();
+
+        let remote = config.directive("remote").unwrap();
+        assert_eq!(remote.args(), &["vpn.example.com", "1194", "udp"]);
+        assert_eq!(remote.line(), 4);
+
+        let verb = config.directive("verb").unwrap();
+        assert_eq!(verb.args(), &["3"]);
+        assert_eq!(verb.line(), 13);
+    }
+
+    #[test]
+    fn should_parse_quoted_arguments() {
+        let config = OvpnConfig::parse(PROFILE).unwrap();
+        let auth = config.directive("auth-user-pass").unwrap();
+
+        assert_eq!(auth.arg(0), Some("/etc/openvpn/my creds.txt"));
+    }
+
+    #[test]
+    fn should_parse_inline_blocks_and_comments() {
+        let config = OvpnConfig::parse(PROFILE).unwrap();
+
+        let ca = config.inline_block("ca").unwrap();
+        assert_eq!(ca.line(), 8);
+        assert!(ca.content().starts_with("-----BEGIN CERTIFICATE-----"));
+        assert!(ca.content().ends_with("-----END CERTIFICATE-----\r\n"));
+
+        let comments: Vec<&str> = config.comments().map(|c| c.text()).collect();
+        assert_eq!(comments, vec![" Corporate profile", " legacy option"]);
+    }
+
+    #[test]
+    fn should_round_trip_without_changes() {
+        let config = OvpnConfig::parse(PROFILE).unwrap();
+        assert_eq!(config.render(), PROFILE);
+    }
+
+    #[test]
+    fn should_edit_only_touched_lines() {
+        let mut config = OvpnConfig::parse("client\n# keep me\ncipher AES-256-CBC\ncipher BF-CBC\n").unwrap();
+
+        config.set_directive("cipher", vec!["AES-256-GCM".to_string()]);
+        config.push_directive("auth-user-pass", vec!["my file.txt".to_string()]);
+        config.set_inline_block("tls-crypt", "KEY");
+
+        assert_eq!(
+            config.to_string(),
+            "client\n# keep me\ncipher AES-256-GCM\nauth-user-pass \"my file.txt\"\n<tls-crypt>\nKEY\n</tls-crypt>\n"
+        );
+        assert_eq!(config.remove_directive("cipher"), 1);
+        assert!(config.remove_inline_block("tls-crypt"));
+        assert_eq!(config.to_string(), "client\n# keep me\nauth-user-pass \"my file.txt\"\n");
+    }
+
+    #[test]
+    fn should_reject_unclosed_inline_block() {
+        let result = OvpnConfig::parse("client\n<key>\nsecret\n");
+        assert_eq!(result, Err(OvpnParseError::UnclosedBlock { tag: "key".to_string(), line: 2 }));
+    }
+
+    #[test]
+    fn should_reject_unterminated_quote() {
+        let result = OvpnConfig::parse("client\nauth-user-pass \"creds.txt\n");
+        assert_eq!(result, Err(OvpnParseError::UnterminatedQuote { line: 2 }));
+    }
+
+    #[test]
+    fn should_strip_leading_dashes_from_directive_names() {
+        let config = OvpnConfig::parse("--client\n--remote host 443\n").unwrap();
+        assert!(config.has_directive("client"));
+        assert!(matches!(config.entries().nth(1), Some(OvpnEntry::Directive(d)) if d.name() == "remote"));
+    }
+}
+
+#[cfg(test)]
+mod config_validator_tests {
+    use ui_openvpn_linux::domain::validation::{ConfigValidator, Severity, ValidationReport};
+    use std::fs;
+    use tempfile::TempDir;
+
+    const VALID: &str = "client\ndev tun\nproto udp\nremote vpn.example.com 1194\n<ca>\nCA\n</ca>\n<cert>\nCERT\n</cert>\n<key>\nKEY\n</key>\n";
+
+    fn rules(source: &str) -> Vec<String> {
+        ConfigValidator::new(None)
+            .validate_source(source)
+            .into_iter()
+            .map(|finding| finding.rule)
+            .collect()
+    }
+
+    #[test]
+    fn should_accept_complete_inline_profile() {
+        assert!(rules(VALID).is_empty());
+    }
+
+    #[test]
+    fn should_report_missing_remote_and_credentials() {
+        let found = rules("client\ndev tun\n");
+        assert!(found.contains(&"missing-remote".to_string()));
+        assert!(found.contains(&"missing-ca".to_string()));
+        assert!(found.contains(&"missing-cert".to_string()));
+    }
+
+    #[test]
+    fn should_resolve_file_references_relative_to_profile_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("ca.crt"), "CA").unwrap();
+        let source = "client\ndev tun\nremote host 1194\nca ca.crt\ncert client.crt\nkey client.key\n";
+
+        let findings = ConfigValidator::new(Some(temp_dir.path().to_path_buf())).validate_source(source);
+        let missing: Vec<usize> = findings
+            .iter()
+            .filter(|f| f.rule == "missing-file")
+            .filter_map(|f| f.line)
+            .collect();
+
+        assert_eq!(missing, vec![5, 6]);
+    }
+
+    #[test]
+    fn should_flag_conflicts_duplicates_and_unknown_options() {
+        let source = format!("{}tls-auth ta.key 1\ntls-crypt tc.key\nverb 3\nverb 4\nremote-cert-tsl server\n", VALID);
+        let findings = ConfigValidator::new(None).validate_source(&source);
+
+        let conflict = findings.iter().find(|f| f.rule == "conflicting-options").unwrap();
+        assert_eq!((conflict.severity, conflict.line), (Severity::Error, Some(15)));
+        let duplicate = findings.iter().find(|f| f.rule == "duplicate-option").unwrap();
+        assert_eq!((duplicate.severity, duplicate.line), (Severity::Warning, Some(17)));
+        let unknown = findings.iter().find(|f| f.rule == "unknown-option").unwrap();
+        assert_eq!(unknown.line, Some(18));
+    }
+
+    #[test]
+    fn should_flag_dev_and_proto_mismatches() {
+        let source = VALID.replace("dev tun\nproto udp", "dev tun0\ndev-type tap\nproto tcp\nfragment 1300");
+        let found = rules(&source);
+
+        assert!(found.contains(&"dev-mismatch".to_string()));
+        assert!(found.contains(&"proto-mismatch".to_string()));
+    }
+
+    #[test]
+    fn should_report_syntax_errors_with_location() {
+        let findings = ConfigValidator::new(None).validate_source("client\n<ca>\nCA\n");
+        let report = ValidationReport::new("corp.ovpn".to_string(), findings);
+
+        assert!(report.has_errors());
+        assert_eq!(
+            report.format_finding(&report.findings[0]),
+            "corp.ovpn:2: error: line 2: inline block <ca> is never closed [syntax]"
+        );
+    }
+}
+
+#[cfg(test)]
+mod profile_linter_tests {
+    use ui_openvpn_linux::domain::{lint::ProfileLinter, ovpn::OvpnConfig, validation::Severity};
+
+    const LEGACY: &str = "client\ndev tun\ncipher AES-256-CBC\nauth SHA1\ncomp-lzo\ntls-version-min 1.0 or-highest\nscript-security 3\n";
+
+    #[test]
+    fn should_flag_deprecated_and_insecure_directives() {
+        let config = OvpnConfig::parse(LEGACY).unwrap();
+        let findings = ProfileLinter::lint(&config);
+
+        let rules: Vec<(&str, Option<usize>)> = findings.iter().map(|f| (f.rule.as_str(), f.line)).collect();
+        assert_eq!(
+            rules,
+            vec![
+                ("cipher-without-data-ciphers", Some(3)),
+                ("weak-auth-digest", Some(4)),
+                ("deprecated-compression", Some(5)),
+                ("outdated-tls", Some(6)),
+                ("script-security", Some(7)),
+            ]
+        );
+        assert!(findings.iter().all(|f| f.hint.is_some()));
+    }
+
+    #[test]
+    fn should_treat_64_bit_ciphers_and_md5_as_errors() {
+        let config = OvpnConfig::parse("client\ncipher BF-CBC\nauth MD5\n").unwrap();
+        let findings = ProfileLinter::lint(&config);
+
+        assert!(findings.iter().all(|f| f.severity == Severity::Error));
+        assert_eq!(findings.len(), 2);
+    }
+
+    #[test]
+    fn should_produce_fixed_copy_that_lints_clean() {
+        let config = OvpnConfig::parse(LEGACY).unwrap();
+        let fixed = ProfileLinter::fix(&config);
+
+        assert_eq!(
+            fixed.render(),
+            "client\ndev tun\ndata-ciphers AES-256-GCM:AES-128-GCM:CHACHA20-POLY1305\ndata-ciphers-fallback AES-256-CBC\nauth SHA256\ntls-version-min 1.2 or-highest\nscript-security 2\n"
+        );
+        assert!(ProfileLinter::lint(&fixed).is_empty());
+    }
+
+    #[test]
+    fn should_accept_modern_profile() {
+        let config = OvpnConfig::parse("client\ndata-ciphers AES-256-GCM\ncipher AES-256-GCM\ncompress stub-v2\nauth SHA512\n").unwrap();
+        assert!(ProfileLinter::lint(&config).is_empty());
+    }
+}
+
+#[cfg(test)]
+mod state_machine_tests {
+    use ui_openvpn_linux::domain::entities::{ConnectionState, VpnStatus};
+    use ui_openvpn_linux::domain::state_machine::{ConnectionStateMachine, TransitionError, TunnelState};
+
+    fn drive(machine: &mut ConnectionStateMachine, states: &[&str]) {
+        for state in states {
+            machine.apply_openvpn_state(state, "").unwrap();
+        }
+    }
+
+    #[test]
+    fn should_follow_openvpn_udp_handshake_to_connected() {
+        let mut machine = ConnectionStateMachine::new();
+        machine.transition(TunnelState::Connecting).unwrap();
+
+        // UDP with a literal IP: no RESOLVE and no TCP_CONNECT.
+        drive(&mut machine, &["WAIT", "AUTH", "GET_CONFIG", "ASSIGN_IP", "ADD_ROUTES"]);
+        assert_eq!(machine.connection_state(), ConnectionState::Connecting);
+
+        drive(&mut machine, &["CONNECTED"]);
+        assert_eq!(machine.state(), &TunnelState::Connected);
+        assert_eq!(machine.connection_state(), ConnectionState::Connected);
+    }
+
+    #[test]
+    fn should_reject_illegal_transitions() {
+        let mut machine = ConnectionStateMachine::new();
+        let error = machine.transition(TunnelState::Connected).unwrap_err();
+        assert_eq!(
+            error,
+            TransitionError::IllegalTransition { from: TunnelState::Disconnected, to: TunnelState::Connected }
+        );
+
+        machine.transition(TunnelState::Connecting).unwrap();
+        drive(&mut machine, &["AUTH"]);
+        assert!(machine.apply_openvpn_state("WAIT", "").is_err());
+        assert_eq!(machine.state(), &TunnelState::Auth);
+    }
+
+    #[test]
+    fn should_map_exiting_reasons() {
+        assert_eq!(TunnelState::from_openvpn("EXITING", "SIGTERM"), Some(TunnelState::Exiting));
+        assert_eq!(
+            TunnelState::from_openvpn("EXITING", "auth-failure"),
+            Some(TunnelState::Failed("auth-failure".to_string()))
+        );
+        assert_eq!(TunnelState::from_openvpn("TCP_CONNECT", ""), Some(TunnelState::Waiting));
+        assert_eq!(TunnelState::from_openvpn("SOMETHING_NEW", ""), None);
+    }
+
+    #[test]
+    fn should_restart_handshake_after_reconnecting() {
+        let mut machine = ConnectionStateMachine::new();
+        machine.transition(TunnelState::Connecting).unwrap();
+        drive(&mut machine, &["WAIT", "AUTH", "GET_CONFIG", "CONNECTED", "RECONNECTING", "WAIT", "AUTH", "CONNECTED"]);
+        assert_eq!(machine.state(), &TunnelState::Connected);
+
+        machine.apply_openvpn_state("EXITING", "tls-error").unwrap();
+        assert_eq!(machine.connection_state(), ConnectionState::Error("tls-error".to_string()));
+        machine.transition(TunnelState::Connecting).unwrap();
+    }
+
+    #[test]
+    fn should_wait_for_the_supervisor_after_a_drop() {
+        let mut machine = ConnectionStateMachine::new();
+        machine.transition(TunnelState::Connecting).unwrap();
+        drive(&mut machine, &["WAIT", "AUTH", "CONNECTED"]);
+        machine.apply_openvpn_state("EXITING", "ping-exit").unwrap();
+
+        machine.transition(TunnelState::Reconnecting).unwrap();
+        machine.transition(TunnelState::GaveUp("ping-exit".to_string())).unwrap();
+        assert_eq!(machine.connection_state(), ConnectionState::Error("ping-exit".to_string()));
+        assert!(!machine.state().is_active());
+        assert!(machine.transition(TunnelState::Reconnecting).is_err());
+        machine.transition(TunnelState::Connecting).unwrap();
+    }
+
+    #[test]
+    fn should_track_phase_in_vpn_status() {
+        let mut status = VpnStatus::from_tunnel_state(TunnelState::Auth, String::new());
+        assert_eq!(status.state(), &ConnectionState::Connecting);
+        assert!(status.connected_since().is_none());
+
+        status.set_tunnel_state(TunnelState::Connected);
+        assert_eq!(status.state(), &ConnectionState::Connected);
+        assert!(status.connected_since().is_some());
+
+        status.set_state(ConnectionState::Disconnected);
+        assert!(status.tunnel_state().is_none());
+    }
+
+    #[test]
+    fn should_forget_tunnel_addresses_once_down() {
+        let mut status = VpnStatus::from_tunnel_state(TunnelState::Connected, "10.8.0.6".to_string());
+        status.set_remote(Some("203.0.113.5".to_string()), Some(1194));
+        status.set_interface(Some("tun0".to_string()));
+        status.set_gateway(Some("10.8.0.1".to_string()));
+
+        status.set_tunnel_state(TunnelState::Reconnecting);
+        assert_eq!(status.interface(), Some("tun0"));
+
+        status.set_tunnel_state(TunnelState::Failed("ping-restart".to_string()));
+        assert_eq!(status.ip_address(), "");
+        assert!(status.remote_address().is_none() && status.remote_port().is_none());
+        assert!(status.interface().is_none() && status.gateway().is_none());
+    }
+}
+
+#[cfg(test)]
+mod retry_policy_tests {
+    use ui_openvpn_linux::domain::reconnect::RetryPolicy;
+    use std::time::Duration;
+
+    #[test]
+    fn should_back_off_exponentially_up_to_the_cap() {
+        let policy = RetryPolicy { jitter_percent: 0, ..RetryPolicy::default() };
+        let delays: Vec<u64> = (1..=9).map(|attempt| policy.delay(attempt, 0.5).as_secs()).collect();
+        assert_eq!(delays, [2, 4, 8, 16, 32, 64, 128, 256, 300]);
+        assert_eq!(policy.delay(u32::MAX, 0.5), Duration::from_secs(300));
+    }
+
+    #[test]
+    fn should_spread_delays_within_the_jitter_band() {
+        let policy = RetryPolicy { initial_delay_secs: 10, jitter_percent: 20, ..RetryPolicy::default() };
+        assert_eq!(policy.delay(1, 0.0), Duration::from_secs(8));
+        assert_eq!(policy.delay(1, 0.5), Duration::from_secs(10));
+        assert!(policy.delay(1, 0.999) < Duration::from_secs(12));
+    }
+
+    #[test]
+    fn should_limit_attempts() {
+        let policy = RetryPolicy { max_attempts: Some(3), ..RetryPolicy::default() };
+        assert!(policy.allows(1) && policy.allows(3));
+        assert!(!policy.allows(4));
+
+        let forever = RetryPolicy { max_attempts: None, ..RetryPolicy::default() };
+        assert!(forever.allows(10_000));
+        assert!(!RetryPolicy::disabled().allows(1));
+    }
+}
+
+#[cfg(test)]
+mod diagnosis_tests {
+    use ui_openvpn_linux::domain::{diagnosis::diagnose, entities::VpnError};
+
+    #[test]
+    fn should_recognize_common_failures() {
+        let cases = [
+            ("AUTH: Received control message: AUTH_FAILED", "auth"),
+            ("TLS Error: TLS key negotiation failed to occur within 60 seconds (check your network connectivity)", "tls"),
+            ("TLS Error: TLS handshake failed", "tls"),
+            ("OpenSSL: error:0A000086:SSL routines::certificate verify failed", "cert"),
+            ("RESOLVE: Cannot resolve host address: vpn.example.com:1194 (Name or service not known)", "resolve"),
+            ("ERROR: Cannot ioctl TUNSETIFF tun: Operation not permitted (errno=1)", "tun"),
+            ("ERROR: Cannot open TUN/TAP dev /dev/net/tun: Permission denied (errno=13)", "tun"),
+            ("OPTIONS ERROR: failed to negotiate cipher with server.  Add the server's cipher ('BF-CBC') to --data-ciphers", "cipher"),
+        ];
+        for (line, expected) in cases {
+            let kind = match diagnose([line]) {
+                Some(VpnError::AuthFailed) => "auth",
+                Some(VpnError::TlsHandshakeFailed) => "tls",
+                Some(VpnError::CertificateVerifyFailed(_)) => "cert",
+                Some(VpnError::HostUnresolved(_)) => "resolve",
+                Some(VpnError::TunPermissionDenied) => "tun",
+                Some(VpnError::CipherNegotiationFailed) => "cipher",
+                other => panic!("{:?} for {}", other, line),
+            };
+            assert_eq!(kind, expected, "{}", line);
+        }
+        assert!(diagnose(["Initialization Sequence Completed"]).is_none());
+    }
+
+    #[test]
+    fn should_prefer_the_most_specific_cause() {
+        let lines = [
+            "VERIFY ERROR: depth=0, error=certificate has expired: CN=vpn.example.com",
+            "OpenSSL: error:0A000086:SSL routines::certificate verify failed",
+            "TLS_ERROR: BIO read tls_read_plaintext error",
+            "TLS Error: TLS handshake failed",
+        ];
+        match diagnose(lines) {
+            Some(VpnError::CertificateVerifyFailed(reason)) => {
+                assert_eq!(reason, "certificate has expired: CN=vpn.example.com")
+            }
+            other => panic!("unexpected diagnosis {:?}", other),
+        }
+
+        let error = diagnose(["RESOLVE: Cannot resolve host address: vpn.example.com:1194 (Temporary failure in name resolution)"]);
+        assert!(matches!(&error, Some(VpnError::HostUnresolved(host)) if host == "vpn.example.com:1194"));
+        assert!(error.unwrap().hint().is_some());
+    }
+}
+
+#[cfg(test)]
+mod traffic_stats_tests {
+    use ui_openvpn_linux::domain::entities::TrafficStats;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn should_derive_throughput_from_consecutive_samples() {
+        let start = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let first = TrafficStats::new(1_000, 500, start);
+        assert_eq!(first.rate_in(), None);
+
+        let second = first.next(5_000, 1_500, start + Duration::from_secs(2));
+        assert_eq!(second.bytes_in(), 5_000);
+        assert_eq!(second.rate_in(), Some(2_000));
+        assert_eq!(second.rate_out(), Some(500));
+    }
+
+    #[test]
+    fn should_drop_rate_when_counters_restart() {
+        let start = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let restarted = TrafficStats::new(1_000, 500, start).next(10, 20, start + Duration::from_secs(1));
+
+        assert_eq!(restarted.rate_in(), None);
+        assert_eq!(restarted.rate_out(), None);
+    }
+}
+
+#[cfg(test)]
+mod profile_bundle_tests {
+    use ui_openvpn_linux::domain::{bundle::ProfileBundle, ovpn::OvpnConfig};
+    use std::path::Path;
+
+    const PROFILE: &str = "client\ncd /etc/openvpn\nca ca.crt\ncert client.crt\nkey keys/client.key\ntls-auth ta.key 1\n";
+
+    fn bundle() -> ProfileBundle {
+        let config = OvpnConfig::parse(PROFILE).unwrap();
+        let mut bundle = ProfileBundle::new(config);
+        bundle.add_file("ca", "ca.crt", b"CA\n".to_vec());
+        bundle.add_file("cert", "client.crt", b"CERT\n".to_vec());
+        bundle.add_file("tls-auth", "ta.key", b"TA\n".to_vec());
+        bundle
+    }
+
+    #[test]
+    fn should_point_copied_files_at_the_profile_directory() {
+        let mut bundle = bundle();
+        bundle.add_file("extra-certs", "other/ca.crt", b"OTHER\n".to_vec());
+        let names: Vec<&str> = bundle.files().iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["ca.crt", "client.crt", "ta.key", "ca-2.crt"]);
+
+        let relocated = bundle.relocated(Path::new("/home/u/.connectvpn.conf/work.d"));
+
+        assert!(relocated.contains("ca /home/u/.connectvpn.conf/work.d/ca.crt\n"));
+        assert!(relocated.contains("tls-auth /home/u/.connectvpn.conf/work.d/ta.key 1\n"));
+        // Not bundled, so left as written.
+        assert!(relocated.contains("key keys/client.key\n"));
+        assert!(!relocated.contains("cd "));
+    }
+
+    #[test]
+    fn should_inline_files_and_keep_the_key_direction() {
+        let inlined = bundle().inlined().unwrap();
+        let config = OvpnConfig::parse(&inlined).unwrap();
+
+        assert_eq!(config.inline_block("ca").unwrap().content(), "CA\n");
+        assert_eq!(config.inline_block("tls-auth").unwrap().content(), "TA\n");
+        assert_eq!(config.directive("key-direction").unwrap().args(), &["1"]);
+        assert!(!config.has_directive("ca"));
+        assert!(!config.has_directive("tls-auth"));
+        assert!(config.has_directive("key"));
+    }
+
+    #[test]
+    fn should_refuse_to_inline_binary_files_except_pkcs12() {
+        let config = OvpnConfig::parse("client\npkcs12 id.p12\nca ca.der\n").unwrap();
+        let mut bundle = ProfileBundle::new(config);
+        bundle.add_file("pkcs12", "id.p12", vec![0, 159, 146, 150]);
+        assert!(bundle.inlined().unwrap().contains("<pkcs12>\nAJ+Slg==\n</pkcs12>\n"));
+
+        bundle.add_file("ca", "ca.der", vec![0, 159, 146, 150]);
+        assert!(bundle.inlined().unwrap_err().to_string().contains("ca.der"));
+    }
+
+    #[test]
+    fn should_strip_keys_and_credentials() {
+        let config = OvpnConfig::parse(
+            "client\nca ca.crt\ncert client.crt\nkey keys/client.key\ntls-auth ta.key 1\nauth-user-pass creds.txt\n<tls-crypt>\nTC\n</tls-crypt>\n",
+        )
+        .unwrap();
+        let mut bundle = ProfileBundle::new(config);
+        bundle.add_file("ca", "ca.crt", b"CA\n".to_vec());
+        bundle.add_file("tls-auth", "ta.key", b"TA\n".to_vec());
+        bundle.add_file("auth-user-pass", "creds.txt", b"me\nhunter2\n".to_vec());
+
+        let stripped = bundle.strip_secrets();
+        assert_eq!(stripped, vec!["key", "tls-auth", "tls-crypt", "auth-user-pass"]);
+
+        let inlined = bundle.inlined().unwrap();
+        assert!(!inlined.contains("hunter2"));
+        assert!(!inlined.contains("TA") && !inlined.contains("TC"));
+        let config = OvpnConfig::parse(&inlined).unwrap();
+        assert_eq!(config.inline_block("ca").unwrap().content(), "CA\n");
+        assert!(config.directive("auth-user-pass").unwrap().args().is_empty());
+        assert!(!config.has_directive("key"));
+        assert!(config.has_directive("cert"));
+    }
+}
+
+#[cfg(test)]
+mod networkmanager_tests {
+    use ui_openvpn_linux::domain::{
+        networkmanager::{NmConnection, NmKeyfileError},
+        ovpn::OvpnConfig,
+    };
+    use std::collections::BTreeMap;
+
+    const KEYFILE: &str = "\
+[connection]
+id=Office VPN
+uuid=4f1c2a6e-8d0b-4b43-9c3e-2f0a7d5b1e90
+type=vpn
+autoconnect=false
+
+[vpn]
+ca=/home/u/.cert/nm-openvpn/office-ca.pem
+cert=/home/u/.cert/nm-openvpn/office-cert.pem
+cert-pass-flags=0
+cipher=AES-256-GCM
+comp-lzo=no-by-default
+connection-type=password-tls
+dev=tun
+key=/home/u/.cert/nm-openvpn/office-key.pem
+password-flags=2
+remote=vpn1.example.com:1194:udp, [2001:db8::1]:443:tcp
+ta=/home/u/.cert/nm-openvpn/office-tls-auth.pem
+ta-dir=1
+tunnel-mtu=1400
+username=alice
+mystery-option=yes
+service-type=org.freedesktop.NetworkManager.openvpn
+
+[ipv4]
+method=auto
+never-default=true
+";
+
+    #[test]
+    fn should_convert_a_keyfile_to_a_profile() {
+        let connection = NmConnection::parse(KEYFILE).unwrap();
+        assert_eq!(connection.name(), "Office VPN");
+        assert_eq!(connection.username(), Some("alice"));
+
+        let converted = connection.to_ovpn();
+        let config = &converted.value;
+        let remotes: Vec<Vec<String>> = config.directives_named("remote").map(|d| d.args().to_vec()).collect();
+        assert_eq!(remotes, vec![
+            vec!["vpn1.example.com".to_string(), "1194".to_string(), "udp".to_string()],
+            vec!["2001:db8::1".to_string(), "443".to_string(), "tcp".to_string()],
+        ]);
+        assert_eq!(config.directive("ca").unwrap().args(), &["/home/u/.cert/nm-openvpn/office-ca.pem"]);
+        assert_eq!(config.directive("tls-auth").unwrap().args(), &["/home/u/.cert/nm-openvpn/office-tls-auth.pem", "1"]);
+        assert_eq!(config.directive("cipher").unwrap().args(), &["AES-256-GCM"]);
+        assert_eq!(config.directive("tun-mtu").unwrap().args(), &["1400"]);
+        assert!(config.has_directive("client"));
+        assert!(config.has_directive("auth-user-pass"));
+        assert_eq!(config.directive("pull-filter").unwrap().args(), &["ignore", "redirect-gateway"]);
+        assert_eq!(converted.skipped, vec!["mystery-option".to_string()]);
+    }
+
+    #[test]
+    fn should_round_trip_a_profile_through_a_keyfile() {
+        let profile = "client\ndev tun\nproto udp\nremote vpn.example.com 1194\nauth-user-pass\n\
+                       cipher AES-256-GCM\nremote-cert-tls server\nca /etc/openvpn/ca.crt\n<tls-crypt>\nKEY\n</tls-crypt>\n";
+        let config = OvpnConfig::parse(profile).unwrap();
+        let files = BTreeMap::from([("tls-crypt".to_string(), "/tmp/work-tls-crypt.pem".to_string())]);
+
+        let exported = NmConnection::from_ovpn("Work", "0d7e6c3a-51a2-4a5e-bb2f-8e1f0c9a7d11", &config, &files);
+        let keyfile = exported.value.render();
+        // A CA without a client certificate means username/password only.
+        assert!(keyfile.contains("connection-type=password\n"));
+        assert!(keyfile.contains("password-flags=2\n"));
+        assert!(!keyfile.contains("[vpn-secrets]"));
+
+        let back = NmConnection::parse(&keyfile).unwrap().to_ovpn().value;
+        assert_eq!(back.directive("remote").unwrap().args(), &["vpn.example.com", "1194"]);
+        assert_eq!(back.directive("proto").unwrap().args(), &["udp"]);
+        assert_eq!(back.directive("tls-crypt").unwrap().args(), &["/tmp/work-tls-crypt.pem"]);
+        assert_eq!(back.directive("ca").unwrap().args(), &["/etc/openvpn/ca.crt"]);
+        assert_eq!(back.directive("remote-cert-tls").unwrap().args(), &["server"]);
+        assert!(back.has_directive("auth-user-pass"));
+    }
+
+    #[test]
+    fn should_reject_keyfiles_that_are_not_openvpn() {
+        let wifi = "[connection]\nid=Home\ntype=wifi\n";
+        assert!(matches!(NmConnection::parse(wifi), Err(NmKeyfileError::NotVpn(_))));
+
+        let wireguard = "[connection]\nid=WG\ntype=vpn\n\n[vpn]\nservice-type=org.freedesktop.NetworkManager.wireguard\n";
+        assert!(matches!(NmConnection::parse(wireguard), Err(NmKeyfileError::NotOpenVpn(_))));
+
+        assert!(matches!(NmConnection::parse("id=x\n[connection\n"), Err(NmKeyfileError::Syntax { .. })));
+    }
+}
@@ -1,11 +1,12 @@
 #[cfg(test)]
 mod file_vpn_repository_tests {
     use ui_openvpn_linux::{
-        domain::{entities::Vpn, repositories::VpnRepository},
+        domain::repositories::VpnRepository,
         infrastructure::repositories::FileVpnRepository,
     };
     use std::path::PathBuf;
     use std::fs;
+    use std::time::{Duration, UNIX_EPOCH};
     use tempfile::TempDir;
 
     fn create_temp_vpn_config(temp_dir: &TempDir, name: &str, content: &str) -> PathBuf {
@@ -29,6 +30,18 @@ mod file_vpn_repository_tests {
         assert_eq!(vpn.id(), "test-vpn");
     }
 
+    #[tokio::test]
+    async fn should_attach_parsed_config() {
+        let temp_dir = TempDir::new().unwrap();
+        let _config_path = create_temp_vpn_config(&temp_dir, "test-vpn", "client\nremote vpn.example.com 1194\n");
+
+        let repo = FileVpnRepository::new(temp_dir.path().to_path_buf());
+        let vpn = repo.find_by_id("test-vpn").await.unwrap().unwrap();
+
+        let config = vpn.config().unwrap();
+        assert_eq!(config.directive("remote").unwrap().args(), &["vpn.example.com", "1194"]);
+    }
+
     #[tokio::test]
     async fn should_return_none_when_vpn_not_found() {
         let temp_dir = TempDir::new().unwrap();
@@ -59,30 +72,120 @@ mod file_vpn_repository_tests {
     }
 
     #[tokio::test]
-    async fn should_map_display_names_correctly() {
+    async fn should_map_display_names_from_metadata() {
         let temp_dir = TempDir::new().unwrap();
         let _config1 = create_temp_vpn_config(&temp_dir, "David_cruz", "config 1");
         let _config2 = create_temp_vpn_config(&temp_dir, "julian", "config 2");
-        
+        let _config3 = create_temp_vpn_config(&temp_dir, "lab", "config 3");
+        fs::write(
+            temp_dir.path().join("profiles.json"),
+            r#"{"version": 1, "profiles": {
+                "David_cruz": {"display_name": "Dynamic"},
+                "julian": {"display_name": "Howden", "favorite": true, "tags": ["work"]},
+                "deleted": {"display_name": "Gone"}
+            }}"#,
+        )
+        .unwrap();
+
         let repo = FileVpnRepository::new(temp_dir.path().to_path_buf());
-        let result = repo.list_all().await;
+        let vpns = repo.list_all().await.unwrap();
 
-        assert!(result.is_ok());
-        let vpns = result.unwrap();
-        
-        for vpn in vpns {
-            match vpn.id() {
-                "David_cruz" => assert_eq!(vpn.display_name(), "Dynamic"),
-                "julian" => assert_eq!(vpn.display_name(), "Howden"),
-                _ => panic!("Unexpected VPN ID"),
-            }
-        }
+        let names: Vec<(&str, &str)> = vpns.iter().map(|v| (v.id(), v.display_name())).collect();
+        // Favorites first, then alphabetical; profiles without metadata use their id.
+        assert_eq!(names, vec![("julian", "Howden"), ("David_cruz", "Dynamic"), ("lab", "lab")]);
+        assert_eq!(vpns[0].metadata().tags(), &["work".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn should_persist_metadata_on_save() {
+        let temp_dir = TempDir::new().unwrap();
+        let _config = create_temp_vpn_config(&temp_dir, "julian", "client\n");
+        let repo = FileVpnRepository::new(temp_dir.path().to_path_buf());
+
+        let mut vpn = repo.find_by_id("julian").await.unwrap().unwrap();
+        let mut metadata = vpn.metadata().clone();
+        metadata.set_display_name(Some("Howden".to_string()));
+        metadata.set_description(Some("Office gateway".to_string()));
+        metadata.set_last_used(Some(UNIX_EPOCH + Duration::from_secs(1_700_000_000)));
+        vpn.set_metadata(metadata);
+        repo.save(&vpn).await.unwrap();
+
+        let stored = fs::read_to_string(temp_dir.path().join("profiles.json")).unwrap();
+        assert!(stored.contains("\"last_used\": \"2023-11-14T22:13:20Z\""));
+
+        let reloaded = FileVpnRepository::new(temp_dir.path().to_path_buf())
+            .find_by_id("julian")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(reloaded.display_name(), "Howden");
+        assert_eq!(reloaded.metadata(), vpn.metadata());
+    }
+
+    #[tokio::test]
+    async fn should_refuse_corrupt_metadata() {
+        let temp_dir = TempDir::new().unwrap();
+        let _config = create_temp_vpn_config(&temp_dir, "julian", "client\n");
+        fs::write(temp_dir.path().join("profiles.json"), "{not json").unwrap();
+
+        let repo = FileVpnRepository::new(temp_dir.path().to_path_buf());
+        let error = repo.list_all().await.unwrap_err();
+
+        assert!(error.to_string().contains("profiles.json"));
+    }
+
+    #[tokio::test]
+    async fn should_manage_profile_lifecycle() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let repo = FileVpnRepository::new(temp_dir.path().to_path_buf());
+
+        let vpn = repo.create("office", "client\nremote a.example.com\n").await.unwrap();
+        assert_eq!(vpn.display_name(), "office");
+        let mode = fs::metadata(temp_dir.path().join("office.ovpn")).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert!(repo.create("office", "client\n").await.is_err());
+
+        let mut vpn = repo.find_by_id("office").await.unwrap().unwrap();
+        let mut metadata = vpn.metadata().clone();
+        metadata.set_display_name(Some("Office".to_string()));
+        vpn.set_metadata(metadata);
+        repo.save(&vpn).await.unwrap();
+        let mode = fs::metadata(temp_dir.path().join("profiles.json")).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert!(fs::read_dir(temp_dir.path()).unwrap().all(|entry| !entry.unwrap().file_name().to_string_lossy().ends_with(".tmp")));
+
+        let renamed = repo.rename("office", "hq").await.unwrap();
+        assert_eq!(renamed.display_name(), "Office");
+        assert!(!temp_dir.path().join("office.ovpn").exists());
+        assert!(repo.find_by_id("office").await.unwrap().is_none());
+
+        let updated = repo.update_config("hq", "client\nremote b.example.com\n").await.unwrap();
+        assert_eq!(updated.config().unwrap().directive("remote").unwrap().args(), &["b.example.com"]);
+
+        repo.delete("hq").await.unwrap();
+        assert!(repo.list_all().await.unwrap().is_empty());
+        let stored = fs::read_to_string(temp_dir.path().join("profiles.json")).unwrap();
+        assert!(!stored.contains("Office"));
+        assert!(repo.delete("hq").await.is_err());
     }
 }
 
 #[cfg(test)]
 mod openvpn_service_tests {
-    use ui_openvpn_linux::infrastructure::services::OpenVpnService;
+    use ui_openvpn_linux::infrastructure::services::{OpenVpnService, ShutdownStep};
+    use std::path::Path;
+    use std::process::{Child, Command};
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    /// Runs `binary` under the name `openvpn` so the inspector treats it as one.
+    fn spawn_as_openvpn(dir: &TempDir, binary: &str, args: &[&str]) -> Child {
+        let fake = dir.path().join("openvpn");
+        std::fs::copy(Path::new(binary), &fake).unwrap();
+        Command::new(&fake).args(args).spawn().unwrap()
+    }
 
     #[tokio::test]
     async fn should_create_openvpn_service() {
@@ -98,4 +201,724 @@ mod openvpn_service_tests {
         
         assert_eq!(args, vec!["--config".to_string(), "/path/to/config.ovpn".to_string()]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn should_name_instances_apart_per_config_file() {
+        let (one, other) = (TempDir::new().unwrap(), TempDir::new().unwrap());
+        let path = |dir: &TempDir, file: &str| dir.path().join(file).to_string_lossy().to_string();
+        let name = |path: &str| OpenVpnService::instance_name(path);
+
+        let dotted = name(&path(&one, "a.b.ovpn"));
+        assert!(dotted.starts_with("a_b-") && !dotted.contains('.'), "{}", dotted);
+        assert_ne!(dotted, name(&path(&one, "a_b.ovpn")));
+        assert_ne!(name(&path(&one, "work.ovpn")), name(&path(&other, "work.ovpn")));
+        assert_eq!(name(&path(&one, "work.ovpn")), name(&path(&one, "work.ovpn")));
+    }
+
+    #[test]
+    fn should_query_passwords_only_with_a_resident_client() {
+        let socket = std::path::Path::new("/run/user/1000/ui-openvpn/work.sock");
+        let asks = |service: &OpenVpnService| {
+            let args = service.build_management_args(socket);
+            (args.contains(&"--management-query-passwords".to_string()), args.contains(&"interact".to_string()))
+        };
+
+        assert!(!OpenVpnService::new().has_resident_client());
+        assert_eq!(asks(&OpenVpnService::new()), (false, false));
+        assert_eq!(asks(&OpenVpnService::new().with_resident_client(true)), (true, true));
+        assert!(OpenVpnService::new().build_management_args(socket).contains(&"--management-hold".to_string()));
+    }
+
+    #[tokio::test]
+    async fn should_stop_with_sigterm_before_escalating() {
+        let dir = TempDir::new().unwrap();
+        let mut child = spawn_as_openvpn(&dir, "/bin/sleep", &["30"]);
+        let service = OpenVpnService::new().with_shutdown_timeout(Duration::from_secs(5));
+
+        assert_eq!(service.stop_instance(child.id(), None).await, Ok(ShutdownStep::Terminate));
+        child.wait().unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_kill_processes_that_ignore_sigterm() {
+        let dir = TempDir::new().unwrap();
+        let mut child = spawn_as_openvpn(&dir, "/bin/sh", &["-c", "trap '' TERM; while :; do sleep 0.1; done"]);
+        let service = OpenVpnService::new().with_shutdown_timeout(Duration::from_millis(500));
+        // Let the shell install its trap first.
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        let step = service.stop_instance(child.id(), None).await.unwrap();
+        assert_eq!(step, ShutdownStep::Kill);
+        assert!(step.is_forced());
+        child.wait().unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_disconnect_only_the_requested_profile() {
+        let (work_dir, lab_dir) = (TempDir::new().unwrap(), TempDir::new().unwrap());
+        let work = work_dir.path().join("work.ovpn").to_string_lossy().to_string();
+        let lab = lab_dir.path().join("lab.ovpn").to_string_lossy().to_string();
+        // `; :` keeps the shell from exec'ing sleep, so it stays named openvpn.
+        let mut work_child = spawn_as_openvpn(&work_dir, "/bin/sh", &["-c", "sleep 30; :", "openvpn", "--config", &work]);
+        let mut lab_child = spawn_as_openvpn(&lab_dir, "/bin/sh", &["-c", "sleep 30; :", "openvpn", "--config", &lab]);
+        let service = OpenVpnService::new().with_shutdown_timeout(Duration::from_secs(5));
+
+        let stopped = service.disconnect(&work).await.unwrap();
+        assert_eq!(stopped.len(), 1);
+        assert_eq!(stopped[0].pid, work_child.id());
+        work_child.wait().unwrap();
+        assert_eq!(lab_child.try_wait().unwrap(), None);
+
+        lab_child.kill().unwrap();
+        lab_child.wait().unwrap();
+    }
+
+    #[test]
+    fn should_name_shutdown_steps_both_ways() {
+        for step in [ShutdownStep::Management, ShutdownStep::Terminate, ShutdownStep::Kill] {
+            assert_eq!(ShutdownStep::from_name(step.name()), Some(step));
+        }
+        assert_eq!(ShutdownStep::from_name("sighup"), None);
+    }
+}
+#[cfg(test)]
+mod process_inspector_tests {
+    use ui_openvpn_linux::infrastructure::{process::ProcessInspector, services::OpenVpnService};
+    use std::fs;
+    use std::os::unix::fs::symlink;
+    use std::path::{Path, PathBuf};
+    use std::time::{Duration, UNIX_EPOCH};
+    use tempfile::TempDir;
+
+    const BOOT_TIME: u64 = 1_700_000_000;
+
+    fn add_process(root: &Path, pid: u32, argv: &[&str], exe: Option<&str>, cwd: &str, uid: u32) {
+        let dir = root.join(pid.to_string());
+        fs::create_dir_all(&dir).unwrap();
+        let cmdline: Vec<u8> = argv.iter().flat_map(|arg| arg.bytes().chain(std::iter::once(0))).collect();
+        fs::write(dir.join("cmdline"), cmdline).unwrap();
+        fs::write(dir.join("status"), format!("Name:\topenvpn\nUid:\t{uid}\t{uid}\t{uid}\t{uid}\n")).unwrap();
+        fs::write(
+            dir.join("stat"),
+            format!("{pid} (open vpn) S 1 {pid} {pid} 0 -1 4194560 1 0 0 0 0 0 0 0 20 0 1 0 500 1000 10\n"),
+        )
+        .unwrap();
+        symlink(cwd, dir.join("cwd")).unwrap();
+        if let Some(exe) = exe {
+            symlink(exe, dir.join("exe")).unwrap();
+        }
+    }
+
+    fn fake_procfs() -> (TempDir, ProcessInspector) {
+        let temp_dir = TempDir::new().unwrap();
+        let proc_root = temp_dir.path().join("proc");
+        fs::create_dir_all(&proc_root).unwrap();
+        fs::write(proc_root.join("stat"), format!("cpu 1 2 3\nbtime {}\n", BOOT_TIME)).unwrap();
+        let passwd = temp_dir.path().join("passwd");
+        fs::write(&passwd, "root:x:0:0:root:/root:/bin/bash\nalice:x:1000:1000::/home/alice:/bin/sh\n").unwrap();
+
+        add_process(&proc_root, 100, &["openvpn", "--config", "work.ovpn"], Some("/usr/sbin/openvpn"), "/home/alice/vpn", 0);
+        add_process(&proc_root, 101, &["pkexec", "openvpn", "--config", "/x.ovpn"], Some("/usr/bin/pkexec"), "/", 1000);
+        add_process(&proc_root, 102, &["bash"], Some("/usr/bin/bash"), "/", 1000);
+        add_process(
+            &proc_root,
+            103,
+            &["/usr/sbin/openvpn", "--cd", "/etc/my vpn", "--config=office vpn.ovpn"],
+            None,
+            "/",
+            1000,
+        );
+
+        let inspector = ProcessInspector::new(proc_root, passwd);
+        (temp_dir, inspector)
+    }
+
+    #[test]
+    fn should_discover_only_real_openvpn_processes() {
+        let (_temp_dir, inspector) = fake_procfs();
+        let pids: Vec<u32> = inspector.openvpn_processes().iter().map(|p| p.pid).collect();
+
+        assert_eq!(pids, vec![100, 103]);
+    }
+
+    #[test]
+    fn should_resolve_config_paths_against_cwd_and_cd() {
+        let (_temp_dir, inspector) = fake_procfs();
+        let processes = inspector.openvpn_processes();
+
+        assert_eq!(processes[0].config_path, Some(PathBuf::from("/home/alice/vpn/work.ovpn")));
+        assert_eq!(processes[1].config_path, Some(PathBuf::from("/etc/my vpn/office vpn.ovpn")));
+    }
+
+    #[test]
+    fn should_report_owner_and_start_time() {
+        let (_temp_dir, inspector) = fake_procfs();
+        let process = inspector.inspect(103).unwrap();
+
+        assert_eq!(process.uid, Some(1000));
+        assert_eq!(process.user.as_deref(), Some("alice"));
+        let start = process.start_time.unwrap();
+        assert!(start > UNIX_EPOCH + Duration::from_secs(BOOT_TIME));
+        assert!(start <= UNIX_EPOCH + Duration::from_secs(BOOT_TIME + 500));
+    }
+
+    #[tokio::test]
+    async fn should_drive_service_connection_checks() {
+        let (_temp_dir, inspector) = fake_procfs();
+        let service = OpenVpnService::with_inspector(inspector);
+
+        assert!(service.is_connected().await);
+        assert_eq!(
+            service.connected_vpn_configs().await,
+            vec!["/home/alice/vpn/work.ovpn".to_string(), "/etc/my vpn/office vpn.ovpn".to_string()]
+        );
+    }
+}
+
+#[cfg(test)]
+mod management_client_tests {
+    use ui_openvpn_linux::infrastructure::management::{
+        DynamicChallenge, ManagementClient, ManagementError, ManagementEvent, PasswordPrompt, StaticChallenge,
+    };
+    use std::time::Duration;
+    use tempfile::TempDir;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::UnixListener;
+
+    /// Replies to each known command with a canned answer, then pushes `events`.
+    fn spawn_fake_server(listener: UnixListener, events: &'static [&'static str]) {
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = stream.into_split();
+            writer.write_all(b">INFO:OpenVPN Management Interface Version 5\r\n").await.unwrap();
+
+            let mut lines = BufReader::new(reader).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                let reply = match line.as_str() {
+                    "state on" => "SUCCESS: real-time state notification set to ON\r\n".to_string(),
+                    "state" => "1700000000,WAIT,,,,,,\r\n1700000001,CONNECTED,SUCCESS,10.8.0.6,203.0.113.5,1194,,\r\nEND\r\n".to_string(),
+                    "log all" => "1700000000,I,TUN/TAP device tun0 opened\r\n1700000001,,PUSH: Received control message: 'PUSH_REPLY,route 10.8.0.0 255.255.255.0,route-gateway 10.8.0.1,topology subnet,ifconfig 10.8.0.6 255.255.255.0'\r\nEND\r\n".to_string(),
+                    "hold off" => "SUCCESS: hold flag set to OFF\r\n".to_string(),
+                    "hold release" => {
+                        let mut reply = "SUCCESS: hold release succeeded\r\n".to_string();
+                        for event in events {
+                            reply.push_str(event);
+                            reply.push_str("\r\n");
+                        }
+                        reply
+                    }
+                    other => format!("ERROR: unknown command [{}]\r\n", other),
+                };
+                writer.write_all(reply.as_bytes()).await.unwrap();
+            }
+        });
+    }
+
+    #[test]
+    fn should_parse_notifications() {
+        let state = ManagementEvent::parse(">STATE:1700000001,CONNECTED,SUCCESS,10.8.0.6,203.0.113.5,1194,,,fd00::6");
+        match state {
+            Some(ManagementEvent::State(state)) => {
+                assert_eq!(state.name, "CONNECTED");
+                assert_eq!(state.local_ip.as_deref(), Some("10.8.0.6"));
+                assert_eq!(state.remote_port, Some(1194));
+                assert_eq!(state.local_ipv6.as_deref(), Some("fd00::6"));
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+
+        assert_eq!(
+            ManagementEvent::parse(">BYTECOUNT:1024,2048"),
+            Some(ManagementEvent::ByteCount { bytes_in: 1024, bytes_out: 2048 })
+        );
+        assert!(matches!(
+            ManagementEvent::parse(">LOG:1700000002,W,WARNING: cipher, deprecated"),
+            Some(ManagementEvent::Log(log)) if log.flags == "W" && log.message == "WARNING: cipher, deprecated"
+        ));
+        assert_eq!(
+            ManagementEvent::parse(">HOLD:Waiting for hold release:0"),
+            Some(ManagementEvent::Hold("Waiting for hold release:0".to_string()))
+        );
+        assert_eq!(
+            ManagementEvent::parse(">PASSWORD:Need 'Auth' username/password"),
+            Some(ManagementEvent::Password(PasswordPrompt::UsernamePassword {
+                realm: "Auth".to_string(),
+                static_challenge: None,
+            }))
+        );
+        assert_eq!(
+            ManagementEvent::parse(">PASSWORD:Need 'Private Key' password"),
+            Some(ManagementEvent::Password(PasswordPrompt::Password { realm: "Private Key".to_string() }))
+        );
+        assert_eq!(ManagementEvent::parse("SUCCESS: done"), None);
+    }
+
+    #[test]
+    fn should_parse_and_answer_challenges() {
+        let challenge = match ManagementEvent::parse(">PASSWORD:Need 'Auth' username/password SC:1,Enter OTP") {
+            Some(ManagementEvent::Password(PasswordPrompt::UsernamePassword { static_challenge: Some(sc), .. })) => sc,
+            other => panic!("unexpected event {:?}", other),
+        };
+        assert_eq!(
+            challenge,
+            StaticChallenge { text: "Enter OTP".to_string(), echo: true, concat: false }
+        );
+        assert_eq!(challenge.password("pass", "123456"), "SCRV1:cGFzcw==:MTIzNDU2");
+        let concat = StaticChallenge { concat: true, ..challenge };
+        assert_eq!(concat.password("pass", "123456"), "pass123456");
+
+        // dXNlcg== is "user".
+        let event = ManagementEvent::parse(">PASSWORD:Verification Failed: 'Auth' ['CRV1:R,E:Om01u7Fh4LrGBS7uh0SWmzwabUiGiW6l:dXNlcg==:Enter your OTP']");
+        let dynamic = match event {
+            Some(ManagementEvent::Password(PasswordPrompt::VerificationFailed { challenge: Some(dynamic), .. })) => dynamic,
+            other => panic!("unexpected event {:?}", other),
+        };
+        assert_eq!(
+            dynamic,
+            DynamicChallenge {
+                text: "Enter your OTP".to_string(),
+                echo: true,
+                response_required: true,
+                state_id: "Om01u7Fh4LrGBS7uh0SWmzwabUiGiW6l".to_string(),
+                username: "user".to_string(),
+            }
+        );
+        assert_eq!(dynamic.password("654321"), "CRV1::Om01u7Fh4LrGBS7uh0SWmzwabUiGiW6l::654321");
+        assert_eq!(
+            ManagementEvent::parse(">PASSWORD:Verification Failed: 'Auth'"),
+            Some(ManagementEvent::Password(PasswordPrompt::VerificationFailed {
+                realm: "Auth".to_string(),
+                challenge: None,
+            }))
+        );
+    }
+
+    #[tokio::test]
+    async fn should_read_tunnel_details_from_log_history() {
+        let temp_dir = TempDir::new().unwrap();
+        let socket = temp_dir.path().join("mgmt.sock");
+        spawn_fake_server(UnixListener::bind(&socket).unwrap(), &[]);
+
+        let client = ManagementClient::connect(&socket).await.unwrap();
+        let history = client.log_history().await.unwrap();
+
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].opened_device(), Some("tun0"));
+        let options = history[1].push_reply_options().unwrap();
+        assert!(options.contains(&"route-gateway 10.8.0.1"));
+        assert_eq!(options.last(), Some(&"ifconfig 10.8.0.6 255.255.255.0"));
+    }
+
+    #[tokio::test]
+    async fn should_exchange_commands_and_stream_events() {
+        let temp_dir = TempDir::new().unwrap();
+        let socket = temp_dir.path().join("mgmt.sock");
+        spawn_fake_server(
+            UnixListener::bind(&socket).unwrap(),
+            &[">STATE:1700000001,CONNECTED,SUCCESS,10.8.0.6,203.0.113.5,1194,,", ">BYTECOUNT:10,20"],
+        );
+
+        let client = ManagementClient::connect(&socket).await.unwrap();
+        let mut events = client.subscribe();
+        client.enable_state_events().await.unwrap();
+        client.disable_hold().await.unwrap();
+        client.release_hold().await.unwrap();
+
+        let mut received = Vec::new();
+        while received.len() < 2 {
+            let event = tokio::time::timeout(Duration::from_secs(5), events.recv()).await.unwrap().unwrap();
+            if !matches!(event, ManagementEvent::Info(_)) {
+                received.push(event);
+            }
+        }
+        assert!(matches!(&received[0], ManagementEvent::State(state) if state.name == "CONNECTED"));
+        assert_eq!(received[1], ManagementEvent::ByteCount { bytes_in: 10, bytes_out: 20 });
+    }
+
+    #[tokio::test]
+    async fn should_read_multiline_state_and_report_errors() {
+        let temp_dir = TempDir::new().unwrap();
+        let socket = temp_dir.path().join("mgmt.sock");
+        spawn_fake_server(UnixListener::bind(&socket).unwrap(), &[]);
+
+        let client = ManagementClient::connect(&socket).await.unwrap();
+
+        let state = client.current_state().await.unwrap().unwrap();
+        assert_eq!(state.name, "CONNECTED");
+        assert_eq!(state.remote_ip.as_deref(), Some("203.0.113.5"));
+
+        let result = client.signal("SIGTERM").await;
+        assert!(matches!(result, Err(ManagementError::CommandFailed(message)) if message.contains("signal SIGTERM")));
+    }
+
+    #[tokio::test]
+    async fn should_refuse_credentials_with_line_breaks() {
+        let temp_dir = TempDir::new().unwrap();
+        let socket = temp_dir.path().join("mgmt.sock");
+        spawn_fake_server(UnixListener::bind(&socket).unwrap(), &[]);
+
+        let client = ManagementClient::connect(&socket).await.unwrap();
+
+        let result = client.send_username("Auth", "user\nsignal SIGTERM").await;
+        assert!(matches!(result, Err(ManagementError::LineBreak("username"))));
+        let result = client.send_password("Auth", "pass\r\nsignal SIGTERM").await;
+        assert!(matches!(result, Err(ManagementError::LineBreak("password"))));
+        // Nothing reached the socket, so the next command still gets its own reply.
+        client.disable_hold().await.unwrap();
+    }
+}
+
+#[cfg(test)]
+mod traffic_meter_tests {
+    use ui_openvpn_linux::infrastructure::traffic::TrafficMeter;
+    use std::fs;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn write_counters(root: &Path, interface: &str, rx: u64, tx: u64) {
+        let statistics = root.join(interface).join("statistics");
+        fs::create_dir_all(&statistics).unwrap();
+        fs::write(statistics.join("rx_bytes"), format!("{}\n", rx)).unwrap();
+        fs::write(statistics.join("tx_bytes"), format!("{}\n", tx)).unwrap();
+    }
+
+    #[test]
+    fn should_read_interface_counters() {
+        let temp_dir = TempDir::new().unwrap();
+        write_counters(temp_dir.path(), "tun0", 4096, 1024);
+        let meter = TrafficMeter::new(temp_dir.path().to_path_buf());
+
+        let first = meter.sample("tun0").unwrap();
+        assert_eq!((first.bytes_in(), first.bytes_out()), (4096, 1024));
+        assert_eq!(first.rate_in(), None);
+
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        write_counters(temp_dir.path(), "tun0", 8192, 1024);
+        let second = meter.sample("tun0").unwrap();
+        assert!(second.rate_in().unwrap() > 0);
+        assert_eq!(second.rate_out(), Some(0));
+    }
+
+    #[test]
+    fn should_ignore_unknown_or_unsafe_interfaces() {
+        let temp_dir = TempDir::new().unwrap();
+        let meter = TrafficMeter::new(temp_dir.path().to_path_buf());
+
+        assert!(meter.sample("tun9").is_none());
+        assert!(meter.sample("../etc").is_none());
+    }
+}
+
+#[cfg(test)]
+mod profile_bundle_reader_tests {
+    use ui_openvpn_linux::{
+        domain::repositories::VpnRepository,
+        infrastructure::{bundle::read_bundle, repositories::FileVpnRepository},
+    };
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn should_read_referenced_files_and_report_missing_ones() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("provider");
+        fs::create_dir_all(source.join("keys")).unwrap();
+        fs::write(source.join("ca.crt"), "CA\n").unwrap();
+        fs::write(source.join("keys/client.key"), "KEY\n").unwrap();
+        fs::write(
+            source.join("work.ovpn"),
+            "client\nca ca.crt\nkey keys/client.key\ncert missing.crt\ncrl-verify keys dir\n",
+        )
+        .unwrap();
+
+        let bundle = read_bundle(&source.join("work.ovpn")).await.unwrap();
+
+        let read: Vec<(&str, &[u8])> = bundle.files().iter().map(|f| (f.name.as_str(), f.content.as_slice())).collect();
+        assert_eq!(read, vec![("ca.crt", &b"CA\n"[..]), ("client.key", &b"KEY\n"[..])]);
+        let unresolved: Vec<&str> = bundle.unresolved().iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(unresolved, vec!["missing.crt", "keys"]);
+    }
+
+    #[tokio::test]
+    async fn should_keep_stored_files_with_the_profile_across_renames() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("source");
+        fs::create_dir_all(&source).unwrap();
+        fs::write(source.join("ca.crt"), "CA\n").unwrap();
+        fs::write(source.join("work.ovpn"), "client\nca ca.crt\n").unwrap();
+        let config_dir = temp_dir.path().join("config");
+        let repo = FileVpnRepository::new(config_dir.clone());
+
+        let bundle = read_bundle(&source.join("work.ovpn")).await.unwrap();
+        let dir = repo.store_files("work", bundle.files()).await.unwrap();
+        repo.create("work", &bundle.relocated(&dir)).await.unwrap();
+        assert_eq!(repo.list_all().await.unwrap().len(), 1);
+
+        let vpn = repo.rename("work", "office").await.unwrap();
+        let ca = vpn.config().unwrap().directive("ca").unwrap().arg(0).unwrap().to_string();
+        assert_eq!(ca, config_dir.join("office.d/ca.crt").to_string_lossy());
+        assert_eq!(fs::read_to_string(&ca).unwrap(), "CA\n");
+
+        repo.delete("office").await.unwrap();
+        assert!(!config_dir.join("office.d").exists());
+    }
+}
+
+#[cfg(test)]
+mod profile_archive_tests {
+    use ui_openvpn_linux::infrastructure::archive::{write_archive, ProfileArchive};
+    use std::collections::BTreeMap;
+    use std::fs::File;
+    use std::io::Write;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    const PROFILE: &str = "client\nremote vpn.example.com\nca ../ca.crt\ncert client.crt\nkey client.key\n";
+
+    fn write_zip(path: &Path, entries: &[(&str, &str)]) {
+        let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+        for (name, content) in entries {
+            zip.start_file(*name, zip::write::SimpleFileOptions::default()).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn should_list_profiles_and_resolve_shared_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("provider.zip");
+        write_zip(
+            &path,
+            &[
+                ("vpn/ca.crt", "CA\n"),
+                ("vpn/udp/client.crt", "CERT\n"),
+                ("vpn/udp/client.key", "KEY\n"),
+                ("vpn/udp/nl.ovpn", PROFILE),
+                ("vpn/tcp/nl.ovpn", PROFILE),
+                ("vpn/readme.conf", "not a profile\n"),
+                ("__MACOSX/vpn/._nl.ovpn", "junk"),
+                ("../escape.ovpn", PROFILE),
+            ],
+        );
+
+        let archive = ProfileArchive::open(&path).unwrap();
+        assert_eq!(archive.profiles(), vec!["vpn/tcp/nl.ovpn", "vpn/udp/nl.ovpn"]);
+
+        let bundle = archive.bundle("vpn/tcp/nl.ovpn").unwrap();
+        let files: Vec<(&str, &[u8])> = bundle.files().iter().map(|f| (f.reference.as_str(), f.content.as_slice())).collect();
+        // The tcp folder has no client files of its own; the only copies in the archive are used.
+        assert_eq!(files, vec![("../ca.crt", &b"CA\n"[..]), ("client.crt", &b"CERT\n"[..]), ("client.key", &b"KEY\n"[..])]);
+        assert!(bundle.unresolved().is_empty());
+    }
+
+    #[test]
+    fn should_read_tar_gz_bundles() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("provider.tar.gz");
+        let encoder = flate2::write::GzEncoder::new(File::create(&path).unwrap(), flate2::Compression::default());
+        let mut tar = tar::Builder::new(encoder);
+        for (name, content) in [("ca.crt", "CA\n"), ("de.ovpn", "client\nremote de.example.com\nca ca.crt\ntls-auth ta.key 1\n")] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            tar.append_data(&mut header, name, content.as_bytes()).unwrap();
+        }
+        tar.into_inner().unwrap().finish().unwrap();
+
+        let archive = ProfileArchive::open(&path).unwrap();
+        assert_eq!(archive.profiles(), vec!["de.ovpn"]);
+        let bundle = archive.bundle("de.ovpn").unwrap();
+        assert_eq!(bundle.files().len(), 1);
+        assert_eq!(bundle.unresolved()[0].path, "ta.key");
+        assert!(!ProfileArchive::is_archive(Path::new("de.ovpn")));
+    }
+
+    #[test]
+    fn should_write_archives_that_read_back() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let files = BTreeMap::from([
+            ("home.ovpn".to_string(), b"client\nremote home.example.com\n".to_vec()),
+            ("work.ovpn".to_string(), b"client\nremote work.example.com\n".to_vec()),
+        ]);
+
+        for name in ["profiles.zip", "profiles.tar.gz"] {
+            let path = temp_dir.path().join(name);
+            write_archive(&path, &files).unwrap();
+
+            assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+            let archive = ProfileArchive::open(&path).unwrap();
+            assert_eq!(archive.profiles(), vec!["home.ovpn", "work.ovpn"]);
+            assert!(archive.bundle("work.ovpn").unwrap().config().render().contains("work.example.com"));
+        }
+        assert!(write_archive(&temp_dir.path().join("profiles.rar"), &files).is_err());
+    }
+}
+
+#[cfg(test)]
+mod credential_store_tests {
+    use ui_openvpn_linux::domain::{credentials::Credentials, repositories::CredentialStore};
+    use ui_openvpn_linux::infrastructure::credentials::EncryptedCredentialStore;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::TempDir;
+
+    fn store(temp_dir: &TempDir) -> EncryptedCredentialStore {
+        EncryptedCredentialStore::new(
+            temp_dir.path().join("profiles/credentials.enc"),
+            temp_dir.path().join("keys/credentials.key"),
+        )
+    }
+
+    #[tokio::test]
+    async fn should_keep_credentials_encrypted_on_disk() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::create_dir(temp_dir.path().join("profiles")).unwrap();
+        let store = store(&temp_dir);
+        assert_eq!(store.load("work").await.unwrap(), None);
+
+        store.save("work", &Credentials::new("alice", "hunter2")).await.unwrap();
+        store.save("home", &Credentials::new("bob", "swordfish")).await.unwrap();
+
+        let raw = std::fs::read(store.path()).unwrap();
+        assert!(!raw.windows(7).any(|window| window == b"hunter2"));
+        for path in [store.path().to_path_buf(), temp_dir.path().join("keys/credentials.key")] {
+            assert_eq!(std::fs::metadata(path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+
+        store.rename("work", "office").await.unwrap();
+        assert_eq!(store.load("work").await.unwrap(), None);
+        assert_eq!(store.load("office").await.unwrap(), Some(Credentials::new("alice", "hunter2")));
+
+        assert!(store.remove("office").await.unwrap());
+        assert!(!store.remove("office").await.unwrap());
+        assert_eq!(store.load("home").await.unwrap().unwrap().username(), "bob");
+    }
+
+    #[tokio::test]
+    async fn should_refuse_a_store_written_with_another_key() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::create_dir(temp_dir.path().join("profiles")).unwrap();
+        let store = store(&temp_dir);
+        store.save("work", &Credentials::new("alice", "hunter2")).await.unwrap();
+
+        std::fs::remove_file(temp_dir.path().join("keys/credentials.key")).unwrap();
+        std::fs::write(temp_dir.path().join("keys/credentials.key"), [7u8; 32]).unwrap();
+
+        let error = store.load("work").await.unwrap_err().to_string();
+        assert!(error.contains("cannot be decrypted"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn should_keep_named_secrets_apart_from_the_password() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::create_dir(temp_dir.path().join("profiles")).unwrap();
+        let store = store(&temp_dir);
+        store.save("work", &Credentials::new("alice", "hunter2")).await.unwrap();
+        store.save_secret("work", "totp", "GEZDGNBV").await.unwrap();
+
+        assert!(store.remove("work").await.unwrap());
+        assert_eq!(store.load("work").await.unwrap(), None);
+        assert_eq!(store.load_secret("work", "totp").await.unwrap().as_deref(), Some("GEZDGNBV"));
+
+        store.rename("work", "office").await.unwrap();
+        assert_eq!(store.load_secret("office", "totp").await.unwrap().as_deref(), Some("GEZDGNBV"));
+        assert!(store.forget("office").await.unwrap());
+        assert!(!store.forget("office").await.unwrap());
+        assert!(!store.remove_secret("office", "totp").await.unwrap());
+    }
+}
+
+#[cfg(test)]
+mod connection_log_tests {
+    use ui_openvpn_linux::infrastructure::logs::{ConnectionLog, LogLine, LogStream};
+    use std::time::{Duration, UNIX_EPOCH};
+    use tempfile::TempDir;
+
+    fn line_at(secs: u64, stream: LogStream, text: &str) -> LogLine {
+        LogLine { time: UNIX_EPOCH + Duration::from_secs(secs), stream, text: text.to_string() }
+    }
+
+    #[test]
+    fn should_write_lines_that_read_back() {
+        let dir = TempDir::new().unwrap();
+        let log = ConnectionLog::new(dir.path().join("logs"));
+        log.append("work", line_at(1_700_000_000, LogStream::Stdout, "Initialization Sequence Completed")).unwrap();
+        log.append("work", line_at(1_700_000_060, LogStream::Stderr, "AUTH: Received control message")).unwrap();
+        log.append("work", line_at(1_700_000_061, LogStream::Note, "Ignoring saved credentials: bad key")).unwrap();
+
+        let written = std::fs::read_to_string(log.path("work")).unwrap();
+        assert_eq!(
+            written,
+            "2023-11-14T22:13:20Z Initialization Sequence Completed\n\
+             2023-11-14T22:14:20Z [stderr] AUTH: Received control message\n\
+             2023-11-14T22:14:21Z [ui-openvpn] Ignoring saved credentials: bad key\n"
+        );
+        assert_eq!(log.read("work", None).unwrap().len(), 3);
+
+        let since = UNIX_EPOCH + Duration::from_secs(1_700_000_030);
+        let recent = log.read("work", Some(since)).unwrap();
+        assert_eq!(
+            recent,
+            vec![
+                line_at(1_700_000_060, LogStream::Stderr, "AUTH: Received control message"),
+                line_at(1_700_000_061, LogStream::Note, "Ignoring saved credentials: bad key"),
+            ]
+        );
+        assert!(log.read("other", None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn should_rotate_by_size_and_keep_reading_in_order() {
+        let dir = TempDir::new().unwrap();
+        let log = ConnectionLog::new(dir.path().to_path_buf()).with_max_bytes(100);
+        for i in 0..10 {
+            log.append("work", line_at(1_700_000_000 + i, LogStream::Stdout, &format!("line {:02} of output", i))).unwrap();
+        }
+
+        assert!(dir.path().join("work.log.1").exists());
+        assert!(dir.path().join("work.log.3").exists());
+        assert!(!dir.path().join("work.log.4").exists());
+        let texts: Vec<String> = log.read("work", None).unwrap().into_iter().map(|line| line.text).collect();
+        // The oldest lines fell off with the fourth rotated file.
+        assert_eq!(texts.last().unwrap(), "line 09 of output");
+        assert!(texts.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(texts.len() < 10);
+    }
+
+    #[test]
+    fn should_follow_new_lines_across_rotations() {
+        let dir = TempDir::new().unwrap();
+        let log = ConnectionLog::new(dir.path().to_path_buf()).with_max_bytes(120);
+        log.append("work", line_at(1_700_000_000, LogStream::Stdout, "before following")).unwrap();
+
+        let mut tail = log.tail("work");
+        assert!(tail.read_new().unwrap().is_empty());
+        log.append("work", line_at(1_700_000_001, LogStream::Stdout, "first new line")).unwrap();
+        assert_eq!(tail.read_new().unwrap()[0].text, "first new line");
+
+        // Pushes the file over the limit, so the next line starts a new file.
+        log.append("work", line_at(1_700_000_002, LogStream::Stdout, &"x".repeat(60))).unwrap();
+        log.append("work", line_at(1_700_000_003, LogStream::Stdout, "after rotation")).unwrap();
+        let texts: Vec<String> = tail.read_new().unwrap().into_iter().map(|line| line.text).collect();
+        assert_eq!(texts.last().unwrap(), "after rotation");
+    }
+
+    #[test]
+    fn should_keep_recent_lines_per_session() {
+        let dir = TempDir::new().unwrap();
+        let log = ConnectionLog::new(dir.path().to_path_buf());
+        log.append("work", LogLine::new(LogStream::Stdout, "old session")).unwrap();
+        log.begin("work");
+        for text in ["one", "two", "three"] {
+            log.append("work", LogLine::new(LogStream::Stderr, text)).unwrap();
+        }
+
+        let texts: Vec<String> = log.recent("work", 2).into_iter().map(|line| line.text).collect();
+        assert_eq!(texts, vec!["two", "three"]);
+        assert_eq!(log.recent("work", 10).len(), 3);
+        assert!(log.recent("home", 10).is_empty());
+    }
+}
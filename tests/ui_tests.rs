@@ -0,0 +1,193 @@
+#[cfg(test)]
+mod askpass_prompt_tests {
+    use ui_openvpn_linux::domain::credentials::{CredentialKind, CredentialPrompt, CredentialRequest, KEY_REALM};
+    use ui_openvpn_linux::ui::askpass::AskpassPrompt;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn helper(temp_dir: &TempDir, script: &str) -> PathBuf {
+        let path = temp_dir.path().join("askpass");
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    fn key_request() -> CredentialRequest {
+        CredentialRequest {
+            vpn_id: "work".to_string(),
+            vpn_name: "Work".to_string(),
+            kind: CredentialKind::Password { realm: KEY_REALM.to_string() },
+            username: None,
+            retry: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn should_read_the_answer_from_the_helper() {
+        let temp_dir = TempDir::new().unwrap();
+        // Echoes the prompt back so the test can see what the helper was asked.
+        let prompt = AskpassPrompt::new(helper(&temp_dir, r#"printf '%s\n' "$1""#));
+
+        let reply = prompt.ask(&key_request()).await.unwrap();
+        assert_eq!(reply.credentials.password(), "'Private Key' password for Work:");
+        assert_eq!(reply.credentials.username(), "");
+        assert!(!reply.remember);
+    }
+
+    #[tokio::test]
+    async fn should_cancel_when_the_helper_fails() {
+        let temp_dir = TempDir::new().unwrap();
+        let prompt = AskpassPrompt::new(helper(&temp_dir, "exit 1"));
+        assert_eq!(prompt.ask(&key_request()).await, None);
+
+        let missing = AskpassPrompt::new(temp_dir.path().join("missing"));
+        assert_eq!(missing.ask(&key_request()).await, None);
+    }
+}
+
+#[cfg(test)]
+mod format_tests {
+    use ui_openvpn_linux::domain::{entities::VpnStatus, state_machine::TunnelState};
+    use ui_openvpn_linux::ui::format::format_phase;
+
+    #[test]
+    fn should_describe_reconnects_and_giving_up() {
+        let mut status = VpnStatus::from_tunnel_state(TunnelState::Connected, String::new());
+        assert_eq!(format_phase(&status), None);
+
+        status.set_tunnel_state(TunnelState::Reconnecting);
+        status.set_reconnect_attempt(Some(2));
+        assert_eq!(format_phase(&status).as_deref(), Some("reconnecting (attempt 2)"));
+        status.set_tunnel_state(TunnelState::Auth);
+        assert_eq!(format_phase(&status).as_deref(), Some("reconnecting (attempt 2, auth)"));
+
+        status.set_tunnel_state(TunnelState::GaveUp("tls-error".to_string()));
+        assert_eq!(format_phase(&status).as_deref(), Some("gave up after 2 attempts: tls-error"));
+
+        status.set_tunnel_state(TunnelState::Connected);
+        assert_eq!(status.reconnect_attempt(), None);
+    }
+}
+
+#[cfg(test)]
+mod daemon_tests {
+    use ui_openvpn_linux::application::services::{VpnApplicationService, VpnControl};
+    use ui_openvpn_linux::infrastructure::{repositories::FileVpnRepository, services::OpenVpnService};
+    use ui_openvpn_linux::ui::daemon::{ClientPrompt, DaemonClient, DaemonServer};
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::UnixStream;
+
+    async fn start(temp_dir: &TempDir) -> (Arc<DaemonServer>, PathBuf) {
+        let profiles = temp_dir.path().join("profiles");
+        std::fs::create_dir(&profiles).unwrap();
+        std::fs::write(profiles.join("work.ovpn"), "client\nremote vpn.example.com 1194\n").unwrap();
+
+        let service = Arc::new(VpnApplicationService::new(
+            Arc::new(FileVpnRepository::new(profiles)),
+            Arc::new(OpenVpnService::new()),
+        ));
+        let socket = temp_dir.path().join("run").join("daemon.rpc.sock");
+        let server = DaemonServer::bind(&socket, service, Arc::new(ClientPrompt::new(None))).await.unwrap();
+        let server = Arc::new(server);
+        let serving = Arc::clone(&server);
+        tokio::spawn(async move { serving.serve().await });
+        (server, socket)
+    }
+
+    async fn exchange(socket: &PathBuf, line: &str) -> serde_json::Value {
+        let mut stream = UnixStream::connect(socket).await.unwrap();
+        stream.write_all(format!("{}\n", line).as_bytes()).await.unwrap();
+        let mut reply = String::new();
+        BufReader::new(stream).read_line(&mut reply).await.unwrap();
+        serde_json::from_str(&reply).unwrap()
+    }
+
+    #[tokio::test]
+    async fn should_list_profiles_through_the_daemon() {
+        let temp_dir = TempDir::new().unwrap();
+        let (_server, socket) = start(&temp_dir).await;
+
+        let client = DaemonClient::if_running(socket).await.expect("daemon is listening");
+        let vpns = client.list_vpns().await.unwrap();
+        assert_eq!(vpns.len(), 1);
+        assert_eq!(vpns[0].id(), "work");
+        assert!(!vpns[0].is_connected());
+
+        let error = client.disconnect_vpn("missing").await.unwrap_err();
+        assert_eq!(error.to_string(), "VPN not found: missing");
+    }
+
+    #[tokio::test]
+    async fn should_keep_exit_codes_through_the_daemon() {
+        let temp_dir = TempDir::new().unwrap();
+        let (_server, socket) = start(&temp_dir).await;
+
+        let reply = exchange(&socket, r#"{"jsonrpc":"2.0","id":1,"method":"connect","params":{"vpn_id":"missing"}}"#).await;
+        assert_eq!(reply["result"]["error"]["kind"], "not_found");
+        assert_eq!(reply["result"]["error"]["detail"], "missing");
+
+        let status = tokio::process::Command::new(env!("CARGO_BIN_EXE_ui-openvpn"))
+            .arg("--socket")
+            .arg(&socket)
+            .args(["connect", "missing"])
+            .env("HOME", temp_dir.path())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status()
+            .await
+            .unwrap();
+        assert_eq!(status.code(), Some(3));
+    }
+
+    #[tokio::test]
+    async fn should_answer_with_json_rpc_errors() {
+        let temp_dir = TempDir::new().unwrap();
+        let (_server, socket) = start(&temp_dir).await;
+
+        let reply = exchange(&socket, r#"{"jsonrpc":"2.0","id":7,"method":"reboot"}"#).await;
+        assert_eq!(reply["id"], 7);
+        assert_eq!(reply["error"]["code"], -32601);
+
+        let reply = exchange(&socket, r#"{"jsonrpc":"2.0","id":8,"method":"connect","params":{}}"#).await;
+        assert_eq!(reply["error"]["code"], -32602);
+
+        let reply = exchange(&socket, "not json").await;
+        assert_eq!(reply["error"]["code"], -32700);
+        assert!(reply["id"].is_null());
+    }
+
+    #[tokio::test]
+    async fn should_refuse_a_second_daemon() {
+        let temp_dir = TempDir::new().unwrap();
+        let (_server, socket) = start(&temp_dir).await;
+        let service = Arc::new(VpnApplicationService::new(
+            Arc::new(FileVpnRepository::new(temp_dir.path().to_path_buf())),
+            Arc::new(OpenVpnService::new()),
+        ));
+
+        let second = DaemonServer::bind(&socket, Arc::clone(&service), Arc::new(ClientPrompt::new(None))).await;
+        assert_eq!(second.err().unwrap().kind(), std::io::ErrorKind::AddrInUse);
+        assert!(DaemonClient::if_running(socket).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn should_replace_a_stale_socket() {
+        let temp_dir = TempDir::new().unwrap();
+        let socket = temp_dir.path().join("daemon.rpc.sock");
+        drop(std::os::unix::net::UnixListener::bind(&socket).unwrap());
+        assert!(socket.exists() && DaemonClient::if_running(socket.clone()).await.is_none());
+
+        let service = Arc::new(VpnApplicationService::new(
+            Arc::new(FileVpnRepository::new(temp_dir.path().to_path_buf())),
+            Arc::new(OpenVpnService::new()),
+        ));
+        let server = DaemonServer::bind(&socket, service, Arc::new(ClientPrompt::new(None))).await.unwrap();
+        assert!(DaemonClient::if_running(socket.clone()).await.is_some());
+        drop(server);
+        assert!(!socket.exists());
+    }
+}